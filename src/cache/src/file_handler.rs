@@ -3,6 +3,7 @@ use std::sync::RwLock;
 use std::error;
 use std::time::Instant;
 use once_cell::sync::Lazy;
+use config::server_config::ServerConfig;
 use log::{debug, error, info};
 use rs2cache::Cache;
 use rs2cache::js5_compression::Js5Compression;
@@ -12,6 +13,7 @@ use rs2cache::store::ARCHIVESET;
 
 struct CacheData {
     preloaded_data: HashMap<(u8, u16), Vec<u8>>,
+    group_names: HashMap<(u8, i32), u16>,
     master_index: Option<Vec<u8>>,
     checksums: Vec<u32>,
     cache_path: String
@@ -20,9 +22,10 @@ struct CacheData {
 static GLOBAL_CACHE_DATA: Lazy<RwLock<CacheData>> = Lazy::new(|| {
     RwLock::new(CacheData {
         preloaded_data: HashMap::with_capacity(67551),
+        group_names: HashMap::new(),
         master_index: None,
         checksums: Vec::new(),
-        cache_path: "../../src/cacheLocal".to_string()
+        cache_path: ServerConfig::get().paths.cache.to_string_lossy().into_owned()
     })
 });
 
@@ -36,9 +39,9 @@ static INIT: Lazy<()> = Lazy::new(|| {
 
 fn initialize_cache() -> Result<(), Box<dyn error::Error>> {
     let start = Instant::now();
-    let cache_path = "../../src/cacheLocal";
+    let cache_path = ServerConfig::get().paths.cache.to_string_lossy().into_owned();
 
-    let cache = match Cache::open(cache_path) {
+    let cache = match Cache::open(&cache_path) {
         Ok(cache) => cache,
         Err(e) => {
             return Err(format!("Failed to open cache: {}", e).into());
@@ -51,6 +54,7 @@ fn initialize_cache() -> Result<(), Box<dyn error::Error>> {
     let mut checksums: Vec<u32> = Vec::with_capacity(master_index.entries.len());
 
     let mut preloaded_data = HashMap::with_capacity(67553);
+    let mut group_names = HashMap::new();
     let mut total_entries = 0;
     let mut successful_loads = 0;
     let mut failed_loads = 0;
@@ -60,7 +64,8 @@ fn initialize_cache() -> Result<(), Box<dyn error::Error>> {
         let js5_index_compressed = cache.store.read(255, archive_id as u32).unwrap();
         let js5_index_decompressed = Js5Compression::uncompress(js5_index_compressed, None)?;
         let js5_index = Js5Index::read(js5_index_decompressed).unwrap();
-        for (group_id, _) in js5_index.groups.iter() {
+        for (group_id, group) in js5_index.groups.iter() {
+            group_names.insert((archive_id as u8, group.name_hash), *group_id as u16);
             match cache.store.read(archive_id as u8, *group_id) {
                 Ok(data) => {
                     preloaded_data.insert((archive_id as u8, *group_id as u16), data);
@@ -105,9 +110,10 @@ fn initialize_cache() -> Result<(), Box<dyn error::Error>> {
 
     let mut global_data = GLOBAL_CACHE_DATA.write().unwrap();
     global_data.preloaded_data = preloaded_data;
+    global_data.group_names = group_names;
     global_data.master_index = Some(master_index_data);
     global_data.checksums = checksums;
-    global_data.cache_path = cache_path.to_string();
+    global_data.cache_path = cache_path;
 
     Ok(())
 }
@@ -145,6 +151,18 @@ pub fn get_data(archive: u8, group: u16) -> Result<Vec<u8>, Box<dyn error::Error
     Ok(data)
 }
 
+/// Hash of a group or file name as stored in the js5 index.
+pub fn name_hash(name: &str) -> i32 {
+    name.to_lowercase().bytes().fold(0i32, |hash, c| hash.wrapping_mul(31).wrapping_add(c as i32))
+}
+
+/// Look up a group id by its name, e.g. the `m50_50` map square in the maps archive.
+pub fn get_group_id(archive: u8, name: &str) -> Option<u16> {
+    ensure_initialized().ok()?;
+    let data_cache = GLOBAL_CACHE_DATA.read().unwrap();
+    data_cache.group_names.get(&(archive, name_hash(name))).copied()
+}
+
 pub fn get_master_index() -> Result<Vec<u8>, Box<dyn error::Error>> {
     ensure_initialized()?;
     let data_cache = GLOBAL_CACHE_DATA.read().unwrap();
@@ -5,6 +5,7 @@ use std::sync::OnceLock;
 use std::time::Instant;
 use std::error;
 use log::{error, info};
+use config::server_config::ServerConfig;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
@@ -40,7 +41,7 @@ pub fn initialize_xtea() -> Result<bool, Box<dyn error::Error>> {
     let start = Instant::now();
     info!("Initializing XTEA module.");
     
-    let mut file = File::open("../../src/cacheLocal/xteaKeys.json")?;
+    let mut file = File::open(ServerConfig::get().paths.cache.join("xteaKeys.json"))?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
     
@@ -0,0 +1,3 @@
+pub mod server_config;
+#[cfg(test)]
+mod server_config_tests;
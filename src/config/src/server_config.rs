@@ -0,0 +1,238 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+static CONFIG: OnceLock<ServerConfig> = OnceLock::new();
+
+/// Settings shared by the engine, js5, proxy and worldlist servers.
+///
+/// Read from a TOML file, `../config.toml` unless `RS2_CONFIG` points somewhere else. Any key can be
+/// overridden with a `RS2_<SECTION>_<KEY>` environment variable, e.g. `RS2_NETWORK_GAME_ADDR`.
+/// Whatever is left unset keeps its default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub world: WorldConfig,
+    pub network: NetworkConfig,
+    pub paths: PathConfig,
+    pub compiler: CompilerConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorldConfig {
+    pub members: bool,
+    pub max_players: usize,
+    pub tick_rate_ms: u64,
+    /// Address the world list tells clients to connect to.
+    pub host: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    pub game_addr: String,
+    pub proxy_addr: String,
+    pub js5_addr: String,
+    pub worldlist_addr: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathConfig {
+    pub cache: PathBuf,
+    /// Scripts, packs, accounts and player saves.
+    pub data: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompilerConfig {
+    /// Only ever use the vendored jar, never fetching releases from GitHub.
+    pub offline: bool,
+    pub jar: PathBuf,
+    /// Expected SHA-256 of the jar in hex, required when offline.
+    pub sha256: String,
+    /// Compiler project file, the compiler runs from the directory it is in.
+    pub neptune: PathBuf,
+    pub java: String,
+    /// Compile scripts on startup before they are loaded.
+    pub compile_on_start: bool,
+}
+
+/// Why the configuration could not be loaded.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Env(String, String),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "failed to read {}: {}", path.display(), err),
+            ConfigError::Parse(path, err) => write!(f, "failed to parse {}: {}", path.display(), err),
+            ConfigError::Env(name, value) => write!(f, "invalid value {:?} for {}", value, name),
+            ConfigError::Invalid(reason) => write!(f, "invalid configuration: {}", reason),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl Default for WorldConfig {
+    fn default() -> WorldConfig {
+        WorldConfig {
+            members: false,
+            max_players: 2048,
+            tick_rate_ms: 600,
+            host: "localhost".to_string(),
+        }
+    }
+}
+
+impl Default for NetworkConfig {
+    fn default() -> NetworkConfig {
+        NetworkConfig {
+            game_addr: "127.0.0.1:40001".to_string(),
+            proxy_addr: "127.0.0.1:40000".to_string(),
+            js5_addr: "127.0.0.1:43595".to_string(),
+            worldlist_addr: "127.0.0.1:43596".to_string(),
+        }
+    }
+}
+
+impl Default for PathConfig {
+    fn default() -> PathConfig {
+        PathConfig {
+            cache: PathBuf::from("../../src/cacheLocal"),
+            data: PathBuf::from("./data"),
+        }
+    }
+}
+
+impl Default for CompilerConfig {
+    fn default() -> CompilerConfig {
+        CompilerConfig {
+            offline: false,
+            jar: PathBuf::from("./RuneScriptCompiler.jar"),
+            sha256: String::new(),
+            neptune: PathBuf::from("./neptune.toml"),
+            java: "java".to_string(),
+            compile_on_start: false,
+        }
+    }
+}
+
+impl WorldConfig {
+    #[inline]
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.tick_rate_ms)
+    }
+}
+
+impl ServerConfig {
+    pub const PATH_VAR: &'static str = "RS2_CONFIG";
+    pub const DEFAULT_PATH: &'static str = "../config.toml";
+    const ENV_PREFIX: &'static str = "RS2";
+
+    /// Load the configuration at startup, every [ServerConfig::get] after this sees the same values.
+    pub fn init() -> Result<&'static ServerConfig, ConfigError> {
+        if let Some(config) = CONFIG.get() {
+            return Ok(config);
+        }
+
+        let path = std::env::var_os(Self::PATH_VAR).map_or_else(|| PathBuf::from(Self::DEFAULT_PATH), PathBuf::from);
+        let config = Self::load(&path, |name| std::env::var(name).ok())?;
+        Ok(CONFIG.get_or_init(|| config))
+    }
+
+    /// The loaded configuration, loading it first if nothing called [ServerConfig::init].
+    pub fn get() -> &'static ServerConfig {
+        match Self::init() {
+            Ok(config) => config,
+            Err(e) => panic!("Failed to load configuration: {}", e),
+        }
+    }
+
+    /// Defaults, then the file at `path` if there is one, then environment overrides looked up through `env`.
+    pub fn load(path: &Path, env: impl Fn(&str) -> Option<String>) -> Result<ServerConfig, ConfigError> {
+        let mut table = Table::try_from(ServerConfig::default()).map_err(|e| ConfigError::Invalid(e.to_string()))?;
+
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                let file: Table = toml::from_str(&contents).map_err(|e| ConfigError::Parse(path.to_path_buf(), e))?;
+                Self::merge(&mut table, file);
+            },
+            Err(err) if err.kind() == ErrorKind::NotFound => {},
+            Err(err) => return Err(ConfigError::Io(path.to_path_buf(), err)),
+        }
+
+        Self::apply_env(&mut table, env)?;
+
+        let config: ServerConfig = table.try_into().map_err(|e: toml::de::Error| ConfigError::Invalid(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn merge(table: &mut Table, file: Table) {
+        for (key, value) in file {
+            match (table.get_mut(&key), value) {
+                (Some(Value::Table(section)), Value::Table(values)) => Self::merge(section, values),
+                (_, value) => {
+                    // Unknown keys are kept so deserializing reports them rather than silently ignoring a typo.
+                    table.insert(key, value);
+                },
+            }
+        }
+    }
+
+    fn apply_env(table: &mut Table, env: impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        for (section, values) in table.iter_mut() {
+            let Value::Table(values) = values else {
+                continue;
+            };
+
+            for (key, value) in values.iter_mut() {
+                let name = format!("{}_{}_{}", Self::ENV_PREFIX, section, key).to_uppercase();
+                let Some(raw) = env(&name) else {
+                    continue;
+                };
+
+                // Parsed as whatever type the default has.
+                *value = match value {
+                    Value::String(_) => Value::String(raw),
+                    Value::Integer(_) => Value::Integer(raw.trim().parse().map_err(|_| ConfigError::Env(name, raw))?),
+                    Value::Boolean(_) => Value::Boolean(raw.trim().parse().map_err(|_| ConfigError::Env(name, raw))?),
+                    _ => return Err(ConfigError::Env(name, raw)),
+                };
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        // Player info sends pids in 11 bits.
+        if self.world.max_players == 0 || self.world.max_players > 2048 {
+            return Err(ConfigError::Invalid(format!("world.max_players must be 1 to 2048, got {}", self.world.max_players)));
+        }
+        if self.world.tick_rate_ms == 0 {
+            return Err(ConfigError::Invalid("world.tick_rate_ms must be above 0".to_string()));
+        }
+        if self.compiler.offline && self.compiler.sha256.is_empty() {
+            return Err(ConfigError::Invalid("compiler.sha256 must be set to run the compiler offline".to_string()));
+        }
+        if !self.compiler.sha256.is_empty() && (self.compiler.sha256.len() != 64 || !self.compiler.sha256.chars().all(|c| c.is_ascii_hexdigit())) {
+            return Err(ConfigError::Invalid("compiler.sha256 must be 64 hex characters".to_string()));
+        }
+        Ok(())
+    }
+}
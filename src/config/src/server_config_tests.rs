@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::server_config::{ConfigError, ServerConfig};
+
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+fn load(contents: Option<&str>, env: &[(&str, &str)]) -> Result<ServerConfig, ConfigError> {
+    let path = std::env::temp_dir().join(format!("server_config_test_{}_{}.toml", std::process::id(), NEXT_FILE.fetch_add(1, Ordering::Relaxed)));
+    if let Some(contents) = contents {
+        std::fs::write(&path, contents).unwrap();
+    }
+
+    let env: HashMap<String, String> = env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    let result = ServerConfig::load(&path, |name| env.get(name).cloned());
+
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+#[test]
+fn test_defaults_without_file() {
+    let config = ServerConfig::load(Path::new("/nonexistent/config.toml"), |_| None).unwrap();
+    assert_eq!(config, ServerConfig::default());
+}
+
+#[test]
+fn test_file_overrides_defaults() {
+    let config = load(Some("[world]\nmax_players = 100\n\n[paths]\ndata = \"./staging\"\n"), &[]).unwrap();
+    assert_eq!(config.world.max_players, 100);
+    assert_eq!(config.paths.data, PathBuf::from("./staging"));
+    assert_eq!(config.world.tick_rate_ms, 600);
+    assert_eq!(config.network.game_addr, "127.0.0.1:40001");
+}
+
+#[test]
+fn test_env_overrides_file() {
+    let config = load(
+        Some("[network]\ngame_addr = \"0.0.0.0:40001\"\n"),
+        &[("RS2_NETWORK_GAME_ADDR", "0.0.0.0:50001"), ("RS2_WORLD_MEMBERS", "true"), ("RS2_WORLD_TICK_RATE_MS", "300")],
+    ).unwrap();
+    assert_eq!(config.network.game_addr, "0.0.0.0:50001");
+    assert!(config.world.members);
+    assert_eq!(config.world.tick_rate_ms, 300);
+}
+
+#[test]
+fn test_rejects_bad_values() {
+    assert!(matches!(load(None, &[("RS2_WORLD_MAX_PLAYERS", "lots")]), Err(ConfigError::Env(..))));
+    assert!(matches!(load(None, &[("RS2_WORLD_MAX_PLAYERS", "4096")]), Err(ConfigError::Invalid(_))));
+    assert!(matches!(load(Some("[world]\nmax_player = 10\n"), &[]), Err(ConfigError::Invalid(_))));
+    assert!(matches!(load(Some("[world"), &[]), Err(ConfigError::Parse(..))));
+    assert!(matches!(load(None, &[("RS2_COMPILER_OFFLINE", "true")]), Err(ConfigError::Invalid(_))));
+}
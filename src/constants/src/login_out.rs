@@ -1,7 +1,13 @@
 pub mod login_out {
     pub const OK: i32 = 2;
+    pub const INVALID_CREDENTIALS: i32 = 3;
+    pub const ACCOUNT_LOCKED: i32 = 4;
+    pub const ALREADY_LOGGED_IN: i32 = 5;
     pub const CLIENT_OUT_OF_DATE: i32 = 6;
     pub const WORLD_FULL: i32 = 7;
+    pub const UPDATE_IN_PROGRESS: i32 = 14;
     pub const RECONNECT_OK: i32 = 15;
+    pub const TOO_MANY_ATTEMPTS: i32 = 16;
     pub const INVALID_LOGIN_PACKET: i32 = 22;
+    pub const ERROR_LOADING_PROFILE: i32 = 24;
 }
\ No newline at end of file
@@ -1,6 +0,0 @@
-pub mod server_addresses {
-    pub const PROXY_ADDR: &str = "127.0.0.1:40000";
-    pub const JAGGRAB_ADDR: &str = "127.0.0.1:443";
-    pub const JS5_ADDR: &str = "127.0.0.1:43595";
-    pub const WORLDLIST_ADDR: &str = "127.0.0.1:43596";
-}
\ No newline at end of file
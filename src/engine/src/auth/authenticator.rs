@@ -0,0 +1,33 @@
+use std::error::Error;
+use constants::login_out::login_out;
+
+/// Outcome of checking a login against an account backend.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum AuthResponse {
+    Ok,
+    InvalidCredentials,
+    Locked,
+    /// Only given out by [crate::auth::login_throttle::LoginThrottle], never by a backend.
+    TooManyAttempts,
+}
+
+impl AuthResponse {
+    /// The response code the client shows for this outcome.
+    pub fn login_out(&self) -> i32 {
+        match self {
+            AuthResponse::Ok => login_out::OK,
+            AuthResponse::InvalidCredentials => login_out::INVALID_CREDENTIALS,
+            AuthResponse::Locked => login_out::ACCOUNT_LOCKED,
+            AuthResponse::TooManyAttempts => login_out::TOO_MANY_ATTEMPTS,
+        }
+    }
+}
+
+/// Where accounts live. Implementations are shared between connection threads.
+pub trait Authenticator: Send + Sync {
+    fn authenticate(&self, username: &str, password: &str) -> AuthResponse;
+
+    fn create_account(&self, username: &str, password: &str) -> Result<(), Box<dyn Error>>;
+
+    fn set_locked(&self, username: &str, locked: bool) -> Result<(), Box<dyn Error>>;
+}
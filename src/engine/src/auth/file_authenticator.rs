@@ -0,0 +1,233 @@
+use std::error::Error;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use log::error;
+use config::server_config::ServerConfig;
+use sha2::{Digest, Sha256};
+use crate::auth::authenticator::{AuthResponse, Authenticator};
+
+struct Account {
+    username: String,
+    salt: Vec<u8>,
+    hash: String,
+    locked: bool,
+}
+
+/// Held while the accounts file is read and rewritten, so two writers never lose each other's changes.
+///
+/// A lock file rather than an OS lock so it's shared by the server and the `create_account` tool on every platform.
+struct AccountsLock {
+    path: PathBuf,
+}
+
+impl AccountsLock {
+    const ATTEMPTS: u32 = 50;
+    const RETRY: Duration = Duration::from_millis(100);
+
+    fn acquire(path: PathBuf) -> Result<AccountsLock, Box<dyn Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        for _ in 0..Self::ATTEMPTS {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(AccountsLock { path }),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => thread::sleep(Self::RETRY),
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Err(format!("timed out waiting on {}, remove it if nothing is writing accounts", path.display()).into())
+    }
+}
+
+impl Drop for AccountsLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Accounts kept in a local text file, one `username salt hash locked` line each.
+///
+/// The file is read again for every login so accounts created while the server is up work straight away.
+/// Changes are written to a temporary file and renamed over it, so a login never reads half a file.
+pub struct FileAuthenticator {
+    path: PathBuf,
+}
+
+impl FileAuthenticator {
+    const SALT_LENGTH: usize = 16;
+
+    pub fn new(path: impl Into<PathBuf>) -> FileAuthenticator {
+        FileAuthenticator { path: path.into() }
+    }
+
+    /// `accounts.txt` in the configured data directory.
+    pub fn default_path() -> PathBuf {
+        ServerConfig::get().paths.data.join("accounts.txt")
+    }
+
+    /// Usernames have to fit in a base37 name, the same as the client allows.
+    pub fn validate_username(username: &str) -> Result<(), Box<dyn Error>> {
+        if username.is_empty() || username.len() > 12 {
+            return Err("username must be 1 to 12 characters".into());
+        }
+        if !username.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            return Err("username may only contain a-z, 0-9 and _".into());
+        }
+        Ok(())
+    }
+
+    pub fn validate_password(password: &str) -> Result<(), Box<dyn Error>> {
+        if password.len() < 5 || password.len() > 20 {
+            return Err("password must be 5 to 20 characters".into());
+        }
+        Ok(())
+    }
+
+    fn hash(salt: &[u8], password: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(salt);
+        hasher.update(password.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Compare without stopping at the first difference, so timing doesn't give away how much matched.
+    fn hashes_match(a: &str, b: &str) -> bool {
+        a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+    }
+
+    fn lock(&self) -> Result<AccountsLock, Box<dyn Error>> {
+        AccountsLock::acquire(self.path.with_extension("lock"))
+    }
+
+    fn read(&self) -> Result<Vec<Account>, Box<dyn Error>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut accounts = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let account = match parts.as_slice() {
+                [username, salt, hash, locked] => decode_hex(salt).map(|salt| Account {
+                    username: username.to_string(),
+                    salt,
+                    hash: hash.to_string(),
+                    locked: *locked == "1",
+                }),
+                _ => None,
+            };
+
+            match account {
+                Some(account) => accounts.push(account),
+                None => return Err(format!("{}:{}: malformed account", self.path.display(), index + 1).into()),
+            }
+        }
+
+        Ok(accounts)
+    }
+
+    fn write(&self, accounts: &[Account]) -> Result<(), Box<dyn Error>> {
+        let mut contents = String::new();
+        for account in accounts {
+            contents.push_str(&format!(
+                "{} {} {} {}\n",
+                account.username,
+                encode_hex(&account.salt),
+                account.hash,
+                account.locked as u8
+            ));
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let temp = self.path.with_extension("tmp");
+        fs::write(&temp, contents)?;
+        fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+}
+
+impl Authenticator for FileAuthenticator {
+    fn authenticate(&self, username: &str, password: &str) -> AuthResponse {
+        let accounts = match self.read() {
+            Ok(accounts) => accounts,
+            Err(e) => {
+                error!("Failed to read accounts: {}", e);
+                return AuthResponse::InvalidCredentials;
+            }
+        };
+
+        match accounts.iter().find(|account| account.username == username) {
+            Some(account) if Self::hashes_match(&Self::hash(&account.salt, password), &account.hash) => {
+                if account.locked {
+                    AuthResponse::Locked
+                } else {
+                    AuthResponse::Ok
+                }
+            },
+            _ => AuthResponse::InvalidCredentials,
+        }
+    }
+
+    fn create_account(&self, username: &str, password: &str) -> Result<(), Box<dyn Error>> {
+        Self::validate_username(username)?;
+        Self::validate_password(password)?;
+
+        let _lock = self.lock()?;
+        let mut accounts = self.read()?;
+        if accounts.iter().any(|account| account.username == username) {
+            return Err(format!("account {} already exists", username).into());
+        }
+
+        let salt: [u8; Self::SALT_LENGTH] = rand::random();
+        accounts.push(Account {
+            username: username.to_string(),
+            salt: salt.to_vec(),
+            hash: Self::hash(&salt, password),
+            locked: false,
+        });
+
+        self.write(&accounts)
+    }
+
+    fn set_locked(&self, username: &str, locked: bool) -> Result<(), Box<dyn Error>> {
+        let _lock = self.lock()?;
+        let mut accounts = self.read()?;
+        match accounts.iter_mut().find(|account| account.username == username) {
+            Some(account) => account.locked = locked,
+            None => return Err(format!("no account named {}", username).into()),
+        }
+
+        self.write(&accounts)
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use uuid::Uuid;
+use crate::auth::authenticator::{AuthResponse, Authenticator};
+use crate::auth::file_authenticator::FileAuthenticator;
+
+/// An accounts file of the test's own, removed again when it's dropped.
+struct Accounts {
+    path: PathBuf,
+    authenticator: FileAuthenticator,
+}
+
+impl Accounts {
+    fn new() -> Accounts {
+        let path = std::env::temp_dir().join(format!("accounts_{}.txt", Uuid::new_v4()));
+        Accounts { authenticator: FileAuthenticator::new(&path), path }
+    }
+}
+
+impl Drop for Accounts {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[test]
+fn test_login() {
+    let accounts = Accounts::new();
+    accounts.authenticator.create_account("bob", "hunter2").unwrap();
+    assert_eq!(accounts.authenticator.authenticate("bob", "hunter2"), AuthResponse::Ok);
+}
+
+#[test]
+fn test_bad_password() {
+    let accounts = Accounts::new();
+    accounts.authenticator.create_account("bob", "hunter2").unwrap();
+    assert_eq!(accounts.authenticator.authenticate("bob", "hunter3"), AuthResponse::InvalidCredentials);
+}
+
+#[test]
+fn test_unknown_user() {
+    let accounts = Accounts::new();
+    accounts.authenticator.create_account("bob", "hunter2").unwrap();
+    assert_eq!(accounts.authenticator.authenticate("alice", "hunter2"), AuthResponse::InvalidCredentials);
+}
+
+#[test]
+fn test_locked() {
+    let accounts = Accounts::new();
+    accounts.authenticator.create_account("bob", "hunter2").unwrap();
+    accounts.authenticator.set_locked("bob", true).unwrap();
+    assert_eq!(accounts.authenticator.authenticate("bob", "hunter2"), AuthResponse::Locked);
+    // A wrong password doesn't give away that the account is locked.
+    assert_eq!(accounts.authenticator.authenticate("bob", "hunter3"), AuthResponse::InvalidCredentials);
+}
+
+#[test]
+fn test_duplicate_account() {
+    let accounts = Accounts::new();
+    accounts.authenticator.create_account("bob", "hunter2").unwrap();
+    assert!(accounts.authenticator.create_account("bob", "hunter3").is_err());
+    assert_eq!(accounts.authenticator.authenticate("bob", "hunter2"), AuthResponse::Ok);
+}
+
+#[test]
+fn test_concurrent_writes() {
+    let accounts = Arc::new(Accounts::new());
+    let writers: Vec<_> = (0..8)
+        .map(|i| {
+            let accounts = Arc::clone(&accounts);
+            thread::spawn(move || accounts.authenticator.create_account(&format!("user{}", i), "hunter2").unwrap())
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    for i in 0..8 {
+        assert_eq!(accounts.authenticator.authenticate(&format!("user{}", i), "hunter2"), AuthResponse::Ok);
+    }
+}
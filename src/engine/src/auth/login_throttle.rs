@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::auth::authenticator::{AuthResponse, Authenticator};
+
+/// Stops password guessing by refusing logins to an account after too many failures in a row.
+pub struct LoginThrottle {
+    failures: Mutex<HashMap<String, (u32, Instant)>>,
+    cooldown: Duration,
+}
+
+impl LoginThrottle {
+    const MAX_FAILURES: u32 = 5;
+    /// How long an account stays throttled after its last failure.
+    const COOLDOWN: Duration = Duration::from_secs(300);
+
+    pub fn new() -> LoginThrottle {
+        LoginThrottle::with_cooldown(Self::COOLDOWN)
+    }
+
+    pub(crate) fn with_cooldown(cooldown: Duration) -> LoginThrottle {
+        LoginThrottle {
+            failures: Mutex::new(HashMap::new()),
+            cooldown,
+        }
+    }
+
+    pub fn authenticate(&self, authenticator: &dyn Authenticator, username: &str, password: &str) -> AuthResponse {
+        {
+            let mut failures = self.failures.lock().unwrap();
+            failures.retain(|_, (_, last)| last.elapsed() < self.cooldown);
+
+            if failures.get(username).is_some_and(|(count, _)| *count >= Self::MAX_FAILURES) {
+                return AuthResponse::TooManyAttempts;
+            }
+        }
+
+        // Unlocked while the backend works, it may be reading files and other accounts shouldn't wait on it.
+        let response = authenticator.authenticate(username, password);
+
+        let mut failures = self.failures.lock().unwrap();
+        if response == AuthResponse::InvalidCredentials {
+            let entry = failures.entry(username.to_string()).or_insert((0, Instant::now()));
+            entry.0 += 1;
+            entry.1 = Instant::now();
+        } else if response == AuthResponse::Ok {
+            failures.remove(username);
+        }
+
+        response
+    }
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+use std::thread;
+use std::time::Duration;
+use crate::auth::authenticator::{AuthResponse, Authenticator};
+use crate::auth::login_throttle::LoginThrottle;
+
+/// The one account `bob` with the password `hunter2`.
+struct Backend;
+
+impl Authenticator for Backend {
+    fn authenticate(&self, username: &str, password: &str) -> AuthResponse {
+        if username == "bob" && password == "hunter2" {
+            AuthResponse::Ok
+        } else {
+            AuthResponse::InvalidCredentials
+        }
+    }
+
+    fn create_account(&self, _username: &str, _password: &str) -> Result<(), Box<dyn Error>> {
+        Err("unsupported".into())
+    }
+
+    fn set_locked(&self, _username: &str, _locked: bool) -> Result<(), Box<dyn Error>> {
+        Err("unsupported".into())
+    }
+}
+
+fn fail(throttle: &LoginThrottle, times: usize) {
+    for _ in 0..times {
+        assert_eq!(throttle.authenticate(&Backend, "bob", "wrong"), AuthResponse::InvalidCredentials);
+    }
+}
+
+#[test]
+fn test_lockout() {
+    let throttle = LoginThrottle::new();
+    fail(&throttle, 5);
+
+    // Even the right password is refused until the cooldown passes.
+    assert_eq!(throttle.authenticate(&Backend, "bob", "hunter2"), AuthResponse::TooManyAttempts);
+    // Other accounts aren't affected.
+    assert_eq!(throttle.authenticate(&Backend, "alice", "wrong"), AuthResponse::InvalidCredentials);
+}
+
+#[test]
+fn test_success_resets_failures() {
+    let throttle = LoginThrottle::new();
+    fail(&throttle, 4);
+    assert_eq!(throttle.authenticate(&Backend, "bob", "hunter2"), AuthResponse::Ok);
+
+    fail(&throttle, 4);
+    assert_eq!(throttle.authenticate(&Backend, "bob", "hunter2"), AuthResponse::Ok);
+}
+
+#[test]
+fn test_lockout_expires() {
+    let throttle = LoginThrottle::with_cooldown(Duration::from_millis(50));
+    fail(&throttle, 5);
+    assert_eq!(throttle.authenticate(&Backend, "bob", "hunter2"), AuthResponse::TooManyAttempts);
+
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(throttle.authenticate(&Backend, "bob", "hunter2"), AuthResponse::Ok);
+}
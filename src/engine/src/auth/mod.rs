@@ -0,0 +1,8 @@
+pub mod authenticator;
+pub mod file_authenticator;
+pub mod login_throttle;
+
+#[cfg(test)]
+mod file_authenticator_tests;
+#[cfg(test)]
+mod login_throttle_tests;
@@ -0,0 +1,33 @@
+use config::server_config::ServerConfig;
+use engine::util::pack_file::revalidate_pack;
+use engine::util::runescript_compiler::{compile_scripts, update_compiler};
+use engine::util::symbols::generate_server_symbols;
+use log::error;
+
+/// Usage: compile_scripts
+///
+/// Builds the server script pack the same way the engine does on startup, without starting a world.
+fn main() {
+    if std::env::var_os("RUST_LOG").is_none() {
+        std::env::set_var("RUST_LOG", "info");
+    }
+    env_logger::init();
+
+    if let Err(e) = ServerConfig::init() {
+        error!("Failed to load configuration: {}", e);
+        std::process::exit(1);
+    }
+
+    if let Err(e) = update_compiler() {
+        error!("Failed to update compiler: {}", e);
+        std::process::exit(1);
+    }
+
+    revalidate_pack();
+    generate_server_symbols();
+
+    if let Err(e) = compile_scripts() {
+        error!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,37 @@
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use engine::auth::authenticator::Authenticator;
+use engine::auth::file_authenticator::FileAuthenticator;
+
+/// Usage: create_account <username> [accounts file]
+///
+/// The password is read from stdin, never from the arguments where `ps` and shell history would see it.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 || args.len() > 3 {
+        eprintln!("Usage: {} <username> [accounts file]", args[0]);
+        std::process::exit(1);
+    }
+
+    // Logins arrive as lowercase base37 names, so accounts are stored the same way.
+    let username = args[1].to_lowercase().replace(' ', "_");
+    let path = args.get(2).map_or_else(FileAuthenticator::default_path, PathBuf::from);
+
+    eprint!("Password for {}: ", username);
+    let _ = std::io::stderr().flush();
+
+    let mut password = String::new();
+    if let Err(e) = std::io::stdin().lock().read_line(&mut password) {
+        eprintln!("Failed to read password: {}", e);
+        std::process::exit(1);
+    }
+    let password = password.trim_end_matches(['\r', '\n']);
+
+    match FileAuthenticator::new(path).create_account(&username, password) {
+        Ok(()) => println!("Created account {}", username),
+        Err(e) => {
+            eprintln!("Failed to create account {}: {}", username, e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,34 +1,65 @@
-use std::net::{IpAddr, TcpListener};
-use std::sync::{Arc, Mutex, Once};
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 use log::{debug, error, info};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 use cache::file_handler::{ensure_initialized, get_checksum};
-use cache::xtea::{initialize_xtea, XTEAKey};
+use cache::xtea::initialize_xtea;
+use config::server_config::ServerConfig;
 use constants::window_mode::window_mode;
 use constants::login_out::login_out;
 use constants::title_protocol::title_protocol;
+use crate::auth::authenticator::{AuthResponse, Authenticator};
+use crate::auth::file_authenticator::FileAuthenticator;
+use crate::auth::login_throttle::LoginThrottle;
 use crate::io::client_state::ConnectionState;
+use crate::io::network::Network;
 use crate::io::rsa::rsa;
 use crate::engine_stat::engine_stat;
 use crate::entity::entity::EntityBehavior;
 use crate::entity::entity_list::{NPCList, PlayerList};
+use crate::entity::entity_queue_request::EntityQueueState;
+use crate::entity::entity_timer::PlayerTimerType;
+use crate::entity::entity_type::EntityType;
+use crate::entity::npc::NPC;
 use crate::entity::player::Player;
+use crate::entity::player_save::PlayerSave;
+use crate::entity::player_type::PlayerType;
 use crate::entity::window_status::WindowStatus;
 use crate::game_connection::GameClient;
+use crate::game_map::GameMap;
 use crate::grid::coord_grid::CoordGrid;
 use crate::io::packet::Packet;
+use crate::io::server::model::npc_info::NpcInfo;
+use crate::io::server::model::player_info::PlayerInfo;
+use crate::io::server::model::update_reboot_time::UpdateRebootTime;
+use crate::renderer::npc_renderer::NpcRenderer;
+use crate::renderer::player_renderer::PlayerRenderer;
 use crate::script::script_provider::ScriptProvider;
+use crate::script::script_runner::ScriptRunner;
+use crate::script::script_state::ScriptState;
+use crate::script::server_trigger_types::ServerTriggerTypes;
 use crate::util::base37::decode37;
+use crate::util::cache::config::enum_type::EnumType;
+use crate::util::cache::config::param_type::ParamType;
+use crate::util::cache::config::struct_type::StructType;
+use crate::util::cache::config::var_bit_type::VarBitType;
+use crate::util::cache::config::var_npc_type::VarNpcType;
+use crate::util::cache::config::var_shared_type::VarSharedType;
+use crate::util::cache::config::varp_type::VarpType;
 use crate::util::pack_file::revalidate_pack;
-use crate::util::runescript_compiler::update_compiler;
+use crate::util::runescript_compiler::{compile_scripts, update_compiler};
 use crate::util::symbols::generate_server_symbols;
 
 pub struct Engine {
     pub members: bool,
     pub current_tick: i32,
-    pub tick_rate: Duration, // TODO - make variable to support increased rate during shutdown.
+    pub tick_rate: Duration,
     // TODO - cache?
     // TODO - ops?
     pub cycle_stats: Vec<Duration>,
@@ -36,15 +67,29 @@ pub struct Engine {
     pub players: PlayerList,
     pub npcs: NPCList,
     pub new_players: Arc<Mutex<Vec<Player>>>,
-    // TODO - game_map
+    /// Usernames whose save is still being written after logging out.
+    pub saving_players: Arc<Mutex<HashSet<String>>>,
+    pub authenticator: Arc<dyn Authenticator>,
+    pub login_throttle: Arc<LoginThrottle>,
+    pub player_renderer: PlayerRenderer,
+    pub npc_renderer: NpcRenderer,
+    pub game_map: GameMap,
     // TODO - zone_tracking
+    /// Tick the world goes down on, once a shutdown has been scheduled.
+    pub shutdown_tick: Option<i32>,
+    /// Set by the signal listener, picked up at the start of the next tick.
+    pub shutdown_requested: Arc<AtomicBool>,
+    /// Set by the signal listener on SIGHUP, scripts are reloaded at the start of the next tick.
+    pub reload_requested: Arc<AtomicBool>,
+    /// World variables shared by every script, indexed by vars id.
+    pub vars: Vec<i32>,
+    /// Scripts waiting on a world delay, with the ticks they have left.
+    pub world_queue: Vec<EntityQueueState>,
+    /// Owns the game sockets once started, tests run the engine without one.
+    pub network: Option<Network>,
 }
 
-static mut ENGINE: Option<Engine> = None;
-static INIT: Once = Once::new();
-
 impl Engine {
-    const MAX_PLAYERS: usize = 2048;
     const MAX_NPCS: usize = 8192;
 
     const TIMEOUT_NO_CONNECTION: i32 = 50;
@@ -53,48 +98,91 @@ impl Engine {
     const AFK_EVENTRATE: i32 = 500;
     
     const INVALID_PID: usize = 5000;
+    /// Where an account that has never logged in starts out.
+    const START_COORD: CoordGrid = CoordGrid::from(3200, 0, 3200);
+
+    /// Nothing is left to simulate once everyone is logged out, only saves to wait on.
+    const SHUTDOWN_TICK_RATE: Duration = Duration::from_millis(50);
+    /// Grace period given by SIGINT/SIGTERM, short enough to finish inside a typical stop timeout.
+    const SIGNAL_SHUTDOWN_TICKS: i32 = 10;
     
-    // We don't need safety, we're smart
-    pub fn init() {
-        INIT.call_once(|| {
-            info!("Initializing global engine instance.");
-            unsafe {
-                ENGINE = Some(Engine::new());
-            }
-        })
-    }
-
-    pub fn get() -> &'static mut Engine {
-        unsafe {
-            match &mut ENGINE {
-                Some(engine) => engine,
-                None => {
-                    // Auto-initialize if needed;
-                    Self::init();
-                    ENGINE.as_mut().unwrap()
+    #[inline]
+    pub fn get_var(&self, id: usize) -> i32 {
+        self.vars.get(id).copied().unwrap_or(0)
+    }
+
+    pub fn set_var(&mut self, id: usize, value: i32) {
+        if self.vars.len() <= id {
+            self.vars.resize(id + 1, 0);
+        }
+        self.vars[id] = value;
+    }
+
+    /// Queue a world suspended script, the delay it was suspended with is still on its stack.
+    pub fn enqueue_script(&mut self, mut script: ScriptState) {
+        let delay = script.pop_int();
+        self.world_queue.push(EntityQueueState::new(script, delay));
+    }
+
+    /// Keep a suspended script with whatever it's waiting on, until the tick that resumes it.
+    pub fn suspend_script(&mut self, script: ScriptState) {
+        let secondary = script.get_int_operand() == 1;
+
+        match script.execution {
+            ScriptState::WORLD_SUSPENDED => self.enqueue_script(script),
+            ScriptState::NPC_SUSPENDED => {
+                match script.get_active_npc_nid(self, secondary).map(|nid| self.npcs.get_mut(nid)) {
+                    Ok(Some(npc)) => npc.active_script = Some(Box::new(script)),
+                    _ => error!("Suspended script {} has no npc to wait on", script.script.name()),
                 }
-            }
+            },
+            _ => {
+                match script.get_active_player_pid(self, secondary).map(|pid| self.players.get_mut(pid)) {
+                    Ok(Some(player)) => player.active_script = Some(Box::new(script)),
+                    _ => error!("Suspended script {} has no player to wait on", script.script.name()),
+                }
+            },
         }
     }
 
-    pub fn current_tick() -> i32 {
-        Self::get().current_tick
+    /// Run a script with no protected access, parking it again if it suspends.
+    pub fn execute_script(&mut self, mut script: ScriptState) {
+        let state = ScriptRunner::execute(self, &mut script, false, false);
+
+        if state != ScriptState::FINISHED && state != ScriptState::ABORTED {
+            self.suspend_script(script);
+        }
     }
 
     pub fn new() -> Engine {
+        let config = ServerConfig::get();
+
         Engine {
-            members: false,
+            members: config.world.members,
             current_tick: 0,
-            tick_rate: Duration::from_millis(600),
+            tick_rate: config.world.tick_rate(),
             cycle_stats: vec![Duration::new(0, 0); 12],
             last_cycle_stats: vec![Duration::new(0, 0); 12],
-            players: PlayerList::new(Engine::MAX_PLAYERS - 1),
+            players: PlayerList::new(config.world.max_players - 1),
             npcs: NPCList::new(Engine::MAX_NPCS - 1),
             new_players: Default::default(),
+            saving_players: Default::default(),
+            authenticator: Arc::new(FileAuthenticator::new(FileAuthenticator::default_path())),
+            login_throttle: Arc::new(LoginThrottle::new()),
+            player_renderer: PlayerRenderer::new(),
+            npc_renderer: NpcRenderer::new(),
+            game_map: GameMap::new(),
+            shutdown_tick: None,
+            shutdown_requested: Default::default(),
+            reload_requested: Default::default(),
+            vars: Vec::new(),
+            world_queue: Vec::new(),
+            network: None,
         }
     }
 
-    pub fn start(&mut self, start_cycle: bool) {
+    /// Load everything the world needs and open it up to players, failing if the game port can't be listened on.
+    pub fn start(&mut self, start_cycle: bool) -> std::io::Result<()> {
         if let Err(e) = update_compiler() {
             error!("Failed to update compiler: {}", e);
         }
@@ -102,6 +190,12 @@ impl Engine {
         revalidate_pack();
         generate_server_symbols();
 
+        if ServerConfig::get().compiler.compile_on_start {
+            if let Err(e) = compile_scripts() {
+                error!("Failed to compile scripts, loading the last compiled scripts: {}", e);
+            }
+        }
+
         if let Err(e) = ensure_initialized() {
             error!("Failed to initialize cache: {}", e);
         } else {
@@ -114,49 +208,27 @@ impl Engine {
             debug!("XTEA module initialized.");
         }
 
+        VarpType::load();
+        VarBitType::load();
+        VarNpcType::load();
+        VarSharedType::load();
+        EnumType::load();
+        ParamType::load();
+        StructType::load();
+        self.vars = vec![0; VarSharedType::count()];
         ScriptProvider::load();
 
-        info!("Starting server on port 40001");
-        let listen_addr = "127.0.0.1:40001";
-        let thread_new_players = Arc::clone(&self.new_players);
-        
-        thread::spawn(move || {
-            match TcpListener::bind(listen_addr) {
-                Ok(listener) => {
-                    for stream in listener.incoming() {
-                        match stream {
-                            Ok(stream) => {
-                                let thread_player = Arc::clone(&thread_new_players);
-
-                                thread::spawn(move || {
-                                    let mut game_client = GameClient::new(stream);
-
-                                    loop {
-                                        if game_client.state == ConnectionState::New && game_client.is_connection_active() {
-                                            Self::on_new_connection(&mut game_client, thread_player.clone());
-                                        } else {
-                                            break
-                                        }
-                                    }
-                                });
-                            }
-                            Err(e) => {
-                                error!("Connection failed: {}", e);
-                            }
-                        }
-                    }
-                },
-                Err(e) => {
-                    error!("Failed to bind to {}: {}", listen_addr, e);
-                }
-            }
-        });
+        Self::listen_for_signals(Arc::clone(&self.shutdown_requested), Arc::clone(&self.reload_requested));
+
+        let listen_addr = ServerConfig::get().network.game_addr.clone();
+        self.network = Some(Network::start(&listen_addr, Arc::clone(&self.new_players), Arc::clone(&self.authenticator), Arc::clone(&self.login_throttle))?);
 
-        // TODO - load map
+        self.game_map.init();
         info!("World ready!");
         if start_cycle {
             self.cycle();
         }
+        Ok(())
     }
 
     #[rustfmt::skip]
@@ -170,6 +242,11 @@ impl Engine {
 
         loop {
             let start = Instant::now();
+
+            if self.process_shutdown() {
+                break;
+            }
+            self.process_reload();
             
             self.process_world();
             self.process_in();
@@ -228,6 +305,83 @@ impl Engine {
                 }
             }
         }
+
+        info!("Shutdown complete on tick {}", self.current_tick);
+    }
+
+    /// Start the reboot timer, everyone is logged out and the server stops once it runs out.
+    pub fn schedule_shutdown(&mut self, ticks: i32) {
+        let ticks = ticks.max(0);
+        info!("Shutting down in {} ticks", ticks);
+
+        self.shutdown_tick = Some(self.current_tick + ticks);
+        self.players.for_each_mut(|player| player.write(UpdateRebootTime::new(ticks)));
+    }
+
+    /// Ticks left on the reboot timer, if a shutdown is scheduled.
+    #[inline]
+    pub fn shutdown_ticks_remaining(&self) -> Option<i32> {
+        self.shutdown_tick.map(|tick| (tick - self.current_tick).max(0))
+    }
+
+    #[inline]
+    fn is_shutting_down(&self) -> bool {
+        self.shutdown_tick.is_some_and(|tick| self.current_tick >= tick)
+    }
+
+    /// SIGINT and SIGTERM schedule a shutdown rather than killing the process, so nobody's save is lost.
+    /// SIGHUP reloads scripts.
+    fn listen_for_signals(shutdown_requested: Arc<AtomicBool>, reload_requested: Arc<AtomicBool>) {
+        thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    error!("Failed to start signal listener: {}", e);
+                    return;
+                }
+            };
+
+            runtime.block_on(async move {
+                #[cfg(unix)]
+                let mut terminate = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                    Ok(terminate) => terminate,
+                    Err(e) => {
+                        error!("Failed to listen for SIGTERM: {}", e);
+                        return;
+                    }
+                };
+                #[cfg(unix)]
+                let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+                    Ok(hangup) => hangup,
+                    Err(e) => {
+                        error!("Failed to listen for SIGHUP: {}", e);
+                        return;
+                    }
+                };
+
+                loop {
+                    #[cfg(unix)]
+                    let shutdown = tokio::select! {
+                        _ = tokio::signal::ctrl_c() => true,
+                        _ = terminate.recv() => true,
+                        _ = hangup.recv() => false,
+                    };
+                    #[cfg(not(unix))]
+                    let shutdown = match tokio::signal::ctrl_c().await {
+                        Ok(()) => true,
+                        Err(_) => return,
+                    };
+
+                    if shutdown {
+                        info!("Received shutdown signal");
+                        shutdown_requested.store(true, Ordering::SeqCst);
+                    } else {
+                        info!("Received reload signal");
+                        reload_requested.store(true, Ordering::SeqCst);
+                    }
+                }
+            });
+        });
     }
     
     /// - World Queue
@@ -235,7 +389,21 @@ impl Engine {
     /// - NPC Hunt
     fn process_world(&mut self) {
         let start: Instant = Instant::now();
-        // TODO
+
+        // Scripts run in the order they were queued, anything they queue waits until next tick.
+        let mut ready = Vec::new();
+        for mut request in std::mem::take(&mut self.world_queue) {
+            request.delay -= 1;
+            if request.delay < 0 {
+                ready.push(request.script_state);
+            } else {
+                self.world_queue.push(request);
+            }
+        }
+
+        for script in ready {
+            self.execute_script(script);
+        }
 
         // NPC [ai_spawn] scripts
         // NPC hunt players if not busy
@@ -259,20 +427,27 @@ impl Engine {
         // TODO - separate out stat?
         //self.cycle_stats[EngineStat::BandwidthIn as usize] = 0;
 
-        self.players.for_each_mut(|player| {
-            player.playtime += 1;
-
-            if player.is_client_connected() && player.decode_in(self.current_tick) {
+        let current_tick = self.current_tick;
+        for pid in self.players.ids() {
+            if let Some(player) = self.players.get_mut(pid) {
+                player.playtime += 1;
 
+                if player.is_client_connected() && player.decode_in(current_tick) {
+                    player.path_to_move_click();
+                }
             }
-        });
+
+            Player::process_resume(self, pid);
+        }
 
         // TODO - client input tracking
 
-        // TODO - process pathfinding/following
+        // TODO - process following
         self.cycle_stats[engine_stat::CLIENTS_IN] = start.elapsed();
     }
     
+    /// Respawn
+    ///
     /// Resume suspended script
     ///
     /// Stat regeneration
@@ -286,7 +461,30 @@ impl Engine {
     /// Modes
     fn process_npcs(&mut self) {
         let start: Instant = Instant::now();
-        // TODO
+
+        let current_tick = self.current_tick;
+        for nid in self.npcs.ids() {
+            let Some(npc) = self.npcs.get_mut(nid) else {
+                continue;
+            };
+            if !npc.process_respawn(current_tick) {
+                continue;
+            }
+            let resumed = if npc.pathing_entity.update_delay(current_tick) {
+                None
+            } else {
+                npc.active_script.take_if(|script| script.execution == ScriptState::NPC_SUSPENDED)
+            };
+            if let Some(script) = resumed {
+                self.execute_script(*script);
+            }
+
+            // TODO - stat regeneration
+            NPC::process_timers(self, nid);
+            NPC::process_queue(self, nid);
+            // TODO - movement, modes
+        }
+
         self.cycle_stats[engine_stat::NPCS] = start.elapsed();
     }
     
@@ -310,6 +508,31 @@ impl Engine {
     fn process_players(&mut self) {
         let start: Instant = Instant::now();
 
+        let current_tick = self.current_tick;
+        for pid in self.players.ids() {
+            let Some(player) = self.players.get_mut(pid) else {
+                continue;
+            };
+            let resumed = if player.pathing_entity.update_delay(current_tick) {
+                None
+            } else {
+                player.active_script.take_if(|script| script.execution == ScriptState::SUSPENDED)
+            };
+            if let Some(script) = resumed {
+                Player::execute_script(self, pid, *script, Some(true), Some(true));
+            }
+
+            Player::process_queue(self, pid);
+            Player::process_weak_queue(self, pid);
+            Player::process_timers(self, pid, PlayerTimerType::Normal);
+            Player::process_timers(self, pid, PlayerTimerType::Soft);
+            Player::process_engine_queue(self, pid);
+            // TODO - interactions
+            if let Some(player) = self.players.get_mut(pid) {
+                player.process_movement();
+            }
+        }
+
         self.cycle_stats[engine_stat::PLAYERS] = start.elapsed();
     }
     
@@ -317,9 +540,10 @@ impl Engine {
     fn process_logouts(&mut self) {
         let start: Instant = Instant::now();
 
+        let shutting_down = self.is_shutting_down();
         let mut pids_to_remove = Vec::with_capacity(16);
         self.players.for_each_mut(|player| {
-            let mut force: bool = false;
+            let mut force: bool = shutting_down;
 
             if (self.current_tick - player.initial_connect_tick) - player.last_response >= Self::TIMEOUT_NO_RESPONSE {
                 // X-logged / timed out for 60s: force logout.
@@ -345,9 +569,9 @@ impl Engine {
             }
         });
 
-        // TODO
-
         for pid in pids_to_remove {
+            Player::process_logout_queue(self, pid);
+            self.save_player(pid);
             self.remove_player(pid)
         }
 
@@ -368,6 +592,8 @@ impl Engine {
             }
         }
 
+        let reboot_ticks = self.shutdown_ticks_remaining();
+
         let player_to_add = {
             let mut shared_players = self.new_players.lock().unwrap();
             shared_players.drain(..).collect::<Vec<Player>>()
@@ -375,33 +601,94 @@ impl Engine {
 
         for mut player in player_to_add {
             // Prevent logging in if a player save is being flushed
-            // TODO
+            if self.saving_players.lock().unwrap().contains(&player.username) {
+                player.client.outbound = Packet::new(1);
+                player.client.outbound.p1(login_out::ALREADY_LOGGED_IN);
+                player.client.write_packet();
+                player.client.shutdown();
+                continue;
+            }
+
+            let existing = self.get_pid_by_username(&player.username);
 
             // Reconnect a new socket with player in the world
-            // TODO
+            if player.reconnect {
+                match existing {
+                    Some(pid) => {
+                        if let Some(existing) = self.players.get_mut(pid) {
+                            if !existing.logging_out {
+                                let mut client = std::mem::replace(&mut player.client, GameClient::new_dummy());
+                                client.write_packet();
+                                existing.on_reconnect(client, self.current_tick);
+                                if let Some(ticks) = reboot_ticks {
+                                    existing.write(UpdateRebootTime::new(ticks));
+                                }
+                                continue;
+                            }
+                        }
+                    },
+                    None => {
+                        // The session is gone, the client handles a normal login response to a reconnect as a fresh login.
+                        player.client.outbound = Packet::new(1);
+                        player.client.outbound.p1(login_out::OK);
+                    }
+                }
+            }
 
             // Player already logged in
-            //for (_, other_player) in &self.players {
-            // TODO
-            //}
+            if existing.is_some() {
+                player.client.outbound = Packet::new(1);
+                player.client.outbound.p1(login_out::ALREADY_LOGGED_IN);
+                player.client.write_packet();
+                player.client.shutdown();
+                continue;
+            }
 
             // Prevent logging in when the server is shutting down.
-            // TODO
+            if reboot_ticks.is_some() {
+                player.client.outbound = Packet::new(1);
+                player.client.outbound.p1(login_out::UPDATE_IN_PROGRESS);
+                player.client.write_packet();
+                player.client.shutdown();
+                continue;
+            }
+
+            // Only read once the checks above have made sure no logout save is still being written for them.
+            // Never start a session on defaults over a save that exists but can't be read.
+            match PlayerSave::load(&player.username) {
+                Ok(Some(save)) => save.apply(&mut player),
+                Ok(None) => PlayerSave::new(Self::START_COORD, 0).apply(&mut player),
+                Err(e) => {
+                    error!("Failed to load save for {}: {}", player.username, e);
+                    player.client.outbound = Packet::new(1);
+                    player.client.outbound.p1(login_out::ERROR_LOADING_PROFILE);
+                    player.client.write_packet();
+                    player.client.shutdown();
+                    continue;
+                }
+            }
 
             match self.get_next_pid(Some(&player.client)) {
                 Ok(pid) => {
-                    player.client.write_packet().expect("Failed to write packet to new connection");
+                    player.client.write_packet();
                     player.set_pid(pid);
                     self.players.set(pid, player).expect("Failed to set player!");
 
-                    if let Some(player_ref) = self.players.get_mut(pid) {
-                        player_ref.on_login(self.current_tick);
+                    let Some(player_ref) = self.players.get_mut(pid) else {
+                        continue;
+                    };
+                    player_ref.on_login(self.current_tick);
+                    let self_entity = player_ref.as_entity_type();
+
+                    if let Some(trigger) = ScriptProvider::get_by_trigger_specific(ServerTriggerTypes::LOGIN, -1, -1) {
+                        let script = ScriptRunner::init(trigger, Some(self_entity), None, None);
+                        Player::execute_script(self, pid, script, Some(true), None);
                     }
                 },
                 Err(_err) => {
                     player.client.outbound = Packet::new(1);
                     player.client.outbound.p1(login_out::WORLD_FULL);
-                    player.client.write_packet().expect("Failed to write packet to new connection");
+                    player.client.write_packet();
                     player.client.shutdown();
                 }
             };
@@ -423,6 +710,8 @@ impl Engine {
     
     /// Convert player movements
     ///
+    /// Map update
+    ///
     /// Compute player info
     ///
     /// Convert npc movements
@@ -431,11 +720,45 @@ impl Engine {
     fn process_info(&mut self) {
         let start: Instant = Instant::now();
         // TODO - add benchmark value for this?
-        // TODO
+
+        // Teleports are sent relative to the build area, so the map has to be rebuilt first.
+        let mut pids = Vec::with_capacity(self.players.count());
+        self.players.for_each_mut(|player| {
+            player.pathing_entity.convert_movement_dir();
+            if player.is_client_connected() {
+                player.rebuild_normal(false);
+            }
+            pids.push(player.get_pid());
+        });
+
+        let renderer = &mut self.player_renderer;
+        self.players.for_each(|player| renderer.compute_info(player));
+
+        self.npcs.for_each_mut(|npc| npc.pathing_entity.convert_movement_dir());
+
+        for pid in pids {
+            let mut build_area = match self.players.get_mut(pid) {
+                Some(player) if player.is_client_connected() => std::mem::take(&mut player.build_area),
+                _ => continue,
+            };
+
+            let (player_info, npc_info) = match self.players.get(pid) {
+                Some(viewer) => (
+                    self.player_renderer.encode_info(viewer, &mut build_area, &self.players),
+                    self.npc_renderer.encode_info(viewer, &mut build_area, &self.npcs),
+                ),
+                None => continue,
+            };
+
+            if let Some(player) = self.players.get_mut(pid) {
+                player.build_area = build_area;
+                player.build_area.resize();
+                player.write(PlayerInfo::new(player_info.data));
+                player.write(NpcInfo::new(npc_info.data));
+            }
+        }
     }
     
-    /// Map update
-    ///
     /// Player info
     ///
     /// NPC info
@@ -457,6 +780,7 @@ impl Engine {
             }
             // TODO
             player.encode_out();
+            player.client.flush();
         });
         self.cycle_stats[engine_stat::CLIENTS_OUT] = start.elapsed();
     }
@@ -475,48 +799,133 @@ impl Engine {
         // TODO
         
         // Reset players
-        //for (pid, player) in &self.players {
-            //let _: Player = player.borrow().player;
-            // TODO
-        //}
+        self.players.for_each_mut(|player| player.reset_entity());
         // Reset npcs
-        // TODO
+        self.npcs.for_each_mut(|npc| npc.reset_entity());
         // Reset inventories
         // TODO
         self.cycle_stats[engine_stat::CLEANUP] = start.elapsed();
     }
 
-    fn process_shutdown(&mut self) {
+    /// Swap in freshly compiled scripts between ticks, anything already running finishes on the old ones.
+    fn process_reload(&mut self) {
+        if !self.reload_requested.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        if let Err(e) = ScriptProvider::reload() {
+            error!("Failed to reload scripts, keeping the current ones: {}", e);
+        }
+    }
+
+    /// Picks up shutdown signals, and once the reboot timer runs out logs everyone out for [Engine::process_logouts] to save.
+    ///
+    /// Returns true once every player is gone and their saves are on disk.
+    fn process_shutdown(&mut self) -> bool {
+        if self.shutdown_requested.swap(false, Ordering::SeqCst) {
+            match self.shutdown_tick {
+                // A second signal means now.
+                Some(_) => self.shutdown_tick = Some(self.current_tick),
+                None => self.schedule_shutdown(Self::SIGNAL_SHUTDOWN_TICKS),
+            }
+        }
+
+        if !self.is_shutting_down() {
+            return false;
+        }
+
+        if self.tick_rate != Self::SHUTDOWN_TICK_RATE {
+            info!("Reboot timer ran out, logging out {} players", self.players.count());
+            self.tick_rate = Self::SHUTDOWN_TICK_RATE;
+        }
+
         self.players.for_each_mut(|player| {
-            if player.is_client_connected() {
+            if !player.logging_out {
+                player.logging_out = true;
                 player.logout();
-                player.client.shutdown();
             }
         });
+
+        self.players.count() == 0 && self.saving_players.lock().unwrap().is_empty()
     }
 
     #[inline]
     pub fn remove_player(&mut self, pid: usize) {
         if let Some(player_ref) = self.players.get_mut(pid) {
-            if player_ref.is_client_connected() {
-                player_ref.client.shutdown();
-            }
+            // Even a socket that already looks closed, its tasks only stop once it's let go.
+            player_ref.client.shutdown();
             player_ref.set_active(false);
         }
         self.players.remove(pid);
+        self.player_renderer.remove_permanent(pid);
+        self.players.for_each_mut(|viewer| viewer.build_area.forget_player(pid));
     }
 
-    fn on_new_connection(client: &mut GameClient, thread_player: Arc<Mutex<Vec<Player>>>) {
-        
-        if let Err(err) = client.read_packet_with_size(1) {
-            error!("Failed to read packet from client: {}", err);
-            client.shutdown();
-            return
-        }
+    fn get_pid_by_username(&self, username: &str) -> Option<usize> {
+        let mut found = None;
+        self.players.for_each(|player| {
+            if found.is_none() && player.username == username {
+                found = Some(player.get_pid());
+            }
+        });
+        found
+    }
+
+    /// Encode the player's save now, and write it out on another thread so the tick isn't held up by disk IO.
+    fn save_player(&self, pid: usize) {
+        let player = match self.players.get(pid) {
+            Some(player) if player.player_type == PlayerType::ClientBound => player,
+            _ => return,
+        };
+
+        let username = player.username.clone();
+        let data = PlayerSave::from_player(player).encode();
+
+        self.saving_players.lock().unwrap().insert(username.clone());
+        let saving_players = Arc::clone(&self.saving_players);
+
+        thread::spawn(move || {
+            if let Err(e) = PlayerSave::write(&username, &data) {
+                error!("Failed to save player {}: {}", username, e);
+            }
+            saving_players.lock().unwrap().remove(&username);
+        });
+    }
+
+    /// Read `size` bytes of a title packet into the client's inbound [Packet].
+    async fn read_login(stream: &mut TcpStream, client: &mut GameClient, size: usize) -> std::io::Result<()> {
+        let mut data = vec![0u8; size];
+        stream.read_exact(&mut data).await?;
+        client.inbound = Packet::from(data);
+        Ok(())
+    }
+
+    /// Send whatever has been written to the client's outbound [Packet] straight to the socket.
+    async fn write_login(stream: &mut TcpStream, client: &mut GameClient) -> std::io::Result<()> {
+        stream.write_all(&client.outbound.data[..client.outbound.position]).await?;
+        client.outbound = Packet::new(1);
+        Ok(())
+    }
+
+    /// Answer a login with `response`, the socket is closed by the caller.
+    async fn reject_login(stream: &mut TcpStream, client: &mut GameClient, response: i32) -> std::io::Result<Option<Player>> {
+        client.outbound.p1(response);
+        Self::write_login(stream, client).await?;
+        Err(std::io::Error::other(format!("Login rejected with response {}", response)))
+    }
 
+    /// Handle one title packet from a new socket, returning the player once it has logged in.
+    pub(crate) async fn on_new_connection(
+        stream: &mut TcpStream,
+        authenticator: &Arc<dyn Authenticator>,
+        login_throttle: &Arc<LoginThrottle>
+    ) -> std::io::Result<Option<Player>> {
+        let mut client = GameClient::new_dummy();
+
+        Self::read_login(stream, &mut client, 1).await?;
         client.opcode = client.inbound().g1();
         if client.opcode == title_protocol::INIT_GAME_CONNECTION {
-            client.read_packet_with_size(1).unwrap();
+            Self::read_login(stream, &mut client, 1).await?;
 
             // Used to load-balance.
             let _username_hash = client.inbound().g1();
@@ -525,19 +934,19 @@ impl Engine {
             // Server session key for this connection, used in decrypting return values.
             let session_key: u64 = ((rand::random::<f64>() * 99999999.0) as u64) << 32 | ((rand::random::<f64>() * 99999999.0) as u64);
             client.outbound.p8(session_key as i64);
-            client.write_packet().expect("Failed to write packet to new connection");
+            Self::write_login(stream, &mut client).await?;
+            Ok(None)
         }  else if client.opcode == title_protocol::RECONNECT || client.opcode == title_protocol::LOGIN {
+            let opcode = client.opcode;
+
             // RECONNECT & LOGIN packet length is variable, length indicated by 'short' after opcode.
-            client.read_packet_with_size(2).unwrap();
+            Self::read_login(stream, &mut client, 2).await?;
             let payload_length = client.inbound.g2();
-            client.read_packet_with_size(payload_length as usize).unwrap();
+            Self::read_login(stream, &mut client, payload_length as usize).await?;
 
             let client_revision = client.inbound.g4();
             if client_revision != 530 {
-                client.outbound.p1(login_out::CLIENT_OUT_OF_DATE);
-                client.write_packet().expect("Failed to write packet to new connection");
-                client.shutdown();
-                return
+                return Self::reject_login(stream, &mut client, login_out::CLIENT_OUT_OF_DATE).await;
             }
 
             // Data here is unknown. 
@@ -563,10 +972,7 @@ impl Engine {
             for i in 0..28 {
                 let checksum = client.inbound().g4() as u32;
                 if checksum != get_checksum(i).expect("Failed to get checksum for archive") {
-                    client.outbound.p1(login_out::CLIENT_OUT_OF_DATE);
-                    client.write_packet().expect("Failed to write packet to new connection");
-                    client.shutdown();
-                    return
+                    return Self::reject_login(stream, &mut client, login_out::CLIENT_OUT_OF_DATE).await;
                 }
             }
 
@@ -576,56 +982,62 @@ impl Engine {
             let rsa_verification = rsa_packet_decrypted.g1();
             if rsa_verification != 10 {
                 debug!("RSA verification failed, received value: {}", rsa_verification);
-                client.outbound.p1(login_out::INVALID_LOGIN_PACKET);
-                client.write_packet().expect("Failed to write packet to new connection");
-                client.shutdown();
-                return
-            }
-
-            // Sent on login, however it has no function in revision 530.
-            let _xtea_key = XTEAKey(
-                rsa_packet_decrypted.g4() + 50,
-                rsa_packet_decrypted.g4() + 50,
-                rsa_packet_decrypted.g4() + 50, 
-                rsa_packet_decrypted.g4() + 50
-            );
-            
+                return Self::reject_login(stream, &mut client, login_out::INVALID_LOGIN_PACKET).await;
+            }
+
+            // ISAAC seed, every opcode after the login response is masked with it.
+            let seed = [
+                rsa_packet_decrypted.g4(),
+                rsa_packet_decrypted.g4(),
+                rsa_packet_decrypted.g4(),
+                rsa_packet_decrypted.g4()
+            ];
+
             let username = decode37(rsa_packet_decrypted.g8());
             let password = rsa_packet_decrypted.gjstr();
 
-            if client.opcode == title_protocol::RECONNECT {
+            // Hashing the password blocks, keep it off the network threads.
+            let response = {
+                let authenticator = Arc::clone(authenticator);
+                let login_throttle = Arc::clone(login_throttle);
+                let username = username.clone();
+                tokio::task::spawn_blocking(move || login_throttle.authenticate(authenticator.as_ref(), &username, &password)).await?
+            };
+
+            if response != AuthResponse::Ok {
+                debug!("Login rejected for {}: {:?}", username, response);
+                return Self::reject_login(stream, &mut client, response.login_out()).await;
+            }
+
+            // The response goes out with the first flush once the engine has taken the player.
+            let reconnect = opcode == title_protocol::RECONNECT;
+            if reconnect {
                 client.outbound.p1(login_out::RECONNECT_OK);
-            } else if client.opcode == title_protocol::LOGIN {
+            } else {
                 client.outbound.p1(login_out::OK);
             }
 
+            client.set_ciphers(seed);
             client.opcode = 0;
             client.state = ConnectionState::Connected;
 
-            let mut new_client = Some(std::mem::replace(
-                client,
-                GameClient::new_dummy()
-            ));
-            
-            let player = Player::new(
-                &mut new_client, 
-                CoordGrid::from(3200, 0, 3200), 
-                0, 
+            // The save is loaded by the tick once it's sure nothing is still writing it, see [Engine::process_logins].
+            let mut player = Player::new(
+                &mut Some(client),
+                Self::START_COORD,
+                0,
                 window_status, 
-                0, 
+                0,
                 Self::INVALID_PID,
                 verify_id,
                 username
             );
+            player.reconnect = reconnect;
 
-            let mut players_lock = thread_player.lock().unwrap();
-            players_lock.push(player);
+            Ok(Some(player))
         } else {
             debug!("Invalid opcode from initial connection: [{}]", client.opcode);
-            client.outbound.p1(login_out::INVALID_LOGIN_PACKET);
-            client.write_packet().expect("Failed to write packet to new connection");
-            client.shutdown();
-            return
+            Self::reject_login(stream, &mut client, login_out::INVALID_LOGIN_PACKET).await
         }
     }
 
@@ -636,12 +1048,7 @@ impl Engine {
         };
         
         let default = || self.players.next(false, None);
-        let stream = match &client.stream {
-            Some(s) => s,
-            None => return default(),
-        };
-
-        let peer_addr = match stream.peer_addr() {
+        let peer_addr = match client.peer_addr() {
             Ok(addr) => addr,
             Err(_) => return default(),
         };
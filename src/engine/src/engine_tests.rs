@@ -0,0 +1,45 @@
+use std::sync::Arc;
+use std::thread;
+use crate::engine::Engine;
+use crate::game_map::GameMap;
+use crate::grid::coord_grid::CoordGrid;
+use crate::script::script_file::ScriptFile;
+use crate::script::script_opcode::ScriptOpcode;
+use crate::script::script_state::ScriptState;
+
+/// A script that waits on the world for `delay` ticks, then finishes.
+fn world_delay(delay: i32) -> ScriptState {
+    let mut script = ScriptFile::new(0);
+    script.info.lookup_key = 0;
+    script.opcodes.push(ScriptOpcode::PUSH_CONSTANT_INT);
+    script.int_operands.push(delay);
+    script.opcodes.push(ScriptOpcode::WORLD_DELAY);
+    script.int_operands.push(0);
+    ScriptState::new(Arc::new(script), None)
+}
+
+#[test]
+fn test_engines_are_isolated() {
+    // Each engine runs on its own thread, nothing one does should show up in the other.
+    let worlds: Vec<_> = (1..=2).map(|delay| thread::spawn(move || {
+        let mut engine = Engine::new();
+        engine.set_var(0, delay);
+        engine.execute_script(world_delay(delay));
+
+        let tile = CoordGrid::from(3100 + delay as u16, 0, 3100);
+        GameMap::allocate_zone(tile);
+        GameMap::change_floor_collision(tile, true);
+        (engine.get_var(0), engine.world_queue.len(), engine.world_queue[0].delay, GameMap::is_blocked(tile))
+    })).collect();
+
+    let worlds: Vec<_> = worlds.into_iter().map(|world| world.join().unwrap()).collect();
+    assert_eq!(worlds, vec![(1, 1, 1, true), (2, 1, 2, true)]);
+
+    // Except collision, which is one map for the whole process.
+    for delay in 1..=2 {
+        let tile = CoordGrid::from(3100 + delay, 0, 3100);
+        assert!(GameMap::is_blocked(tile));
+        GameMap::change_floor_collision(tile, false);
+        assert!(!GameMap::is_blocked(tile));
+    }
+}
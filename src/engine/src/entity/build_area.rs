@@ -0,0 +1,201 @@
+use fnv::{FnvHashMap, FnvHashSet};
+use crate::entity::entity::EntityBehavior;
+use crate::entity::entity_list::{NPCList, PlayerList};
+use crate::entity::npc::NPC;
+use crate::entity::player::Player;
+
+/// Per viewer tracking of everything the client currently has loaded around it.
+#[derive(Clone, PartialEq, Default)]
+pub struct BuildArea {
+    /// Local players, in the order the client has them.
+    pub players: Vec<usize>,
+    player_set: FnvHashSet<usize>,
+    /// The appearance tick last sent to this viewer for each pid.
+    pub appearances: FnvHashMap<usize, i32>,
+    /// Local npcs by nid, in the order the client has them.
+    pub npcs: Vec<usize>,
+    npc_set: FnvHashSet<usize>,
+    pub view_distance: i32,
+    pub last_resize: i32,
+    pub force_view_distance: bool,
+}
+
+impl BuildArea {
+    /// Ticks between view distance increases.
+    pub const INTERVAL: i32 = 10;
+    /// Highest pid the client can be told about, also used as the add terminator.
+    pub const MAX_PID: usize = 2047;
+    /// The client keeps at most 255 local players.
+    pub const PREFERRED_PLAYERS: usize = 250;
+    /// Highest nid the client can be told about, also used as the add terminator.
+    pub const MAX_NID: usize = 32767;
+    pub const NPC_VIEW_DISTANCE: i32 = 15;
+    pub const PREFERRED_VIEW_DISTANCE: i32 = 15;
+
+    pub fn new() -> BuildArea {
+        BuildArea {
+            players: Vec::with_capacity(BuildArea::PREFERRED_PLAYERS),
+            player_set: FnvHashSet::default(),
+            appearances: FnvHashMap::default(),
+            npcs: Vec::with_capacity(255),
+            npc_set: FnvHashSet::default(),
+            view_distance: BuildArea::PREFERRED_VIEW_DISTANCE,
+            last_resize: 0,
+            force_view_distance: false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.players.clear();
+        self.player_set.clear();
+        self.appearances.clear();
+        self.npcs.clear();
+        self.npc_set.clear();
+        self.view_distance = BuildArea::PREFERRED_VIEW_DISTANCE;
+        self.last_resize = 0;
+    }
+
+    #[inline]
+    pub fn has_player(&self, pid: usize) -> bool {
+        self.player_set.contains(&pid)
+    }
+
+    pub fn add_player(&mut self, pid: usize) {
+        if self.player_set.insert(pid) {
+            self.players.push(pid);
+        }
+    }
+
+    /// Forget the appearance sent for a player who left the world, whoever takes their pid next gets theirs sent in full.
+    pub fn forget_player(&mut self, pid: usize) {
+        self.appearances.remove(&pid);
+    }
+
+    /// Replace the local list with the players that survived this tick, keeping their order.
+    pub fn retain_players(&mut self, players: Vec<usize>) {
+        self.player_set.clear();
+        self.player_set.extend(players.iter().copied());
+        self.players = players;
+    }
+
+    #[inline]
+    pub fn has_npc(&self, nid: usize) -> bool {
+        self.npc_set.contains(&nid)
+    }
+
+    pub fn add_npc(&mut self, nid: usize) {
+        if self.npc_set.insert(nid) {
+            self.npcs.push(nid);
+        }
+    }
+
+    /// Replace the local npc list with the npcs that survived this tick, keeping their order.
+    pub fn retain_npcs(&mut self, npcs: Vec<usize>) {
+        self.npc_set.clear();
+        self.npc_set.extend(npcs.iter().copied());
+        self.npcs = npcs;
+    }
+
+    /// Shrink the view distance while crowded, grow it back slowly once it isn't.
+    pub fn resize(&mut self) {
+        if self.force_view_distance {
+            return;
+        }
+
+        if self.players.len() >= BuildArea::PREFERRED_PLAYERS {
+            if self.view_distance > 1 {
+                self.view_distance -= 1;
+            }
+            self.last_resize = 0;
+            return;
+        }
+
+        self.last_resize += 1;
+        if self.last_resize >= BuildArea::INTERVAL {
+            if self.view_distance < BuildArea::PREFERRED_VIEW_DISTANCE {
+                self.view_distance += 1;
+            } else {
+                self.last_resize = 0;
+            }
+        }
+    }
+
+    /// Whether `other` is close enough to stay in, or be added to, this viewer's local list.
+    pub fn within_view(&self, viewer: &Player, other: &Player) -> bool {
+        let from = viewer.coord();
+        let to = other.coord();
+
+        if from.y() != to.y() {
+            return false;
+        }
+
+        let dx = (to.x() as i32 - from.x() as i32).abs();
+        let dz = (to.z() as i32 - from.z() as i32).abs();
+        dx <= self.view_distance && dz <= self.view_distance
+    }
+
+    /// Players not yet tracked that can be added this tick, nearest first.
+    pub fn get_nearby_players(&self, viewer: &Player, players: &PlayerList) -> Vec<usize> {
+        let pid = viewer.get_pid();
+        let from = viewer.coord();
+        let mut nearby: Vec<(i32, usize)> = Vec::new();
+
+        // TODO - look up through zone tracking once it exists rather than walking the whole list.
+        players.for_each(|other| {
+            let other_pid = other.get_pid();
+            if other_pid == pid || other_pid > BuildArea::MAX_PID || !other.active() || self.has_player(other_pid) {
+                return;
+            }
+
+            if !self.within_view(viewer, other) {
+                return;
+            }
+
+            let to = other.coord();
+            let distance = (to.x() as i32 - from.x() as i32).abs().max((to.z() as i32 - from.z() as i32).abs());
+            nearby.push((distance, other_pid));
+        });
+
+        nearby.sort_unstable();
+        nearby.into_iter().map(|(_, pid)| pid).collect()
+    }
+
+    /// Whether `npc` is close enough to stay in, or be added to, this viewer's local list.
+    pub fn npc_within_view(&self, viewer: &Player, npc: &NPC) -> bool {
+        let from = viewer.coord();
+        let to = npc.coord();
+
+        if from.y() != to.y() {
+            return false;
+        }
+
+        let dx = (to.x() as i32 - from.x() as i32).abs();
+        let dz = (to.z() as i32 - from.z() as i32).abs();
+        dx <= BuildArea::NPC_VIEW_DISTANCE && dz <= BuildArea::NPC_VIEW_DISTANCE
+    }
+
+    /// Npcs not yet tracked that can be added this tick, nearest first.
+    pub fn get_nearby_npcs(&self, viewer: &Player, npcs: &NPCList) -> Vec<usize> {
+        let from = viewer.coord();
+        let mut nearby: Vec<(i32, usize)> = Vec::new();
+
+        // TODO - look up through zone tracking once it exists rather than walking the whole list.
+        npcs.for_each(|npc| {
+            let nid = npc.nid as usize;
+            if nid > BuildArea::MAX_NID || !npc.active() || self.has_npc(nid) {
+                return;
+            }
+
+            if !self.npc_within_view(viewer, npc) {
+                return;
+            }
+
+            let to = npc.coord();
+            let distance = (to.x() as i32 - from.x() as i32).abs().max((to.z() as i32 - from.z() as i32).abs());
+            nearby.push((distance, nid));
+        });
+
+        nearby.sort_unstable();
+        nearby.into_iter().map(|(_, nid)| nid).collect()
+    }
+}
@@ -1,4 +1,3 @@
-use crate::engine::Engine;
 use crate::entity::entity_lifecycle::EntityLifeCycle;
 use crate::entity::non_pathing_entity::NonPathingEntity;
 use crate::entity::npc::NPC;
@@ -28,9 +27,9 @@ pub trait EntityBehavior {
         }
     }
 
-    fn set_lifecycle(&mut self, tick: i32) {
+    fn set_lifecycle(&mut self, tick: i32, current_tick: i32) {
         self.set_lifecycle_tick(tick);
-        self.set_last_lifecycle_tick(Engine::current_tick());
+        self.set_last_lifecycle_tick(current_tick);
     }
 }
 
@@ -1,5 +1,6 @@
 use std::collections::VecDeque;
 use crate::entity::entity::EntityBehavior;
+use crate::entity::entity_type::{NpcHandle, PlayerHandle};
 use crate::entity::npc::NPC;
 use crate::entity::player::Player;
 
@@ -128,6 +129,11 @@ impl<T: EntityBehavior> EntityList<T> {
         self.last_used_index = 0;
     }
 
+    /// Ids in use right now, for processing that runs scripts and can't keep the list borrowed.
+    pub fn ids(&self) -> Vec<usize> {
+        self.iter().map(|(id, _)| id).collect()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
         (0..self.id_to_index.len())
             .filter_map(move |id| {
@@ -174,12 +180,15 @@ impl<T: EntityBehavior> EntityList<T> {
 
 pub struct PlayerList {
     list: EntityList<Player>,
+    /// Uid handed to the next player to enter, so a handle to whoever had the slot before doesn't resolve to them.
+    next_uid: u32,
 }
 
 impl PlayerList {
     pub fn new(size: usize) -> Self {
         PlayerList {
             list: EntityList::new(size, 1),
+            next_uid: 0,
         }
     }
 
@@ -202,6 +211,10 @@ impl PlayerList {
         self.list.count()
     }
 
+    pub fn ids(&self) -> Vec<usize> {
+        self.list.ids()
+    }
+
     pub fn get(&self, id: usize) -> Option<&Player> {
         self.list.get(id)
     }
@@ -210,7 +223,18 @@ impl PlayerList {
         self.list.get_mut(id)
     }
 
-    pub fn set(&mut self, id: usize, entity: Player) -> Result<(), &'static str> {
+    /// The player a handle was taken from, if they're still in the world.
+    pub fn get_by_handle(&self, handle: PlayerHandle) -> Option<&Player> {
+        self.list.get(handle.pid).filter(|player| player.get_uid() == handle.uid)
+    }
+
+    pub fn get_mut_by_handle(&mut self, handle: PlayerHandle) -> Option<&mut Player> {
+        self.list.get_mut(handle.pid).filter(|player| player.get_uid() == handle.uid)
+    }
+
+    pub fn set(&mut self, id: usize, mut entity: Player) -> Result<(), &'static str> {
+        self.next_uid = self.next_uid.wrapping_add(1);
+        entity.set_uid(self.next_uid);
         self.list.set(id, entity)
     }
 
@@ -239,12 +263,15 @@ impl PlayerList {
 
 pub struct NPCList {
     list: EntityList<NPC>,
+    /// Uid handed to the next npc to enter, so a handle to whatever had the slot before doesn't resolve to it.
+    next_uid: u32,
 }
 
 impl NPCList {
     pub fn new(size: usize) -> Self {
         NPCList {
             list: EntityList::new(size, 1),
+            next_uid: 0,
         }
     }
 
@@ -252,6 +279,10 @@ impl NPCList {
         self.list.count()
     }
 
+    pub fn ids(&self) -> Vec<usize> {
+        self.list.ids()
+    }
+
     pub fn get(&self, id: usize) -> Option<&NPC> {
         self.list.get(id)
     }
@@ -260,7 +291,18 @@ impl NPCList {
         self.list.get_mut(id)
     }
 
-    pub fn set(&mut self, id: usize, entity: NPC) -> Result<(), &'static str> {
+    /// The npc a handle was taken from, if it's still in the world.
+    pub fn get_by_handle(&self, handle: NpcHandle) -> Option<&NPC> {
+        self.list.get(handle.nid).filter(|npc| npc.uid == handle.uid)
+    }
+
+    pub fn get_mut_by_handle(&mut self, handle: NpcHandle) -> Option<&mut NPC> {
+        self.list.get_mut(handle.nid).filter(|npc| npc.uid == handle.uid)
+    }
+
+    pub fn set(&mut self, id: usize, mut entity: NPC) -> Result<(), &'static str> {
+        self.next_uid = self.next_uid.wrapping_add(1);
+        entity.uid = self.next_uid;
         self.list.set(id, entity)
     }
 
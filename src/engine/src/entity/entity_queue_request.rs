@@ -1,12 +1,13 @@
-use std::cell::RefCell;
-use std::rc::{Rc, Weak};
+use std::sync::Arc;
 use crate::script::script_file::ScriptFile;
 use crate::script::script_state::ScriptState;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NPCQueueType {
     Normal,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PlayerQueueType {
     Normal,
     Long, // Like normal, but with dev-controlled logout behavior.
@@ -16,63 +17,53 @@ pub enum PlayerQueueType {
     Soft, // Added in OSRS (Only then? Or by 2009?)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum QueueType {
     NPC(NPCQueueType),
     Player(PlayerQueueType),
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum ScriptArgument {
     Number(i32),
     String(String),
 }
 
+#[derive(Clone, PartialEq)]
 pub struct EntityQueueRequest {
-    pub key: u64,
-    pub next: Option<Rc<RefCell<EntityQueueRequest>>>,
-    pub prev: Option<Weak<RefCell<EntityQueueRequest>>>,
-    
     pub queue_type: QueueType,
-    pub script: ScriptFile,
+    pub script: Arc<ScriptFile>,
     pub args: Vec<ScriptArgument>,
     pub delay: i32,
     pub last_int: i32,
+    /// What a long queued script does if the player logs out first, one of the `LOGOUT_` constants.
+    pub logout_action: i32,
 }
 
 impl EntityQueueRequest {
-    pub fn new(queue_type: QueueType, script: ScriptFile, args: Vec<ScriptArgument>, delay: i32) -> Self {
+    /// Drop the script when the player logs out.
+    pub const LOGOUT_DISCARD: i32 = 0;
+    /// Run the script straight away when the player logs out.
+    pub const LOGOUT_ACCELERATE: i32 = 1;
+
+    pub fn new(queue_type: QueueType, script: Arc<ScriptFile>, args: Vec<ScriptArgument>, delay: i32) -> Self {
         EntityQueueRequest {
-            key: 0,
-            next: None,
-            prev: None,
-            
             queue_type,
             script,
             args,
             delay,
             last_int: 0,
+            logout_action: Self::LOGOUT_DISCARD,
         }
     }
-    
-    fn unlink(&mut self) {
-        if let Some(prev) = self.prev.take() {
-            if let Some(prev_strong) = prev.upgrade() {
-                let mut prev_borrowed = prev_strong.borrow_mut();
-                prev_borrowed.next = self.next.take();
-                
-                if let Some(next_ref) = &prev_borrowed.next {
-                    let mut next_borrowed = next_ref.borrow_mut();
-                    next_borrowed.prev = Some(Weak::clone(&Rc::downgrade(&prev_strong)));
-                }
-            }
-        }
+
+    #[inline]
+    pub fn is_type(&self, queue_type: PlayerQueueType) -> bool {
+        self.queue_type == QueueType::Player(queue_type)
     }
 }
 
 pub struct EntityQueueState {
-    pub key: u64,
-    pub next: Option<Rc<RefCell<EntityQueueState>>>,
-    pub prev: Option<Weak<RefCell<EntityQueueState>>>,
-    
     pub script_state: ScriptState,
     pub delay: i32,
 }
@@ -80,26 +71,8 @@ pub struct EntityQueueState {
 impl EntityQueueState {
     pub fn new(script_state: ScriptState, delay: i32) -> Self {
         EntityQueueState {
-            key: 0,
-            next: None,
-            prev: None,
-            
             script_state,
             delay
         }
     }
-
-    fn unlink(&mut self) {
-        if let Some(prev) = self.prev.take() {
-            if let Some(prev_strong) = prev.upgrade() {
-                let mut prev_borrowed = prev_strong.borrow_mut();
-                prev_borrowed.next = self.next.take();
-
-                if let Some(next_ref) = &prev_borrowed.next {
-                    let mut next_borrowed = next_ref.borrow_mut();
-                    next_borrowed.prev = Some(Weak::clone(&Rc::downgrade(&prev_strong)));
-                }
-            }
-        }
-    }
-}
\ No newline at end of file
+}
@@ -1,26 +1,52 @@
+use std::sync::Arc;
 use crate::entity::entity_queue_request::ScriptArgument;
 use crate::script::script_file::ScriptFile;
 
 #[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NPCTimerType {
     NPC,
 }
 
 #[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PlayerTimerType {
     Normal,
     Soft
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TimerType {
     Npc(NPCTimerType),
     Player(PlayerTimerType),
 }
 
+#[derive(Clone, PartialEq)]
 pub struct EntityTimer {
     pub timer_type: TimerType,
-    pub script: ScriptFile,
+    pub script: Arc<ScriptFile>,
     pub args: Option<Vec<ScriptArgument>>,
-    pub interval: f64,
-    pub clock: f64
-}
\ No newline at end of file
+    /// Ticks between each time the timer fires.
+    pub interval: i32,
+    /// Ticks left until it next fires, negative while a due timer is held back.
+    pub clock: i32,
+}
+
+impl EntityTimer {
+    pub fn new(timer_type: TimerType, script: Arc<ScriptFile>, args: Option<Vec<ScriptArgument>>, interval: i32) -> Self {
+        EntityTimer {
+            timer_type,
+            script,
+            args,
+            interval,
+            clock: interval,
+        }
+    }
+
+    /// Count down a tick, returns whether the timer is due.
+    #[inline]
+    pub fn tick(&mut self) -> bool {
+        self.clock -= 1;
+        self.clock <= 0
+    }
+}
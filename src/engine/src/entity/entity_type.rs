@@ -1,12 +1,38 @@
-use crate::entity::loc::Loc;
-use crate::entity::npc::NPC;
-use crate::entity::obj::Obj;
-use crate::entity::player::Player;
+use crate::grid::coord_grid::CoordGrid;
 
-#[derive(Clone, PartialEq)]
+/// Handle to an entity a script is running against, resolved against the engine whenever it's used.
+/// Players and npcs are held by their slot and the uid they took it with, locs and objs by where they are and what they are.
+#[derive(Clone, Copy, PartialEq)]
 pub enum EntityType {
-    Player(Player),
-    NPC(NPC),
-    Loc(Loc),
-    Obj(Obj)
-}
\ No newline at end of file
+    Player(PlayerHandle),
+    NPC(NpcHandle),
+    Loc(LocHandle),
+    Obj(ObjHandle),
+}
+
+/// A slot only refers to the same player while their uid matches, anyone later in the slot gets a new one.
+#[derive(Clone, Copy, PartialEq)]
+pub struct PlayerHandle {
+    pub pid: usize,
+    pub uid: u32,
+}
+
+/// A slot only refers to the same npc while its uid matches, anything later in the slot gets a new one.
+#[derive(Clone, Copy, PartialEq)]
+pub struct NpcHandle {
+    pub nid: usize,
+    pub uid: u32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct LocHandle {
+    pub coord: CoordGrid,
+    /// Packed id, shape and angle, as in [Loc::info](crate::entity::loc::Loc).
+    pub info: u32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct ObjHandle {
+    pub coord: CoordGrid,
+    pub id: u16,
+}
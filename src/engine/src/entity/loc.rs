@@ -1,4 +1,6 @@
 use crate::entity::entity_lifecycle::EntityLifeCycle;
+use crate::entity::entity_type::{EntityType, LocHandle};
+use crate::entity::loc_layer::LocLayer;
 use crate::entity::non_pathing_entity::NonPathingEntity;
 use crate::grid::coord_grid::CoordGrid;
 
@@ -42,4 +44,12 @@ impl Loc {
     pub fn angle(&self) -> u8 {
         ((self.info >> 19) & 0x3) as u8
     }
+
+    pub fn layer(&self) -> LocLayer {
+        LocLayer::from_shape(self.shape())
+    }
+
+    pub fn as_entity_type(&self) -> EntityType {
+        EntityType::Loc(LocHandle { coord: self.entity.entity.coord, info: self.info })
+    }
 }
\ No newline at end of file
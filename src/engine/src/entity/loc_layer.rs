@@ -0,0 +1,20 @@
+/// Which slot on a tile a loc occupies, worked out from its shape.
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq)]
+pub enum LocLayer {
+    Wall = 0,
+    WallDecor = 1,
+    Ground = 2,
+    GroundDecor = 3,
+}
+
+impl LocLayer {
+    pub fn from_shape(shape: u8) -> LocLayer {
+        match shape {
+            0..=3 => LocLayer::Wall,
+            4..=8 => LocLayer::WallDecor,
+            9..=21 => LocLayer::Ground,
+            _ => LocLayer::GroundDecor,
+        }
+    }
+}
@@ -1,13 +1,22 @@
 pub mod block_walk;
+pub mod build_area;
 pub mod entity;
 pub mod entity_lifecycle;
 pub mod loc;
+pub mod loc_layer;
 pub mod move_restrict;
 pub mod move_speed;
 pub mod move_strategy;
 pub mod npc;
 pub mod obj;
 pub mod player;
+pub mod player_save;
+#[cfg(test)]
+mod npc_tests;
+#[cfg(test)]
+mod player_save_tests;
+#[cfg(test)]
+mod player_tests;
 
 mod hunt;
 mod npc_iterator_type;
@@ -24,6 +33,6 @@ pub mod entity_queue_request;
 mod non_pathing_entity;
 mod pathing_entity;
 pub mod entity_type;
-mod player_type;
+pub mod player_type;
 mod level_experience;
-mod entity_timer;
\ No newline at end of file
+pub mod entity_timer;
\ No newline at end of file
@@ -1,3 +1,5 @@
+use rsmod_pathfinder::CollisionType;
+
 #[repr(u8)]
 #[derive(Copy, Clone, PartialEq)]
 pub enum MoveRestrict {
@@ -8,4 +10,18 @@ pub enum MoveRestrict {
     Outdoors = 4,
     NoMove = 5,
     PassThru = 6,
-}
\ No newline at end of file
+}
+
+impl MoveRestrict {
+    /// Collision the pathfinder should check for this restriction, none if the entity can't move at all.
+    pub fn collision_type(&self) -> Option<CollisionType> {
+        match self {
+            MoveRestrict::Normal | MoveRestrict::PassThru => Some(CollisionType::NORMAL),
+            MoveRestrict::Blocked => Some(CollisionType::BLOCKED),
+            MoveRestrict::BlockedNormal => Some(CollisionType::LINE_OF_SIGHT),
+            MoveRestrict::Indoors => Some(CollisionType::INDOORS),
+            MoveRestrict::Outdoors => Some(CollisionType::OUTDOORS),
+            MoveRestrict::NoMove => None,
+        }
+    }
+}
@@ -1,11 +1,21 @@
+use std::sync::Arc;
+use crate::engine::Engine;
 use crate::entity::block_walk::BlockWalk;
-use crate::entity::entity::Entity;
+use crate::entity::entity::{Entity, EntityBehavior};
 use crate::entity::entity_lifecycle::EntityLifeCycle;
-use crate::entity::entity_type::EntityType;
+use crate::entity::entity_queue_request::{EntityQueueRequest, NPCQueueType, QueueType};
+use crate::entity::entity_timer::{EntityTimer, NPCTimerType, TimerType};
+use crate::entity::entity_type::{EntityType, NpcHandle};
 use crate::entity::move_restrict::MoveRestrict;
+use crate::entity::move_speed::MoveSpeed;
 use crate::entity::move_strategy::MoveStrategy;
 use crate::entity::pathing_entity::PathingEntity;
 use crate::grid::coord_grid::CoordGrid;
+use crate::io::server::protocol::info_protocol::InfoProtocol;
+use crate::script::script_file::ScriptFile;
+use crate::script::script_runner::ScriptRunner;
+use crate::script::script_state::ScriptState;
+use crate::util::cache::config::var_npc_type::VarNpcType;
 
 #[derive(Clone, PartialEq)]
 pub struct NPC {
@@ -14,7 +24,18 @@ pub struct NPC {
     pub block_walk: BlockWalk,
     pub move_strategy: MoveStrategy,
     pub nid: i32,
+    /// Given by the [NPCList](crate::entity::entity_list::NPCList) on entering the world, tells this npc apart from later ones in the slot.
+    pub uid: u32,
     pub id: u16, // Cache 'ID'
+    pub base_id: u16, // Cache 'ID' before any transmog
+    /// Npc variables, indexed by varn id.
+    pub vars: Vec<i32>,
+    /// Script waiting on this npc's delay to pass.
+    pub active_script: Option<Box<ScriptState>>,
+    /// Queued [ai_queue] scripts, in the order they were queued.
+    pub queue: Vec<EntityQueueRequest>,
+    /// Npcs only have the one timer, running their [ai_timer].
+    pub timer: Option<EntityTimer>,
 }
 
 impl NPC {
@@ -25,20 +46,176 @@ impl NPC {
                 width,
                 length,
                 lifecycle,
+                MoveSpeed::INSTANT,
             ),
             move_restrict,
             block_walk,
             move_strategy: MoveStrategy::Naive,
             nid,
+            uid: 0,
             id,
+            base_id: id,
+            vars: vec![0; VarNpcType::count()],
+            active_script: None,
+            queue: Vec::new(),
+            timer: None,
         }
     }
+
+    pub fn play_animation(&mut self, anim: i32, delay: i32) {
+        self.pathing_entity.anim_id = anim;
+        self.pathing_entity.anim_delay = delay;
+        self.pathing_entity.masks |= InfoProtocol::NPC_ANIM.id() as u32;
+    }
+
+    pub fn spotanim(&mut self, spotanim: i32, height: i32, delay: i32) {
+        self.pathing_entity.spotanim_id = spotanim;
+        self.pathing_entity.spotanim_height = height;
+        self.pathing_entity.spotanim_delay = delay;
+        self.pathing_entity.masks |= InfoProtocol::NPC_SPOTANIM.id() as u32;
+    }
+
+    /// Face another npc by its nid, or a player by its pid offset by 32768. -1 to stop facing.
+    pub fn face_entity(&mut self, target: i32) {
+        self.pathing_entity.face_entity = target;
+        self.pathing_entity.masks |= InfoProtocol::NPC_FACE_ENTITY.id() as u32;
+    }
+
+    pub fn face_square(&mut self, x: i32, z: i32) {
+        self.pathing_entity.face_x = x;
+        self.pathing_entity.face_z = z;
+        self.pathing_entity.masks |= InfoProtocol::NPC_FACE_COORD.id() as u32;
+    }
+
+    pub fn say(&mut self, message: String) {
+        self.pathing_entity.say = Some(message);
+        self.pathing_entity.masks |= InfoProtocol::NPC_SAY.id() as u32;
+    }
+
+    /// Show as a different npc type, `base_id` is kept to revert to.
+    pub fn change_type(&mut self, id: u16) {
+        self.id = id;
+        self.pathing_entity.masks |= InfoProtocol::NPC_CHANGE_TYPE.id() as u32;
+    }
+
+    pub fn revert_type(&mut self) {
+        if self.id != self.base_id {
+            self.change_type(self.base_id);
+        }
+    }
+
+    /// Two hitsplats fit in a single tick, any more are dropped.
+    pub fn apply_damage(&mut self, damage: i32, damage_type: i32, current_hitpoints: i32, base_hitpoints: i32) {
+        let hit_masks = (InfoProtocol::NPC_HIT.id() as u32, InfoProtocol::NPC_HIT2.id() as u32);
+        self.pathing_entity.apply_damage(damage, damage_type, current_hitpoints, base_hitpoints, hit_masks);
+    }
+
+    #[inline]
+    pub fn get_var(&self, id: usize) -> i32 {
+        self.vars.get(id).copied().unwrap_or(0)
+    }
+
+    pub fn set_var(&mut self, id: usize, value: i32) {
+        if self.vars.len() <= id {
+            self.vars.resize(id + 1, 0);
+        }
+        self.vars[id] = value;
+    }
+
+    /// Back to how it was first spawned, nothing a previous life set on it carries over.
+    pub fn respawn(&mut self) {
+        self.id = self.base_id;
+        self.vars = vec![0; VarNpcType::count()];
+        self.active_script = None;
+        self.queue.clear();
+        self.timer = None;
+    }
+
+    /// A dead [EntityLifeCycle::RESPAWN] npc stays out of the world until its lifecycle tick has passed,
+    /// then comes back through [NPC::respawn]. Returns whether the npc takes part in this tick.
+    pub fn process_respawn(&mut self, current_tick: i32) -> bool {
+        if self.lifecycle() != EntityLifeCycle::RESPAWN || self.active() {
+            return true;
+        }
+        if !self.check_lifecycle(current_tick) {
+            return false;
+        }
+        self.respawn();
+        self.set_active(true);
+        true
+    }
+
+    /// Fire the [ai_timer] every `interval` ticks, an interval of 0 stops it.
+    pub fn set_timer(&mut self, script: Option<Arc<ScriptFile>>, interval: i32) {
+        self.timer = script
+            .filter(|_| interval > 0)
+            .map(|script| EntityTimer::new(TimerType::Npc(NPCTimerType::NPC), script, None, interval));
+    }
+
+    /// The timer keeps counting while the npc is delayed, but only fires once it isn't.
+    pub fn process_timers(engine: &mut Engine, nid: usize) {
+        let Some(npc) = engine.npcs.get_mut(nid) else {
+            return;
+        };
+        let delayed = npc.pathing_entity.delayed;
+        let self_entity = npc.as_entity_type();
+        let Some(timer) = npc.timer.as_mut() else {
+            return;
+        };
+
+        if !timer.tick() || delayed {
+            return;
+        }
+
+        timer.clock = timer.interval;
+        let script = ScriptRunner::init(timer.script.clone(), Some(self_entity), None, None);
+        engine.execute_script(script);
+    }
+
+    /// Queue a script, `arg` is handed to it as its last_int.
+    pub fn enqueue_script(&mut self, script: Arc<ScriptFile>, delay: i32, arg: i32) {
+        let mut request = EntityQueueRequest::new(QueueType::NPC(NPCQueueType::Normal), script, Vec::new(), delay);
+        request.last_int = arg;
+        self.queue.push(request);
+    }
+
+    /// Run queued scripts whose delay is up, nothing counts down while the npc is delayed.
+    pub fn process_queue(engine: &mut Engine, nid: usize) {
+        let mut index = 0;
+        loop {
+            let Some(npc) = engine.npcs.get_mut(nid) else {
+                return;
+            };
+            if index >= npc.queue.len() || npc.pathing_entity.delayed {
+                return;
+            }
+
+            npc.queue[index].delay -= 1;
+            if npc.queue[index].delay >= 0 {
+                index += 1;
+                continue;
+            }
+
+            let request = npc.queue.remove(index);
+            let mut script = ScriptRunner::init(request.script, Some(npc.as_entity_type()), None, Some(request.args));
+            script.last_int = request.last_int;
+            engine.execute_script(script);
+        }
+    }
+
+    pub fn reset_entity(&mut self) {
+        self.pathing_entity.reset_pathing_entity();
+    }
     
     pub fn get_entity(&self) -> &Entity {
         &self.pathing_entity.entity
     }
     
-    pub fn into_entity_type(self) -> EntityType {
-        EntityType::NPC(self)
+    pub fn as_entity_type(&self) -> EntityType {
+        EntityType::NPC(self.handle())
+    }
+
+    pub fn handle(&self) -> NpcHandle {
+        NpcHandle { nid: self.nid as usize, uid: self.uid }
     }
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::cell::{Ref, RefCell};
 use std::rc::Rc;
 use crate::entity::npc::NPC;
@@ -12,7 +13,7 @@ pub enum NpcEventType {
 pub struct NpcEventRequest {
     pub npc_event_type: NpcEventType,
     
-    pub script: ScriptFile,
+    pub script: Arc<ScriptFile>,
     
     pub npc: NPC,
     
@@ -20,7 +21,7 @@ pub struct NpcEventRequest {
 }
 
 impl NpcEventRequest {
-    pub fn new(npc_event_type: NpcEventType, script: ScriptFile, npc: NPC, ) -> Self {
+    pub fn new(npc_event_type: NpcEventType, script: Arc<ScriptFile>, npc: NPC, ) -> Self {
         NpcEventRequest {
             npc_event_type,
             script,
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use crate::engine::Engine;
+use crate::entity::block_walk::BlockWalk;
+use crate::entity::entity::EntityBehavior;
+use crate::entity::entity_lifecycle::EntityLifeCycle;
+use crate::entity::move_restrict::MoveRestrict;
+use crate::entity::move_speed::MoveSpeed;
+use crate::entity::npc::NPC;
+use crate::grid::coord_grid::CoordGrid;
+use crate::script::script_file::ScriptFile;
+
+fn npc() -> NPC {
+    npc_with(EntityLifeCycle::FOREVER)
+}
+
+/// A script that finishes as soon as it runs, so anything left in the queue hasn't run yet.
+fn script(id: i32) -> Arc<ScriptFile> {
+    let mut script = ScriptFile::new(id);
+    script.info.lookup_key = 0;
+    Arc::new(script)
+}
+
+const NID: usize = 1;
+
+/// A world of its own with just the one npc in it.
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.npcs.set(NID, npc()).unwrap();
+    engine
+}
+
+fn npc_with(lifecycle: EntityLifeCycle) -> NPC {
+    NPC::new(CoordGrid::from(3222, 0, 3218), 1, 1, lifecycle, 0, 0, MoveRestrict::Normal, BlockWalk::Npc)
+}
+
+#[test]
+fn test_npc_moves_instantly_by_default() {
+    assert!(matches!(npc().pathing_entity.move_speed(), MoveSpeed::INSTANT));
+}
+
+#[test]
+fn test_dead_npc_waits_out_respawn() {
+    let mut npc = npc_with(EntityLifeCycle::RESPAWN);
+    npc.set_lifecycle(10, 0);
+    assert!(!npc.process_respawn(10));
+    assert!(!npc.active());
+}
+
+#[test]
+fn test_respawn_resets_npc() {
+    let mut npc = npc_with(EntityLifeCycle::RESPAWN);
+    npc.id = 5;
+    npc.set_var(3, 7);
+    npc.set_lifecycle(10, 0);
+
+    assert!(npc.process_respawn(11));
+    assert!(npc.active());
+    assert_eq!(npc.id, npc.base_id);
+    assert_eq!(npc.get_var(3), 0);
+}
+
+#[test]
+fn test_forever_npc_is_never_respawned() {
+    let mut npc = npc();
+    npc.set_var(3, 7);
+    assert!(npc.process_respawn(1));
+    assert_eq!(npc.get_var(3), 7);
+}
+
+#[test]
+fn test_queue_fires_on_exact_tick() {
+    let mut engine = engine();
+    engine.npcs.get_mut(NID).unwrap().enqueue_script(script(1), 2, 0);
+
+    NPC::process_queue(&mut engine, NID);
+    NPC::process_queue(&mut engine, NID);
+    assert_eq!(engine.npcs.get(NID).unwrap().queue.len(), 1);
+    NPC::process_queue(&mut engine, NID);
+    assert!(engine.npcs.get(NID).unwrap().queue.is_empty());
+}
+
+#[test]
+fn test_queue_without_delay_fires_next_tick() {
+    let mut engine = engine();
+    engine.npcs.get_mut(NID).unwrap().enqueue_script(script(1), 0, 0);
+
+    NPC::process_queue(&mut engine, NID);
+    assert!(engine.npcs.get(NID).unwrap().queue.is_empty());
+}
+
+#[test]
+fn test_health_ratio() {
+    let mut npc = npc();
+    assert_eq!(npc.pathing_entity.health_ratio(), 0);
+    npc.apply_damage(5, 1, 5, 10);
+    assert_eq!(npc.pathing_entity.health_ratio(), 127);
+    npc.apply_damage(0, 0, 20, 10);
+    assert_eq!(npc.pathing_entity.health_ratio(), 255);
+}
+
+#[test]
+fn test_third_hit_in_a_tick_is_dropped() {
+    let mut npc = npc();
+    npc.apply_damage(1, 1, 9, 10);
+    npc.apply_damage(2, 1, 7, 10);
+    npc.apply_damage(3, 1, 4, 10);
+
+    let entity = &npc.pathing_entity;
+    assert_eq!((entity.damage_taken, entity.damage_taken2), (1, 2));
+    assert_eq!(entity.health_ratio(), 4 * 255 / 10);
+}
@@ -1,5 +1,6 @@
 use crate::entity::entity::Entity;
 use crate::entity::entity_lifecycle::EntityLifeCycle;
+use crate::entity::entity_type::{EntityType, ObjHandle};
 use crate::grid::coord_grid::CoordGrid;
 
 #[derive(Clone, PartialEq)]
@@ -31,4 +32,8 @@ impl Obj {
             last_change: -1,
         }
     }
+
+    pub fn as_entity_type(&self) -> EntityType {
+        EntityType::Obj(ObjHandle { coord: self.entity.coord, id: self.id })
+    }
 }
\ No newline at end of file
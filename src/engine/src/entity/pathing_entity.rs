@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use crate::entity::block_walk::BlockWalk;
 use crate::entity::entity::Entity;
 use crate::entity::entity_lifecycle::EntityLifeCycle;
@@ -5,8 +6,10 @@ use crate::entity::move_restrict::MoveRestrict;
 use crate::entity::move_speed::MoveSpeed;
 use crate::entity::move_strategy::MoveStrategy;
 use crate::entity::npc_mode::NpcMode;
+use crate::game_map::GameMap;
 use crate::grid::coord_grid::CoordGrid;
 use crate::script::server_trigger_types::ServerTriggerTypes;
+use rsmod_pathfinder::CollisionType;
 
 pub struct TargetSubject {
     pub type_: u32,
@@ -18,7 +21,7 @@ pub enum TargetOp {
     Mode(NpcMode)
 }
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct PathingEntity {
     // Constructor properties
     pub entity: Entity,
@@ -32,15 +35,282 @@ pub struct PathingEntity {
     move_speed: MoveSpeed,
     pub(crate) delayed: bool,
     pub(crate) delayed_until: i32,
+    /// Route being walked, the next waypoint first.
+    pub(crate) waypoints: VecDeque<CoordGrid>,
+    last_crawl: bool,
+
+    // Info properties, reset at the end of every tick.
+    pub(crate) walk_dir: i8,
+    pub(crate) run_dir: i8,
+    pub(crate) tele: bool,
+    pub(crate) jump: bool,
+    pub(crate) last_coord: CoordGrid,
+    pub(crate) orientation: i8,
+    pub(crate) masks: u32,
+    pub(crate) anim_id: i32,
+    pub(crate) anim_delay: i32,
+    pub(crate) spotanim_id: i32,
+    pub(crate) spotanim_height: i32,
+    pub(crate) spotanim_delay: i32,
+    pub(crate) face_entity: i32,
+    pub(crate) face_x: i32,
+    pub(crate) face_z: i32,
+    pub(crate) say: Option<String>,
+    pub(crate) damage_taken: i32,
+    pub(crate) damage_type: i32,
+    pub(crate) damage_taken2: i32,
+    pub(crate) damage_type2: i32,
+    pub(crate) current_hitpoints: i32,
+    pub(crate) base_hitpoints: i32,
 }
 
 impl PathingEntity {
-    pub fn new(coord: CoordGrid, width: u8, length: u8, lifecycle: EntityLifeCycle) -> Self {
+    pub fn new(coord: CoordGrid, width: u8, length: u8, lifecycle: EntityLifeCycle, move_speed: MoveSpeed) -> Self {
         PathingEntity {
             entity: Entity::new(coord, width, length, lifecycle),
-            move_speed: MoveSpeed::INSTANT,
+            move_speed,
             delayed: false,
             delayed_until: -1,
+            waypoints: VecDeque::new(),
+            last_crawl: false,
+            walk_dir: -1,
+            run_dir: -1,
+            tele: false,
+            jump: false,
+            last_coord: coord,
+            orientation: PathingEntity::DEFAULT_ORIENTATION,
+            masks: 0,
+            anim_id: -1,
+            anim_delay: 0,
+            spotanim_id: -1,
+            spotanim_height: 0,
+            spotanim_delay: 0,
+            face_entity: -1,
+            face_x: -1,
+            face_z: -1,
+            say: None,
+            damage_taken: -1,
+            damage_type: -1,
+            damage_taken2: -1,
+            damage_type2: -1,
+            current_hitpoints: 0,
+            base_hitpoints: 0,
+        }
+    }
+
+    /// Facing south.
+    pub const DEFAULT_ORIENTATION: i8 = 6;
+
+    /// Direction of a single step as sent in the info packets, -1 if the delta is not a single step.
+    pub fn direction(dx: i32, dz: i32) -> i8 {
+        match (dx, dz) {
+            (-1, 1) => 0,
+            (0, 1) => 1,
+            (1, 1) => 2,
+            (-1, 0) => 3,
+            (1, 0) => 4,
+            (-1, -1) => 5,
+            (0, -1) => 6,
+            (1, -1) => 7,
+            _ => -1,
+        }
+    }
+
+    /// Place the entity on a new coord without walking there.
+    pub fn teleport(&mut self, coord: CoordGrid, jump: bool) {
+        self.entity.coord = coord;
+        self.tele = true;
+        self.jump = jump;
+    }
+
+    #[inline]
+    pub fn move_speed(&self) -> MoveSpeed {
+        self.move_speed
+    }
+
+    #[inline]
+    pub fn set_move_speed(&mut self, move_speed: MoveSpeed) {
+        self.move_speed = move_speed;
+    }
+
+    #[inline]
+    pub fn has_waypoints(&self) -> bool {
+        !self.waypoints.is_empty()
+    }
+
+    pub fn queue_waypoints(&mut self, waypoints: Vec<CoordGrid>) {
+        self.waypoints.clear();
+        self.waypoints.extend(waypoints);
+    }
+
+    pub fn clear_waypoints(&mut self) {
+        self.waypoints.clear();
+    }
+
+    /// Hold the entity in place for `ticks` after the current one.
+    pub fn delay(&mut self, current_tick: i32, ticks: i32) {
+        self.delayed = true;
+        self.delayed_until = current_tick + 1 + ticks;
+    }
+
+    /// Lift the delay once its tick has come, returns whether the entity is still delayed.
+    pub fn update_delay(&mut self, current_tick: i32) -> bool {
+        if self.delayed && current_tick >= self.delayed_until {
+            self.delayed = false;
         }
+        self.delayed
+    }
+
+    /// Take this tick's steps along the queued waypoints, returns whether the entity moved.
+    pub fn update_movement(&mut self, collision: CollisionType) -> bool {
+        if self.delayed || self.waypoints.is_empty() {
+            return false;
+        }
+
+        match self.move_speed {
+            MoveSpeed::STATIONARY => return false,
+            MoveSpeed::CRAWL => {
+                // One step every other tick.
+                self.last_crawl = !self.last_crawl;
+                if self.last_crawl {
+                    self.walk_dir = self.step(collision);
+                }
+            },
+            MoveSpeed::WALK => {
+                self.walk_dir = self.step(collision);
+            },
+            MoveSpeed::RUN => {
+                self.walk_dir = self.step(collision);
+                if self.walk_dir != -1 {
+                    self.run_dir = self.step(collision);
+                }
+            },
+            MoveSpeed::INSTANT => {
+                if let Some(dest) = self.waypoints.back().copied() {
+                    self.waypoints.clear();
+                    self.teleport(dest, false);
+                }
+            },
+        }
+
+        self.entity.coord != self.last_coord
+    }
+
+    /// Take a single step towards the next waypoint, dropping the route if the step is blocked.
+    fn step(&mut self, collision: CollisionType) -> i8 {
+        let coord = self.entity.coord;
+
+        let dest = loop {
+            match self.waypoints.front() {
+                Some(dest) if *dest == coord => {
+                    self.waypoints.pop_front();
+                },
+                Some(dest) => break *dest,
+                None => return -1,
+            }
+        };
+
+        let dx = (dest.x() as i32 - coord.x() as i32).signum();
+        let dz = (dest.z() as i32 - coord.z() as i32).signum();
+
+        if !GameMap::can_travel(coord, dx, dz, self.entity.width, collision) {
+            self.waypoints.clear();
+            return -1;
+        }
+
+        let next = CoordGrid::from((coord.x() as i32 + dx) as u16, coord.y(), (coord.z() as i32 + dz) as u16);
+        self.entity.coord = next;
+        if next == dest {
+            self.waypoints.pop_front();
+        }
+
+        PathingEntity::direction(dx, dz)
+    }
+
+    /// Route towards an area of the given size, moving as close as possible if it can't be reached.
+    pub fn find_path(&self, dest: CoordGrid, dest_width: u8, dest_length: u8, angle: u8, shape: i8, block_access_flags: u8, collision: CollisionType) -> Vec<CoordGrid> {
+        GameMap::find_path(
+            self.entity.coord,
+            dest,
+            self.entity.width,
+            dest_width,
+            dest_length,
+            angle,
+            shape,
+            true,
+            block_access_flags,
+            collision,
+        )
+    }
+
+    #[inline]
+    pub fn has_line_of_sight(&self, dest: CoordGrid, dest_width: u8, dest_length: u8) -> bool {
+        GameMap::has_line_of_sight(self.entity.coord, dest, self.entity.width, self.entity.length, dest_width, dest_length)
+    }
+
+    #[inline]
+    pub fn has_line_of_walk(&self, dest: CoordGrid, dest_width: u8, dest_length: u8) -> bool {
+        GameMap::has_line_of_walk(self.entity.coord, dest, self.entity.width, self.entity.length, dest_width, dest_length)
+    }
+
+    /// Reconcile the steps taken this tick with where the entity ended up.
+    /// Anything that moved without taking steps, or changed level, is sent as a teleport.
+    pub fn convert_movement_dir(&mut self) {
+        let coord = self.entity.coord;
+        let moved = coord != self.last_coord;
+
+        if moved && (self.walk_dir == -1 || coord.y() != self.last_coord.y()) {
+            self.tele = true;
+        }
+
+        if self.tele {
+            self.walk_dir = -1;
+            self.run_dir = -1;
+        } else if self.run_dir != -1 {
+            self.orientation = self.run_dir;
+        } else if self.walk_dir != -1 {
+            self.orientation = self.walk_dir;
+        }
+    }
+
+    /// Hitpoints scaled to 0-255, how the client sizes the health bar.
+    pub fn health_ratio(&self) -> i32 {
+        if self.base_hitpoints <= 0 {
+            return 0;
+        }
+
+        (self.current_hitpoints * 255 / self.base_hitpoints).clamp(0, 255)
+    }
+
+    /// Two hitsplats fit in a single tick, any more are dropped.
+    /// `hit_masks` are the first and second hit masks of the entity's info protocol.
+    pub(crate) fn apply_damage(&mut self, damage: i32, damage_type: i32, current_hitpoints: i32, base_hitpoints: i32, hit_masks: (u32, u32)) {
+        self.current_hitpoints = current_hitpoints;
+        self.base_hitpoints = base_hitpoints;
+
+        if self.masks & hit_masks.0 == 0 {
+            self.damage_taken = damage;
+            self.damage_type = damage_type;
+            self.masks |= hit_masks.0;
+        } else if self.masks & hit_masks.1 == 0 {
+            self.damage_taken2 = damage;
+            self.damage_type2 = damage_type;
+            self.masks |= hit_masks.1;
+        }
+    }
+
+    /// Clears the per tick movement and info state once it has been sent out.
+    pub fn reset_pathing_entity(&mut self) {
+        self.walk_dir = -1;
+        self.run_dir = -1;
+        self.tele = false;
+        self.jump = false;
+        self.last_coord = self.entity.coord;
+        self.masks = 0;
+        self.say = None;
+        self.damage_taken = -1;
+        self.damage_type = -1;
+        self.damage_taken2 = -1;
+        self.damage_type2 = -1;
     }
 }
\ No newline at end of file
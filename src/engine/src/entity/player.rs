@@ -1,16 +1,23 @@
 use std::cmp::PartialEq;
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::sync::Arc;
 use std::time::Instant;
+use crate::engine::Engine;
 use crate::entity::block_walk::BlockWalk;
+use crate::entity::build_area::BuildArea;
 use crate::entity::entity::{Entity, EntityBehavior};
 use crate::entity::entity_lifecycle::EntityLifeCycle;
+use crate::entity::entity_queue_request::{EntityQueueRequest, PlayerQueueType, QueueType, ScriptArgument};
+use crate::entity::entity_timer::{EntityTimer, PlayerTimerType, TimerType};
 use crate::entity::move_restrict::MoveRestrict;
+use crate::entity::move_speed::MoveSpeed;
 use crate::entity::move_strategy::MoveStrategy;
 use crate::entity::window_status::WindowStatus;
 use crate::grid::coord_grid::CoordGrid;
 use constants::window_mode::window_mode;
 use log::{debug, error, trace};
-use crate::entity::entity_type::EntityType;
+use crate::entity::entity_type::{EntityType, PlayerHandle};
 use crate::entity::pathing_entity::PathingEntity;
 use crate::entity::player_type::PlayerType;
 use crate::game_connection::GameClient;
@@ -21,14 +28,19 @@ use crate::io::server::model::if_opensub::If_OpenSub;
 use crate::io::server::model::if_opentop::If_OpenTop;
 use crate::io::server::model::logout::Logout;
 use crate::io::server::model::rebuild_normal::RebuildNormal;
+use crate::io::server::model::varp_large::VarpLarge;
+use crate::io::server::model::varp_small::VarpSmall;
 use crate::io::server::outgoing_message::{OutgoingMessage, OutgoingMessageEnum};
+use crate::io::server::protocol::info_protocol::InfoProtocol;
 use crate::io::server::protocol::server_protocol_priority::ServerProtocolPriority;
 use crate::io::server::protocol::server_protocol_repository::{ServerProtocolRepository, SERVER_PROTOCOL_REPOSITORY};
+use crate::script::script_file::ScriptFile;
 use crate::script::script_pointer::ScriptPointer;
-use crate::script::script_provider::ScriptProvider;
 use crate::script::script_runner::ScriptRunner;
 use crate::script::script_state::ScriptState;
-use crate::script::server_trigger_types::ServerTriggerTypes;
+use crate::util::bits::clear_bit_range;
+use crate::util::cache::config::var_bit_type::VarBitType;
+use crate::util::cache::config::varp_type::VarpType;
 
 #[derive(Clone, PartialEq)]
 pub struct Player {
@@ -44,12 +56,16 @@ pub struct Player {
     pub playtime: i32,
     
     pid: usize,
+    /// Given by the [PlayerList](crate::entity::entity_list::PlayerList) on entering the world, tells this player apart from later ones in the slot.
+    uid: u32,
     pub username: String,
     
     pub origin_coord: CoordGrid,
     
     // Client data
     pub client: GameClient,
+    /// Logged in with the reconnect title protocol, expecting to pick up an existing session.
+    pub reconnect: bool,
     /// User packet limit
     pub user_limit: u8,
     /// Client packet limit
@@ -60,6 +76,10 @@ pub struct Player {
 
     pub user_path: Vec<i32>,
     pub op_called: bool,
+    /// Run toggled on in the settings.
+    pub run: bool,
+    /// Ctrl was held for the current walk, which inverts [Player::run] until it finishes.
+    pub temp_run: bool,
     pub bytes_read: usize,
 
 
@@ -72,14 +92,42 @@ pub struct Player {
     pub logging_out: bool,
     pub prevent_logout_until: i32,
     
+    pub initial_connect_tick: i32,
     pub last_response: i32,
     pub last_connected: i32,
     pub verify_id: u16,
     
     pub protect: bool,  // Whether protected access is available.
     pub active_script: Option<Box<ScriptState>>,
+    /// Script a dialog answer came in for this tick, resumed by the engine once packets have been read.
+    pub resumed_script: Option<Box<ScriptState>>,
+    /// Normal, long and strong queued scripts, in the order they were queued.
+    pub queue: Vec<EntityQueueRequest>,
+    /// Weak queued scripts, dropped by anything that interrupts the player.
+    pub weak_queue: Vec<EntityQueueRequest>,
+    /// Scripts queued by the engine rather than content.
+    pub engine_queue: Vec<EntityQueueRequest>,
+    /// Timers and soft timers by script id, ordered so timers due on the same tick always fire in the same order.
+    pub timers: BTreeMap<i32, EntityTimer>,
+    /// Player variables, indexed by varp id.
+    pub varps: Vec<i32>,
+
+    // Info
+    pub build_area: BuildArea,
+    pub body: [i32; 7],
+    pub colors: [i32; 5],
+    pub combat_level: u8,
+    pub last_appearance: i32,
+    pub message_color: i32,
+    pub message_effect: i32,
+    pub message_type: i32,
+    pub message: Option<Vec<u8>>,
 }
 impl Player {
+    /// Identity kits for hair, jaw, torso, arms, hands, legs and feet.
+    const DEFAULT_BODY_MALE: [i32; 7] = [0, 10, 18, 26, 33, 36, 42];
+    const DEFAULT_BODY_FEMALE: [i32; 7] = [45, -1, 56, 61, 67, 70, 79];
+
     pub fn new(client: &mut Option<GameClient>, coord: CoordGrid, gender: u8, window_status: WindowStatus, staff_mod_level: i32, pid: usize, verify_id: u16, username: String) -> Player {
         Player {
             player_type: PlayerType::ClientBound,
@@ -87,7 +135,8 @@ impl Player {
                 coord,
                 1,
                 1,
-                EntityLifeCycle::FOREVER
+                EntityLifeCycle::FOREVER,
+                MoveSpeed::WALK
             ),
             move_restrict: MoveRestrict::Normal,
             block_walk: BlockWalk::Npc,
@@ -95,27 +144,47 @@ impl Player {
             gender,
             playtime: -1,
             pid,
+            uid: 0,
             username,
             origin_coord: CoordGrid { coord: 0 },
             staff_mod_level,
             client: GameClient::take_ownership(client),
+            reconnect: false,
             user_limit: 0,
             client_limit: 0,
             restricted_limit: 0,
             outgoing_messages: Vec::new(),
             user_path: Vec::new(),
             op_called: false,
+            run: false,
+            temp_run: false,
             bytes_read: 0,
             window_status,
             request_logout: false,
             request_idle_logout: false,
             logging_out: false,
             prevent_logout_until: -1,
+            initial_connect_tick: -1,
             last_response: -1,
             last_connected: -1,
             verify_id,
             protect: false,
             active_script: None,
+            resumed_script: None,
+            queue: Vec::new(),
+            weak_queue: Vec::new(),
+            engine_queue: Vec::new(),
+            timers: BTreeMap::new(),
+            varps: vec![0; VarpType::count()],
+            build_area: BuildArea::new(),
+            body: Player::default_body(gender),
+            colors: [0; 5],
+            combat_level: 3,
+            last_appearance: -1,
+            message_color: 0,
+            message_effect: 0,
+            message_type: 0,
+            message: None,
         }
     }
     
@@ -126,7 +195,8 @@ impl Player {
               coord,
               1,
               1,
-              EntityLifeCycle::FOREVER
+              EntityLifeCycle::FOREVER,
+              MoveSpeed::WALK
             ),
             move_restrict: MoveRestrict::Normal,
             block_walk: BlockWalk::Npc,
@@ -134,27 +204,47 @@ impl Player {
             gender,
             playtime: -1,
             pid,
+            uid: 0,
             username: format!("dummy_{:?}", pid),
             origin_coord: CoordGrid { coord: 0 },
             staff_mod_level: 0,
             client: GameClient::new_dummy(),
+            reconnect: false,
             user_limit: 0,
             client_limit: 0,
             restricted_limit: 0,
             outgoing_messages: Vec::new(),
             user_path: Vec::new(),
             op_called: false,
+            run: false,
+            temp_run: false,
             bytes_read: 0,
             window_status: WindowStatus { window_mode: window_mode::NULL, canvas_width: 0, canvas_height: 0, anti_aliasing_mode: 0 },
             request_logout: false,
             request_idle_logout: false,
             logging_out: false,
             prevent_logout_until: -1,
+            initial_connect_tick: -1,
             last_response: -1,
             last_connected: -1,
             verify_id: 0,
             protect: false,
             active_script: None,
+            resumed_script: None,
+            queue: Vec::new(),
+            weak_queue: Vec::new(),
+            engine_queue: Vec::new(),
+            timers: BTreeMap::new(),
+            varps: vec![0; VarpType::count()],
+            build_area: BuildArea::new(),
+            body: Player::default_body(gender),
+            colors: [0; 5],
+            combat_level: 3,
+            last_appearance: -1,
+            message_color: 0,
+            message_effect: 0,
+            message_type: 0,
+            message: None,
         }
     }
 
@@ -171,8 +261,12 @@ impl Player {
         &self.pathing_entity.entity
     }
 
-    pub fn as_entity_type(self) -> EntityType {
-        EntityType::Player(self)
+    pub fn as_entity_type(&self) -> EntityType {
+        EntityType::Player(self.handle())
+    }
+
+    pub fn handle(&self) -> PlayerHandle {
+        PlayerHandle { pid: self.pid, uid: self.uid }
     }
     
     pub(crate) fn get_coord(&self) -> CoordGrid {
@@ -214,6 +308,10 @@ impl Player {
     pub(crate) fn get_staff_mod_level(&self) -> i32 {
         self.staff_mod_level
     }
+
+    pub(crate) fn set_staff_mod_level(&mut self, staff_mod_level: i32) {
+        self.staff_mod_level = staff_mod_level;
+    }
     
     pub(crate) fn get_pid(&self) -> usize {
         self.pid
@@ -222,57 +320,185 @@ impl Player {
     pub(crate)fn set_pid(&mut self, pid: usize) {
         self.pid = pid;
     }
+
+    pub(crate) fn get_uid(&self) -> u32 {
+        self.uid
+    }
+
+    pub(crate) fn set_uid(&mut self, uid: u32) {
+        self.uid = uid;
+    }
     
     pub fn delayed(&self) -> bool {
         self.pathing_entity.delayed
     }
+
+    #[inline]
+    fn default_move_speed(&self) -> MoveSpeed {
+        if self.run != self.temp_run {
+            MoveSpeed::RUN
+        } else {
+            MoveSpeed::WALK
+        }
+    }
+
+    /// Replace the current route with one found server side to the destination clicked this tick.
+    pub fn path_to_move_click(&mut self) {
+        let dest = match self.user_path.last() {
+            Some(dest) => CoordGrid::new(*dest as u32),
+            None => return,
+        };
+
+        let collision = match self.move_restrict.collision_type() {
+            Some(collision) if !self.delayed() => collision,
+            _ => return,
+        };
+
+        let route = self.pathing_entity.find_path(dest, 1, 1, 0, -1, 0, collision);
+        self.pathing_entity.queue_waypoints(route);
+    }
+
+    /// Walk or run along the current route, returns whether the player moved.
+    pub fn process_movement(&mut self) -> bool {
+        let collision = match self.move_restrict.collision_type() {
+            Some(collision) => collision,
+            None => return false,
+        };
+
+        // Scripts may have slowed the player down, otherwise follow the run setting.
+        if matches!(self.pathing_entity.move_speed(), MoveSpeed::WALK | MoveSpeed::RUN) {
+            self.pathing_entity.set_move_speed(self.default_move_speed());
+        }
+
+        let moved = self.pathing_entity.update_movement(collision);
+        if !self.pathing_entity.has_waypoints() {
+            self.temp_run = false;
+        }
+
+        moved
+    }
+
+    pub(crate) fn default_body(gender: u8) -> [i32; 7] {
+        if gender == 1 {
+            Player::DEFAULT_BODY_FEMALE
+        } else {
+            Player::DEFAULT_BODY_MALE
+        }
+    }
+
+    /// Flag the appearance as changed, viewers compare `last_appearance` against what they were last sent.
+    pub fn generate_appearance(&mut self, current_tick: i32) {
+        self.last_appearance = current_tick;
+        self.pathing_entity.masks |= InfoProtocol::PLAYER_APPEARANCE.id() as u32;
+    }
+
+    pub fn play_animation(&mut self, anim: i32, delay: i32) {
+        self.pathing_entity.anim_id = anim;
+        self.pathing_entity.anim_delay = delay;
+        self.pathing_entity.masks |= InfoProtocol::PLAYER_ANIM.id() as u32;
+    }
+
+    pub fn spotanim(&mut self, spotanim: i32, height: i32, delay: i32) {
+        self.pathing_entity.spotanim_id = spotanim;
+        self.pathing_entity.spotanim_height = height;
+        self.pathing_entity.spotanim_delay = delay;
+        self.pathing_entity.masks |= InfoProtocol::PLAYER_SPOTANIM.id() as u32;
+    }
+
+    /// Face an npc by its nid, or another player by its pid offset by 32768. -1 to stop facing.
+    pub fn face_entity(&mut self, target: i32) {
+        self.pathing_entity.face_entity = target;
+        self.pathing_entity.masks |= InfoProtocol::PLAYER_FACE_ENTITY.id() as u32;
+    }
+
+    pub fn face_square(&mut self, x: i32, z: i32) {
+        self.pathing_entity.face_x = x;
+        self.pathing_entity.face_z = z;
+        self.pathing_entity.masks |= InfoProtocol::PLAYER_FACE_COORD.id() as u32;
+    }
+
+    pub fn say(&mut self, message: String) {
+        self.pathing_entity.say = Some(message);
+        self.pathing_entity.masks |= InfoProtocol::PLAYER_SAY.id() as u32;
+    }
+
+    /// Public chat, `message` is the packed text exactly as the client sent it.
+    pub fn chat(&mut self, color: i32, effect: i32, message_type: i32, message: Vec<u8>) {
+        self.message_color = color;
+        self.message_effect = effect;
+        self.message_type = message_type;
+        self.message = Some(message);
+        self.pathing_entity.masks |= InfoProtocol::PLAYER_CHAT.id() as u32;
+    }
+
+    /// Two hitsplats fit in a single tick, any more are dropped.
+    pub fn apply_damage(&mut self, damage: i32, damage_type: i32, current_hitpoints: i32, base_hitpoints: i32) {
+        let hit_masks = (InfoProtocol::PLAYER_HIT.id() as u32, InfoProtocol::PLAYER_HIT2.id() as u32);
+        self.pathing_entity.apply_damage(damage, damage_type, current_hitpoints, base_hitpoints, hit_masks);
+    }
+
+    pub fn reset_entity(&mut self) {
+        self.pathing_entity.reset_pathing_entity();
+        self.message = None;
+    }
     
-    pub fn run_script(&mut self, mut script: ScriptState, protected: Option<bool>, force: Option<bool>) -> Result<i32, Box<dyn Error>>{
+    /// Run a script against a player in the world, taking protected access for it if asked.
+    pub fn run_script(engine: &mut Engine, pid: usize, script: &mut ScriptState, protected: Option<bool>, force: Option<bool>) -> Result<i32, Box<dyn Error>>{
         let protect = protected.unwrap_or(false);
         let force = force.unwrap_or(false);
+
+        let Some(player) = engine.players.get_mut(pid) else {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No player {} to run script: {}", pid, script.script.name())
+            )));
+        };
         
-        if !force && protect && (self.protect || self.delayed()) {
+        if !force && protect && (player.protect || player.delayed()) {
             return Err(Box::new(std::io::Error::new(
                 std::io::ErrorKind::PermissionDenied,
                 format!("Cannot get protected access for script: {}", script.script.name())
             )));
         }
         
+        let handle = player.handle();
         if protect {
             script.pointer_add(ScriptPointer::ProtectedActivePlayer);
-            self.protect = true;
+            player.protect = true;
         }
         
-        let state = ScriptRunner::execute(&mut script, false, false);
+        let state = ScriptRunner::execute(engine, script, false, false);
         
         if protect {
-            self.protect = false;
+            Self::release_protect(engine, Some(handle));
         }
         
-        if script.pointer_get(ScriptPointer::ProtectedActivePlayer) && script.active_player.is_some() {
+        if script.pointer_get(ScriptPointer::ProtectedActivePlayer) {
             script.pointer_remove(ScriptPointer::ProtectedActivePlayer);
-            if let Some(player) = script.active_player.as_mut() {
-                player.protect = false;
-            }
+            Self::release_protect(engine, script.active_player);
         }
         
-        if script.pointer_get(ScriptPointer::ProtectedActivePlayer2) && script.active_player2.is_some() {
+        if script.pointer_get(ScriptPointer::ProtectedActivePlayer2) {
             script.pointer_remove(ScriptPointer::ProtectedActivePlayer2);
-            if let Some(player) = script.active_player2.as_mut() {
-                player.protect = false;
-            }
+            Self::release_protect(engine, script.active_player2);
         }
 
         Ok(state)
     }
+
+    /// Give up protected access a script held on a player, if they're still around.
+    fn release_protect(engine: &mut Engine, handle: Option<PlayerHandle>) {
+        if let Some(player) = handle.and_then(|handle| engine.players.get_mut_by_handle(handle)) {
+            player.protect = false;
+        }
+    }
     
-    pub fn execute_script(&mut self, script: ScriptState, protected: Option<bool>, force: Option<bool>) {
+    /// Run a script against a player, a script left waiting on something is kept until it can be resumed.
+    pub fn execute_script(engine: &mut Engine, pid: usize, mut script: ScriptState, protected: Option<bool>, force: Option<bool>) {
         let protect = protected.unwrap_or(false);
         let force = force.unwrap_or(false);
 
-        let mut script_clone = script.clone();
-
-        let state = match self.run_script(script, Some(protect), Some(force)) {
+        let state = match Self::run_script(engine, pid, &mut script, Some(protect), Some(force)) {
             Ok(state) => state,
             Err(err) => {
                 debug!("Script execution failed: {}", err);
@@ -280,25 +506,197 @@ impl Player {
             }
         };
 
-        if state != ScriptState::FINISHED && state != ScriptState::ABORTED {
-            if state == ScriptState::WORLD_SUSPENDED {
-                // TODO - Engine.enqueueScript
-            } else if state == ScriptState::NPC_SUSPENDED {
+        // A resumed script was taken out of `active_script` first, so a finished one has nothing left to clear.
+        if state == ScriptState::FINISHED || state == ScriptState::ABORTED {
+            // TODO - close modal crap goes here
+            return;
+        }
+
+        if state == ScriptState::WORLD_SUSPENDED || state == ScriptState::NPC_SUSPENDED {
+            engine.suspend_script(script);
+        } else if let Some(player) = engine.players.get_mut(pid) {
+            player.active_script = Some(Box::new(script));
+            player.protect = protect;
+        }
+    }
+
+    /// Resume the script a dialog answer came in for, once the packets have been read.
+    pub fn process_resume(engine: &mut Engine, pid: usize) {
+        if let Some(script) = engine.players.get_mut(pid).and_then(|player| player.resumed_script.take()) {
+            Self::execute_script(engine, pid, *script, Some(true), Some(true));
+        }
+    }
+
+    /// Protected access is free and the player isn't waiting on a delay.
+    #[inline]
+    pub fn can_access(&self) -> bool {
+        !self.protect && !self.delayed()
+    }
+
+    pub fn enqueue_script(&mut self, script: Arc<ScriptFile>, queue_type: PlayerQueueType, delay: i32, args: Vec<ScriptArgument>) -> &mut EntityQueueRequest {
+        let request = EntityQueueRequest::new(QueueType::Player(queue_type), script, args, delay);
+        let queue = match queue_type {
+            PlayerQueueType::Engine => &mut self.engine_queue,
+            PlayerQueueType::Weak => &mut self.weak_queue,
+            _ => &mut self.queue,
+        };
+        queue.push(request);
+        queue.last_mut().unwrap()
+    }
+
+    /// Drop queued scripts by id from the primary and weak queues.
+    pub fn unlink_queued_script(&mut self, script_id: i32) {
+        self.queue.retain(|request| request.script.id != script_id);
+        self.weak_queue.retain(|request| request.script.id != script_id);
+    }
+
+    /// How many of a script are waiting in the primary and weak queues.
+    pub fn queued_script_count(&self, script_id: i32) -> i32 {
+        self.queue.iter()
+            .chain(self.weak_queue.iter())
+            .filter(|request| request.script.id == script_id)
+            .count() as i32
+    }
 
+    /// Closing a modal interrupts anything weak queued, and any script waiting on a dialog won't get its answer.
+    pub fn close_modal(&mut self) {
+        self.weak_queue.clear();
+        self.active_script.take_if(|script| script.execution == ScriptState::PAUSEBUTTON || script.execution == ScriptState::COUNTDIALOG);
+        // TODO - track open interfaces, run their [if_close] and send IF_CLOSE
+    }
+
+    /// Anything the player does of their own accord interrupts whatever they were doing.
+    pub fn clear_pending_action(&mut self) {
+        // TODO - clear interaction
+        self.close_modal();
+    }
+
+    /// A strong script anywhere in the queue closes modals before anything runs, and clears the weak queue once reached.
+    pub fn process_queue(engine: &mut Engine, pid: usize) {
+        if let Some(player) = engine.players.get_mut(pid) {
+            if player.queue.iter().any(|request| request.is_type(PlayerQueueType::Strong)) {
+                player.close_modal();
+            }
+        }
+
+        Self::process_requests(engine, pid, |player| &mut player.queue);
+    }
+
+    pub fn process_weak_queue(engine: &mut Engine, pid: usize) {
+        Self::process_requests(engine, pid, |player| &mut player.weak_queue);
+    }
+
+    /// Start a timer, replacing the script's timer if it already had one.
+    pub fn set_timer(&mut self, timer_type: PlayerTimerType, script: Arc<ScriptFile>, args: Vec<ScriptArgument>, interval: i32) {
+        let timer = EntityTimer::new(TimerType::Player(timer_type), script, Some(args), interval);
+        self.timers.insert(timer.script.id, timer);
+    }
+
+    /// Stop a timer, only if it's of the type being cleared.
+    pub fn clear_timer(&mut self, timer_type: PlayerTimerType, script_id: i32) {
+        if self.timers.get(&script_id).is_some_and(|timer| timer.timer_type == TimerType::Player(timer_type)) {
+            self.timers.remove(&script_id);
+        }
+    }
+
+    /// Fire due timers of one type, normal timers wait for protected access while soft timers never do.
+    pub fn process_timers(engine: &mut Engine, pid: usize, timer_type: PlayerTimerType) {
+        let soft = timer_type == PlayerTimerType::Soft;
+        let Some(player) = engine.players.get(pid) else {
+            return;
+        };
+        let ids: Vec<i32> = player.timers.iter()
+            .filter(|(_, timer)| timer.timer_type == TimerType::Player(timer_type))
+            .map(|(&id, _)| id)
+            .collect();
+
+        for id in ids {
+            let Some(player) = engine.players.get_mut(pid) else {
+                return;
+            };
+
+            // An earlier timer's script may have cleared this one.
+            let due = player.timers.get_mut(&id).is_some_and(EntityTimer::tick);
+            if !due || !(soft || player.can_access()) {
+                continue;
+            }
+
+            let Some(timer) = player.timers.get_mut(&id) else {
+                continue;
+            };
+            timer.clock = timer.interval;
+            let (script, args) = (timer.script.clone(), timer.args.clone());
+            let script = ScriptRunner::init(script, Some(player.as_entity_type()), None, args);
+            Self::execute_script(engine, pid, script, Some(!soft), None);
+        }
+    }
+
+    pub fn process_engine_queue(engine: &mut Engine, pid: usize) {
+        Self::process_requests(engine, pid, |player| &mut player.engine_queue);
+    }
+
+    /// Long queued scripts don't wait on a logout, they either run now or not at all.
+    pub fn process_logout_queue(engine: &mut Engine, pid: usize) {
+        let Some(player) = engine.players.get_mut(pid) else {
+            return;
+        };
+
+        let self_entity = player.as_entity_type();
+        let mut accelerated = Vec::new();
+        for request in std::mem::take(&mut player.queue) {
+            if !request.is_type(PlayerQueueType::Long) {
+                player.queue.push(request);
+            } else if request.logout_action == EntityQueueRequest::LOGOUT_ACCELERATE {
+                accelerated.push(request);
+            }
+        }
+
+        for request in accelerated {
+            let script = ScriptRunner::init(request.script, Some(self_entity), None, Some(request.args));
+            Self::execute_script(engine, pid, script, Some(true), Some(true));
+        }
+    }
+
+    /// Run the requests in one of the player's queues whose delay is up, as long as protected access is free.
+    /// Reaching a strong request clears the weak queue. The player is looked up again after every script,
+    /// since a script can change anything, even log them out.
+    fn process_requests(engine: &mut Engine, pid: usize, queue: fn(&mut Player) -> &mut Vec<EntityQueueRequest>) {
+        let mut index = 0;
+        loop {
+            let Some(player) = engine.players.get_mut(pid) else {
+                return;
+            };
+            match queue(player).get(index) {
+                Some(request) if request.is_type(PlayerQueueType::Strong) => player.weak_queue.clear(),
+                Some(_) => {},
+                None => return,
+            }
+
+            let can_access = player.can_access();
+            let self_entity = player.as_entity_type();
+            let requests = queue(player);
+            if Self::tick_request(&mut requests[index]) && can_access {
+                let request = requests.remove(index);
+                Self::execute_request(engine, pid, self_entity, request);
             } else {
-                if let Some(mut player) = script_clone.active_player.take() {
-                    let boxed_script = Box::new(script_clone.clone());
-                    player.active_script = Some(boxed_script);
-                    player.protect = protect;
-                    script_clone.active_player = Some(player);
-                }
+                index += 1;
             }
-        } else if self.active_script.as_ref().map_or(false, |active_script| &script_clone == active_script.as_ref()) {
-            self.active_script = None;
-            // TODO - close modal crap goes here
         }
     }
 
+    /// Count down a queued request, returns whether its delay is up.
+    #[inline]
+    fn tick_request(request: &mut EntityQueueRequest) -> bool {
+        request.delay -= 1;
+        request.delay < 0
+    }
+
+    fn execute_request(engine: &mut Engine, pid: usize, self_entity: EntityType, request: EntityQueueRequest) {
+        let mut script = ScriptRunner::init(request.script, Some(self_entity), None, Some(request.args));
+        script.last_int = request.last_int;
+        Self::execute_script(engine, pid, script, Some(true), None);
+    }
+
     const MAX_PACKET_SIZE: i32 = 20000;
 
     #[inline(always)]
@@ -323,8 +721,9 @@ impl Player {
                 return false;
             }
 
-            if self.client.decryptor.is_some() {
-                // TODO - ISAAC stuff
+            if let Some(decryptor) = self.client.decryptor.as_mut() {
+                let encrypted = self.client.inbound.g1() as i32;
+                self.client.opcode = (encrypted.wrapping_sub(decryptor.next()) & 0xFF) as u8;
             } else {
                 self.client.opcode = self.client.inbound.g1();
             }
@@ -344,7 +743,12 @@ impl Player {
             }
         }
 
-        // Handle variable-length packets
+        // Handle variable-length packets, the size may not have arrived yet either
+        let size_bytes = -self.client.waiting;
+        if (1..=2).contains(&size_bytes) && !self.client.has_available(size_bytes as usize).unwrap_or(false) {
+            return false;
+        }
+
         if self.client.waiting == -1 {
             if let Err(e) = self.client.read_packet_with_size(1) {
                 error!("Error reading packet size (byte): {}", e);
@@ -449,12 +853,20 @@ impl Player {
     /// reset animations
     ///
     /// social
-    pub fn on_login(&mut self) {
+    pub fn on_login(&mut self, current_tick: i32) {
         let start = Instant::now();
 
+        self.initial_connect_tick = current_tick;
+
         // Process initial data
         self.initial_login_data();
         self.rebuild_normal(false);
+        self.build_area.clear();
+        self.generate_appearance(current_tick);
+        self.send_varps();
+        // Place the local player on the freshly built map.
+        self.pathing_entity.tele = true;
+        self.pathing_entity.jump = true;
 
         let window_id = if self.window_status.window_mode.is_resizeable() { 746 } else { 548 };
 
@@ -467,10 +879,6 @@ impl Player {
         verify_id = self.get_incremented_verify_id();
         self.write(If_OpenSub::new(window_id, 100, 662, 1, verify_id));
 
-        if let Some(trigger) = ScriptProvider::get_by_trigger_specific(ServerTriggerTypes::LOGIN, -1, -1) {
-            let script = ScriptRunner::init(trigger, Some(self.clone().as_entity_type()), None, None);
-            self.execute_script(script, Some(true), None);
-        }
 
         self.set_active(true);
 
@@ -479,6 +887,75 @@ impl Player {
         }
     }
 
+    /// Attach a new socket to a player still in the world, leaving everything else as it was.
+    pub fn on_reconnect(&mut self, client: GameClient, current_tick: i32) {
+        if self.is_client_connected() {
+            self.client.shutdown();
+        }
+
+        self.client = client;
+        self.outgoing_messages.clear();
+
+        // The old socket may have been silent for a while, don't let that time the new one out.
+        self.last_response = current_tick - self.initial_connect_tick;
+        self.last_connected = self.last_response;
+        self.request_idle_logout = false;
+
+        // The client starts with an empty scene, so everything around the player is sent again.
+        self.rebuild_normal(true);
+        self.build_area.clear();
+        self.pathing_entity.tele = true;
+        self.pathing_entity.jump = true;
+        self.send_varps();
+    }
+
+    #[inline]
+    pub fn get_var(&self, id: usize) -> i32 {
+        self.varps.get(id).copied().unwrap_or(0)
+    }
+
+    /// Store a varp, sending it to the client when it is transmitted.
+    pub fn set_var(&mut self, id: usize, value: i32) {
+        if self.varps.len() <= id {
+            self.varps.resize(id + 1, 0);
+        }
+        self.varps[id] = value;
+
+        if VarpType::get(id as i32).is_some_and(|varp| varp.transmit) {
+            self.write_varp(id as u16, value);
+        }
+    }
+
+    pub fn get_varbit(&self, varbit: &VarBitType) -> i32 {
+        (self.get_var(varbit.basevar as usize) >> varbit.startbit) & varbit.mask()
+    }
+
+    /// Write `value` into the varbit's range of its base varp, anything outside the range is cut off.
+    pub fn set_varbit(&mut self, varbit: &VarBitType, value: i32) {
+        let basevar = varbit.basevar as usize;
+        let cleared = clear_bit_range(self.get_var(basevar), varbit.startbit as i32, varbit.endbit as i32);
+        self.set_var(basevar, cleared | ((value & varbit.mask()) << varbit.startbit));
+    }
+
+    /// Send every transmitted varp that isn't zero, the client starts with all of them cleared.
+    fn send_varps(&mut self) {
+        for id in 0..self.varps.len() {
+            let value = self.varps[id];
+            if value != 0 && VarpType::get(id as i32).is_some_and(|varp| varp.transmit) {
+                self.write_varp(id as u16, value);
+            }
+        }
+    }
+
+    #[inline]
+    fn write_varp(&mut self, id: u16, value: i32) {
+        if (i8::MIN as i32..=i8::MAX as i32).contains(&value) {
+            self.write(VarpSmall::new(id, value));
+        } else {
+            self.write(VarpLarge::new(id, value));
+        }
+    }
+
     fn initial_login_data(&mut self) {
         self.client.outbound.p1(self.get_staff_mod_level()); // Staff mod level
         self.client.outbound.p1(0); // Blackmarks?
@@ -492,7 +969,7 @@ impl Player {
         self.client.outbound.p1(1); // Members map
     }
 
-    fn rebuild_normal(&mut self, reconnect: bool) {
+    pub(crate) fn rebuild_normal(&mut self, reconnect: bool) {
         let origin_x = CoordGrid::zone(self.get_origin_coord().x()) as i16;
         let origin_z = CoordGrid::zone(self.get_origin_coord().z()) as i16;
 
@@ -533,7 +1010,8 @@ impl Player {
             return false;
         }
 
-        self.last_connected = current_tick;
+        // Relative to the login tick, as compared against in [Engine::process_logouts].
+        self.last_connected = current_tick - self.initial_connect_tick;
 
         self.user_limit = 0;
         self.client_limit = 0;
@@ -552,7 +1030,7 @@ impl Player {
         }
 
         if self.bytes_read > 0 {
-            self.last_response = current_tick;
+            self.last_response = current_tick - self.initial_connect_tick;
             self.bytes_read = 0;
         }
 
@@ -593,6 +1071,7 @@ impl Player {
         
         if message.priority() == ServerProtocolPriority::IMMEDIATE {
             message.write_self(self);
+            self.client.flush();
         } else {
             self.outgoing_messages.push(message.into());
         }
@@ -0,0 +1,337 @@
+use std::error::Error;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use once_cell::sync::Lazy;
+use config::server_config::ServerConfig;
+use log::warn;
+use crate::entity::entity::EntityBehavior;
+use crate::entity::entity_queue_request::ScriptArgument;
+use crate::entity::entity_timer::{PlayerTimerType, TimerType};
+use crate::entity::player::Player;
+use crate::grid::coord_grid::CoordGrid;
+use crate::io::crc::CRC;
+use crate::io::packet::Packet;
+use crate::script::script_provider::ScriptProvider;
+use crate::util::cache::config::varp_type::VarpType;
+
+static CRC32: Lazy<CRC> = Lazy::new(CRC::new);
+
+/// Everything about a player that outlives their session.
+#[derive(Clone, PartialEq)]
+pub struct PlayerSave {
+    pub coord: CoordGrid,
+    pub gender: u8,
+    pub playtime: i32,
+    pub staff_mod_level: i32,
+    pub body: [i32; 7],
+    pub colors: [i32; 5],
+    pub run: bool,
+    /// Permanent varps as id and value, only the ones that aren't zero.
+    pub varps: Vec<(u16, i32)>,
+    pub timers: Vec<TimerSave>,
+}
+
+/// A running timer, saved by script name and looked up again when the save is applied.
+/// Script ids are handed out again on every recompile, so they can't be kept across sessions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerSave {
+    pub script: String,
+    pub soft: bool,
+    pub interval: i32,
+    pub clock: i32,
+    pub args: Vec<ScriptArgument>,
+}
+
+/// Why a save file could not be read.
+#[derive(Debug)]
+pub enum PlayerSaveError {
+    Io(std::io::Error),
+    BadMagic,
+    UnsupportedVersion(u16),
+    Truncated,
+    Checksum,
+    TrailingData,
+}
+
+impl std::fmt::Display for PlayerSaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayerSaveError::Io(err) => write!(f, "failed to access save: {}", err),
+            PlayerSaveError::BadMagic => write!(f, "not a player save"),
+            PlayerSaveError::UnsupportedVersion(version) => write!(f, "unsupported save version {}", version),
+            PlayerSaveError::Truncated => write!(f, "save ended early"),
+            PlayerSaveError::Checksum => write!(f, "save checksum does not match"),
+            PlayerSaveError::TrailingData => write!(f, "unexpected data after the end of the save"),
+        }
+    }
+}
+
+impl Error for PlayerSaveError {}
+
+impl PlayerSave {
+    const MAGIC: u16 = 0x2004;
+    /// 1 - coord, gender, playtime and staff level.
+    ///
+    /// 2 - body, colors and run.
+    ///
+    /// 3 - permanent varps.
+    ///
+    /// 4 - timers and soft timers, by script id.
+    ///
+    /// 5 - timers by script name.
+    pub const VERSION: u16 = 5;
+
+    /// A fresh save for an account that has never logged in.
+    pub fn new(coord: CoordGrid, gender: u8) -> PlayerSave {
+        PlayerSave {
+            coord,
+            gender,
+            playtime: 0,
+            staff_mod_level: 0,
+            body: Player::default_body(gender),
+            colors: [0; 5],
+            run: false,
+            varps: Vec::new(),
+            timers: Vec::new(),
+        }
+    }
+
+    pub fn from_player(player: &Player) -> PlayerSave {
+        PlayerSave {
+            coord: player.coord(),
+            gender: player.gender,
+            playtime: player.playtime,
+            staff_mod_level: player.get_staff_mod_level(),
+            body: player.body,
+            colors: player.colors,
+            run: player.run,
+            varps: player.varps.iter()
+                .enumerate()
+                .filter(|(id, value)| **value != 0 && VarpType::get(*id as i32).is_some_and(VarpType::is_perm))
+                .map(|(id, value)| (id as u16, *value))
+                .collect(),
+            timers: player.timers.values()
+                .map(|timer| TimerSave {
+                    script: timer.script.name().to_string(),
+                    soft: timer.timer_type == TimerType::Player(PlayerTimerType::Soft),
+                    interval: timer.interval,
+                    clock: timer.clock,
+                    args: timer.args.clone().unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Restore the save onto a player who has just logged in and isn't in the world yet.
+    pub fn apply(&self, player: &mut Player) {
+        player.pathing_entity.entity.coord = self.coord;
+        player.pathing_entity.last_coord = self.coord;
+        player.gender = self.gender;
+        player.set_staff_mod_level(self.staff_mod_level);
+        player.playtime = self.playtime;
+        player.body = self.body;
+        player.colors = self.colors;
+        player.run = self.run;
+
+        // Written straight in, the client gets every varp once it has logged in.
+        for &(id, value) in &self.varps {
+            if player.varps.len() <= id as usize {
+                player.varps.resize(id as usize + 1, 0);
+            }
+            player.varps[id as usize] = value;
+        }
+
+        // Timers on scripts that no longer exist are dropped.
+        for saved in &self.timers {
+            let Some(script) = ScriptProvider::get_by_name(&saved.script) else {
+                warn!("Dropping timer on missing script {} for {}", saved.script, player.username);
+                continue;
+            };
+
+            let id = script.id;
+            let timer_type = if saved.soft { PlayerTimerType::Soft } else { PlayerTimerType::Normal };
+            player.set_timer(timer_type, script, saved.args.clone(), saved.interval);
+            if let Some(timer) = player.timers.get_mut(&id) {
+                timer.clock = saved.clock;
+            }
+        }
+    }
+
+    fn directory() -> PathBuf {
+        ServerConfig::get().paths.data.join("players")
+    }
+
+    pub fn path(username: &str) -> PathBuf {
+        Self::directory().join(format!("{}.sav", username.to_lowercase()))
+    }
+
+    /// Read a player's save, none if they have never logged out before.
+    pub fn load(username: &str) -> Result<Option<PlayerSave>, PlayerSaveError> {
+        match fs::read(Self::path(username)) {
+            Ok(data) => Self::decode(data).map(Some),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(PlayerSaveError::Io(err)),
+        }
+    }
+
+    /// Write an encoded save, going through a temporary file so a crash mid write never leaves half a save behind.
+    pub fn write(username: &str, data: &[u8]) -> Result<(), PlayerSaveError> {
+        let path = Self::path(username);
+        let temp = path.with_extension("sav.tmp");
+
+        fs::create_dir_all(Self::directory()).map_err(PlayerSaveError::Io)?;
+        fs::write(&temp, data).map_err(PlayerSaveError::Io)?;
+        fs::rename(&temp, &path).map_err(PlayerSaveError::Io)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Packet::new(64);
+
+        buf.p2(Self::MAGIC as i32);
+        buf.p2(Self::VERSION as i32);
+
+        buf.p4(self.coord.coord as i32);
+        buf.p1(self.gender as i32);
+        buf.p4(self.playtime);
+        buf.p1(self.staff_mod_level);
+
+        for part in self.body {
+            buf.p1(part);
+        }
+        for color in self.colors {
+            buf.p1(color);
+        }
+        buf.p1(self.run as i32);
+
+        buf.p2(self.varps.len() as i32);
+        for &(id, value) in &self.varps {
+            buf.p2(id as i32);
+            buf.p4(value);
+        }
+
+        buf.p2(self.timers.len() as i32);
+        for timer in &self.timers {
+            buf.pjstr(&timer.script, 0);
+            buf.p1(timer.soft as i32);
+            buf.p4(timer.interval);
+            buf.p4(timer.clock);
+            buf.p1(timer.args.len() as i32);
+            for arg in &timer.args {
+                match arg {
+                    ScriptArgument::Number(value) => {
+                        buf.p1(0);
+                        buf.p4(*value);
+                    },
+                    ScriptArgument::String(value) => {
+                        buf.p1(1);
+                        buf.pjstr(value, 0);
+                    },
+                }
+            }
+        }
+
+        let crc = CRC32.get_crc(&buf.data, 0, buf.position);
+        buf.p4(crc);
+
+        buf.data.truncate(buf.position);
+        buf.data
+    }
+
+    /// Decode a save of any version up to [PlayerSave::VERSION], defaulting whatever older versions didn't store.
+    pub fn decode(data: Vec<u8>) -> Result<PlayerSave, PlayerSaveError> {
+        let mut buf = Packet::from(data);
+
+        Self::need(&buf, 4)?;
+        if buf.g2() != Self::MAGIC {
+            return Err(PlayerSaveError::BadMagic);
+        }
+
+        let version = buf.g2();
+        if version == 0 || version > Self::VERSION {
+            return Err(PlayerSaveError::UnsupportedVersion(version));
+        }
+
+        Self::need(&buf, 10)?;
+        let coord = CoordGrid::new(buf.g4() as u32);
+        let gender = buf.g1();
+        let mut save = PlayerSave::new(coord, gender);
+        save.playtime = buf.g4();
+        save.staff_mod_level = buf.g1() as i32;
+
+        if version >= 2 {
+            Self::need(&buf, 13)?;
+            for part in save.body.iter_mut() {
+                *part = buf.g1b() as i32;
+            }
+            for color in save.colors.iter_mut() {
+                *color = buf.g1() as i32;
+            }
+            save.run = buf.g1() == 1;
+        }
+
+        if version >= 3 {
+            Self::need(&buf, 2)?;
+            let count = buf.g2() as usize;
+            Self::need(&buf, count as i32 * 6)?;
+            save.varps = (0..count).map(|_| (buf.g2(), buf.g4())).collect();
+        }
+
+        if version >= 4 {
+            Self::need(&buf, 2)?;
+            let count = buf.g2() as usize;
+            save.timers = Vec::with_capacity(count);
+            for _ in 0..count {
+                // Version 4 saved script ids, which may point at another script since, so only read past those.
+                let script = if version >= 5 {
+                    Self::need(&buf, 1)?;
+                    Some(buf.gjstr())
+                } else {
+                    Self::need(&buf, 2)?;
+                    buf.g2();
+                    None
+                };
+                Self::need(&buf, 10)?;
+                let soft = buf.g1() == 1;
+                let interval = buf.g4();
+                let clock = buf.g4();
+                let arg_count = buf.g1() as usize;
+
+                let mut args = Vec::with_capacity(arg_count);
+                for _ in 0..arg_count {
+                    Self::need(&buf, 1)?;
+                    args.push(if buf.g1() == 0 {
+                        Self::need(&buf, 4)?;
+                        ScriptArgument::Number(buf.g4())
+                    } else {
+                        ScriptArgument::String(buf.gjstr())
+                    });
+                }
+
+                if let Some(script) = script {
+                    save.timers.push(TimerSave { script, soft, interval, clock, args });
+                }
+            }
+        }
+
+        let end = buf.position;
+        Self::need(&buf, 4)?;
+        if buf.g4() != CRC32.get_crc(&buf.data, 0, end) {
+            return Err(PlayerSaveError::Checksum);
+        }
+
+        if buf.remaining() > 0 {
+            return Err(PlayerSaveError::TrailingData);
+        }
+
+        Ok(save)
+    }
+
+    #[inline]
+    fn need(buf: &Packet, bytes: i32) -> Result<(), PlayerSaveError> {
+        if buf.remaining() < bytes {
+            return Err(PlayerSaveError::Truncated);
+        }
+        Ok(())
+    }
+}
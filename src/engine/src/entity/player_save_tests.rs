@@ -0,0 +1,123 @@
+use crate::entity::entity::EntityBehavior;
+use crate::entity::entity_queue_request::ScriptArgument;
+use crate::entity::player::Player;
+use crate::entity::player_save::{PlayerSave, PlayerSaveError, TimerSave};
+use crate::grid::coord_grid::CoordGrid;
+use crate::io::crc::CRC;
+
+fn save() -> PlayerSave {
+    let mut save = PlayerSave::new(CoordGrid::from(3222, 1, 3218), 1);
+    save.playtime = 1234;
+    save.staff_mod_level = 2;
+    save.colors = [1, 2, 3, 4, 5];
+    save.run = true;
+    save.varps = vec![(0, 1), (281, 1000), (1000, -1)];
+    save.timers = vec![
+        TimerSave { script: "[timer,regen]".to_string(), soft: false, interval: 100, clock: -3, args: Vec::new() },
+        TimerSave { script: "[softtimer,poison]".to_string(), soft: true, interval: 5, clock: 2, args: vec![ScriptArgument::Number(-7), ScriptArgument::String("poison".to_string())] },
+    ];
+    save
+}
+
+#[test]
+fn test_save_roundtrip() {
+    let decoded = PlayerSave::decode(save().encode()).unwrap();
+    assert_eq!(decoded.coord.coord, CoordGrid::from(3222, 1, 3218).coord);
+    assert_eq!(decoded.gender, 1);
+    assert_eq!(decoded.playtime, 1234);
+    assert_eq!(decoded.staff_mod_level, 2);
+    assert_eq!(decoded.body, save().body);
+    assert_eq!(decoded.body[1], -1);
+    assert_eq!(decoded.colors, [1, 2, 3, 4, 5]);
+    assert!(decoded.run);
+    assert_eq!(decoded.varps, vec![(0, 1), (281, 1000), (1000, -1)]);
+    assert_eq!(decoded.timers, save().timers);
+}
+
+#[test]
+fn test_save_apply() {
+    let save = save();
+    let mut player = Player::new_dummy(CoordGrid::from(3200, 0, 3200), 0, 1);
+    save.apply(&mut player);
+
+    assert_eq!(player.coord().coord, CoordGrid::from(3222, 1, 3218).coord);
+    assert_eq!(player.gender, 1);
+    assert_eq!(player.get_staff_mod_level(), 2);
+    assert_eq!(player.playtime, 1234);
+    assert_eq!(player.body, save.body);
+    assert_eq!(player.colors, [1, 2, 3, 4, 5]);
+    assert!(player.run);
+    assert_eq!(player.varps[281], 1000);
+    assert_eq!(player.varps[1000], -1);
+    // Neither timer's script exists, so both are dropped rather than bound to whatever has the id now.
+    assert!(player.timers.is_empty());
+}
+
+#[test]
+fn test_save_truncated() {
+    let mut data = save().encode();
+    data.truncate(data.len() - 6);
+    assert!(matches!(PlayerSave::decode(data), Err(PlayerSaveError::Truncated)));
+}
+
+#[test]
+fn test_save_corrupt() {
+    let mut data = save().encode();
+    data[8] ^= 0xff;
+    assert!(matches!(PlayerSave::decode(data), Err(PlayerSaveError::Checksum)));
+
+    let mut data = save().encode();
+    data[0] = 0;
+    assert!(matches!(PlayerSave::decode(data), Err(PlayerSaveError::BadMagic)));
+}
+
+#[test]
+fn test_save_unsupported_version() {
+    let mut data = save().encode();
+    data[3] = (PlayerSave::VERSION + 1) as u8;
+    assert!(matches!(PlayerSave::decode(data), Err(PlayerSaveError::UnsupportedVersion(_))));
+}
+
+#[test]
+fn test_save_migrates_version_1() {
+    // Version 1 stopped after the staff level.
+    let mut data = vec![0x20, 0x04, 0x00, 0x01];
+    data.extend_from_slice(&CoordGrid::from(3222, 0, 3218).coord.to_be_bytes());
+    data.push(1);
+    data.extend_from_slice(&1234i32.to_be_bytes());
+    data.push(0);
+    let crc = CRC::new().get_crc(&data, 0, data.len());
+    data.extend_from_slice(&crc.to_be_bytes());
+
+    let decoded = PlayerSave::decode(data).unwrap();
+    assert_eq!(decoded.coord.coord, CoordGrid::from(3222, 0, 3218).coord);
+    assert_eq!(decoded.gender, 1);
+    assert_eq!(decoded.playtime, 1234);
+    assert_eq!(decoded.staff_mod_level, 0);
+    assert_eq!(decoded.body, PlayerSave::new(decoded.coord, 1).body);
+    assert!(!decoded.run);
+    assert!(decoded.varps.is_empty());
+    assert!(decoded.timers.is_empty());
+}
+
+#[test]
+fn test_save_drops_version_4_timers() {
+    // Version 4 ended with timers keyed by script id.
+    let mut save = save();
+    save.timers.clear();
+    let mut data = save.encode();
+    data.truncate(data.len() - 6);
+    data[3] = 4;
+    data.extend_from_slice(&1u16.to_be_bytes());
+    data.extend_from_slice(&12u16.to_be_bytes());
+    data.push(0);
+    data.extend_from_slice(&100i32.to_be_bytes());
+    data.extend_from_slice(&(-3i32).to_be_bytes());
+    data.push(0);
+    let crc = CRC::new().get_crc(&data, 0, data.len());
+    data.extend_from_slice(&crc.to_be_bytes());
+
+    let decoded = PlayerSave::decode(data).unwrap();
+    assert_eq!(decoded.varps, save.varps);
+    assert!(decoded.timers.is_empty());
+}
@@ -0,0 +1,212 @@
+use std::sync::{Arc, Once};
+use crate::engine::Engine;
+use crate::entity::entity_queue_request::{EntityQueueRequest, PlayerQueueType};
+use crate::entity::entity_timer::PlayerTimerType;
+use crate::entity::move_speed::MoveSpeed;
+use crate::entity::player::Player;
+use crate::game_map::GameMap;
+use crate::grid::coord_grid::CoordGrid;
+use crate::script::script_file::ScriptFile;
+
+/// A script that finishes as soon as it runs, so anything left in a queue hasn't run yet.
+fn script(id: i32) -> Arc<ScriptFile> {
+    let mut script = ScriptFile::new(id);
+    script.info.lookup_key = 0;
+    Arc::new(script)
+}
+
+const PID: usize = 1;
+
+/// A world of its own with just the one player in it.
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.players.set(PID, Player::new_dummy(CoordGrid::from(3222, 0, 3218), 0, PID)).unwrap();
+    engine
+}
+
+fn player(engine: &mut Engine) -> &mut Player {
+    engine.players.get_mut(PID).unwrap()
+}
+
+#[test]
+fn test_queue_delay() {
+    let mut engine = engine();
+    player(&mut engine).enqueue_script(script(1), PlayerQueueType::Normal, 1, Vec::new());
+
+    Player::process_queue(&mut engine, PID);
+    assert_eq!(player(&mut engine).queue.len(), 1);
+    Player::process_queue(&mut engine, PID);
+    assert!(player(&mut engine).queue.is_empty());
+}
+
+#[test]
+fn test_queue_waits_for_access() {
+    let mut engine = engine();
+    player(&mut engine).enqueue_script(script(1), PlayerQueueType::Normal, 0, Vec::new());
+
+    player(&mut engine).protect = true;
+    Player::process_queue(&mut engine, PID);
+    assert_eq!(player(&mut engine).queue.len(), 1);
+
+    player(&mut engine).protect = false;
+    Player::process_queue(&mut engine, PID);
+    assert!(player(&mut engine).queue.is_empty());
+}
+
+#[test]
+fn test_strong_clears_weak() {
+    let mut engine = engine();
+    player(&mut engine).enqueue_script(script(1), PlayerQueueType::Weak, 5, Vec::new());
+    player(&mut engine).enqueue_script(script(2), PlayerQueueType::Strong, 5, Vec::new());
+
+    Player::process_queue(&mut engine, PID);
+    assert!(player(&mut engine).weak_queue.is_empty());
+    assert_eq!(player(&mut engine).queue.len(), 1);
+}
+
+#[test]
+fn test_interaction_clears_weak() {
+    let mut engine = engine();
+    player(&mut engine).enqueue_script(script(1), PlayerQueueType::Weak, 5, Vec::new());
+    player(&mut engine).enqueue_script(script(2), PlayerQueueType::Normal, 5, Vec::new());
+
+    player(&mut engine).clear_pending_action();
+    assert!(player(&mut engine).weak_queue.is_empty());
+    assert_eq!(player(&mut engine).queue.len(), 1);
+}
+
+#[test]
+fn test_queue_count_and_clear() {
+    let mut engine = engine();
+    player(&mut engine).enqueue_script(script(1), PlayerQueueType::Normal, 5, Vec::new());
+    player(&mut engine).enqueue_script(script(1), PlayerQueueType::Weak, 5, Vec::new());
+    player(&mut engine).enqueue_script(script(2), PlayerQueueType::Normal, 5, Vec::new());
+    assert_eq!(player(&mut engine).queued_script_count(1), 2);
+
+    player(&mut engine).unlink_queued_script(1);
+    assert_eq!(player(&mut engine).queued_script_count(1), 0);
+    assert_eq!(player(&mut engine).queued_script_count(2), 1);
+}
+
+#[test]
+fn test_longqueue_logout() {
+    let mut engine = engine();
+    player(&mut engine).enqueue_script(script(1), PlayerQueueType::Long, 50, Vec::new()).logout_action = EntityQueueRequest::LOGOUT_DISCARD;
+    player(&mut engine).enqueue_script(script(2), PlayerQueueType::Long, 50, Vec::new()).logout_action = EntityQueueRequest::LOGOUT_ACCELERATE;
+    player(&mut engine).enqueue_script(script(3), PlayerQueueType::Normal, 50, Vec::new());
+
+    Player::process_logout_queue(&mut engine, PID);
+    assert_eq!(player(&mut engine).queue.len(), 1);
+    assert_eq!(player(&mut engine).queue[0].script.id, 3);
+}
+
+#[test]
+fn test_timer_interval() {
+    let mut engine = engine();
+    player(&mut engine).set_timer(PlayerTimerType::Normal, script(1), Vec::new(), 2);
+
+    Player::process_timers(&mut engine, PID, PlayerTimerType::Normal);
+    assert_eq!(player(&mut engine).timers[&1].clock, 1);
+    Player::process_timers(&mut engine, PID, PlayerTimerType::Normal);
+    assert_eq!(player(&mut engine).timers[&1].clock, 2);
+}
+
+#[test]
+fn test_timers_and_protected_access() {
+    let mut engine = engine();
+    player(&mut engine).set_timer(PlayerTimerType::Normal, script(1), Vec::new(), 1);
+    player(&mut engine).set_timer(PlayerTimerType::Soft, script(2), Vec::new(), 1);
+
+    // The normal timer is held back until it can run, the soft timer isn't.
+    player(&mut engine).protect = true;
+    Player::process_timers(&mut engine, PID, PlayerTimerType::Normal);
+    Player::process_timers(&mut engine, PID, PlayerTimerType::Soft);
+    assert_eq!(player(&mut engine).timers[&1].clock, 0);
+    assert_eq!(player(&mut engine).timers[&2].clock, 1);
+
+    player(&mut engine).protect = false;
+    Player::process_timers(&mut engine, PID, PlayerTimerType::Normal);
+    assert_eq!(player(&mut engine).timers[&1].clock, 1);
+}
+
+#[test]
+fn test_clear_timer_by_type() {
+    let mut engine = engine();
+    player(&mut engine).set_timer(PlayerTimerType::Normal, script(1), Vec::new(), 5);
+
+    player(&mut engine).clear_timer(PlayerTimerType::Soft, 1);
+    assert!(player(&mut engine).timers.contains_key(&1));
+    player(&mut engine).clear_timer(PlayerTimerType::Normal, 1);
+    assert!(player(&mut engine).timers.is_empty());
+}
+
+/// Open up the zones around the player, nothing else is placed in them so every step is allowed.
+/// Tests run in parallel and the collision map is shared, so only the first one allocates.
+fn open_zones() {
+    static OPEN: Once = Once::new();
+    OPEN.call_once(|| {
+        for x in [3216, 3224] {
+            GameMap::allocate_zone(CoordGrid::from(x, 0, 3216));
+        }
+    });
+}
+
+fn position(engine: &mut Engine) -> (u16, u8, u16) {
+    let coord = player(engine).pathing_entity.entity.coord;
+    (coord.x(), coord.y(), coord.z())
+}
+
+fn walk_to(engine: &mut Engine, x: u16) {
+    player(engine).pathing_entity.queue_waypoints(vec![CoordGrid::from(x, 0, 3218)]);
+}
+
+#[test]
+fn test_player_walks_by_default() {
+    let engine = engine();
+    assert!(matches!(engine.players.get(PID).unwrap().pathing_entity.move_speed(), MoveSpeed::WALK));
+}
+
+#[test]
+fn test_walk_one_step_per_tick() {
+    open_zones();
+    let mut engine = engine();
+    walk_to(&mut engine, 3225);
+
+    assert!(player(&mut engine).process_movement());
+    assert_eq!(position(&mut engine), (3223, 0, 3218));
+    assert_eq!(player(&mut engine).pathing_entity.walk_dir, 4);
+    assert_eq!(player(&mut engine).pathing_entity.run_dir, -1);
+}
+
+#[test]
+fn test_run_two_steps_per_tick() {
+    open_zones();
+    let mut engine = engine();
+    player(&mut engine).run = true;
+    walk_to(&mut engine, 3225);
+
+    assert!(player(&mut engine).process_movement());
+    assert_eq!(position(&mut engine), (3224, 0, 3218));
+    assert_eq!(player(&mut engine).pathing_entity.walk_dir, 4);
+    assert_eq!(player(&mut engine).pathing_entity.run_dir, 4);
+
+    // Only the one step left, so the last tick is a walk.
+    player(&mut engine).pathing_entity.walk_dir = -1;
+    player(&mut engine).pathing_entity.run_dir = -1;
+    assert!(player(&mut engine).process_movement());
+    assert_eq!(position(&mut engine), (3225, 0, 3218));
+    assert_eq!(player(&mut engine).pathing_entity.run_dir, -1);
+    assert!(!player(&mut engine).pathing_entity.has_waypoints());
+}
+
+#[test]
+fn test_delayed_player_does_not_move() {
+    open_zones();
+    let mut engine = engine();
+    player(&mut engine).pathing_entity.delayed = true;
+    walk_to(&mut engine, 3225);
+
+    assert!(!player(&mut engine).process_movement());
+    assert_eq!(position(&mut engine), (3222, 0, 3218));
+    assert!(player(&mut engine).pathing_entity.has_waypoints());
+}
@@ -0,0 +1,447 @@
+use std::error::Error;
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+use fnv::FnvHashMap;
+use log::{error, info, warn};
+use cache::file_handler::{get_data, get_group_id};
+use cache::xtea::get_xtea_key_by_mapsquare;
+use constants::js5_archive::js5_archive::MAPS;
+use rs2cache::js5_compression::Js5Compression;
+use rsmod_pathfinder::{allocate_if_absent, can_travel, change_floor, change_loc, change_wall, find_path, has_line_of_sight, has_line_of_walk, is_flagged, CollisionFlag, CollisionType};
+use crate::entity::loc_layer::LocLayer;
+use crate::grid::coord_grid::CoordGrid;
+use crate::io::packet::Packet;
+use crate::util::cache::config::loc_type::LocType;
+
+/// A loc placed by the map files rather than spawned at runtime.
+#[derive(Clone, Copy, PartialEq)]
+pub struct MapLoc {
+    pub id: i32,
+    pub coord: CoordGrid,
+    pub shape: u8,
+    pub angle: u8,
+}
+
+pub struct MapSquare {
+    pub mapsquare_x: i32,
+    pub mapsquare_z: i32,
+    /// Terrain flags, indexed by [MapSquare::index].
+    pub lands: Vec<u8>,
+    pub locs: Vec<MapLoc>,
+}
+
+impl MapSquare {
+    pub const SIZE: usize = 64;
+    pub const LEVELS: usize = 4;
+
+    #[inline(always)]
+    pub fn index(x: usize, z: usize, level: usize) -> usize {
+        (level * MapSquare::SIZE + x) * MapSquare::SIZE + z
+    }
+
+    #[inline(always)]
+    pub fn land(&self, x: usize, z: usize, level: usize) -> u8 {
+        self.lands[MapSquare::index(x, z, level)]
+    }
+}
+
+/// Why a map square could not be loaded.
+#[derive(Debug)]
+pub enum MapLoadError {
+    MissingKey,
+    InvalidKey,
+    Terrain(Box<dyn Error>),
+    Locs(Box<dyn Error>),
+}
+
+impl std::fmt::Display for MapLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapLoadError::MissingKey => write!(f, "no XTEA key for loc file"),
+            MapLoadError::InvalidKey => write!(f, "XTEA key does not decrypt loc file"),
+            MapLoadError::Terrain(err) => write!(f, "failed to read terrain: {}", err),
+            MapLoadError::Locs(err) => write!(f, "failed to read locs: {}", err),
+        }
+    }
+}
+
+impl Error for MapLoadError {}
+
+/// rsmod-pathfinder keeps a single collision map for the whole process, every call into it holds this.
+static COLLISION: Mutex<()> = Mutex::new(());
+
+#[inline]
+fn with_collision<T>(f: impl FnOnce() -> T) -> T {
+    let _guard = COLLISION.lock().unwrap_or_else(PoisonError::into_inner);
+    f()
+}
+
+/// The map squares belong to one engine, the collision built from them does not: every engine in
+/// the process reads and changes the same collision map.
+pub struct GameMap {
+    pub mapsquares: FnvHashMap<i32, MapSquare>,
+}
+
+impl GameMap {
+    /// Terrain flag: the tile can't be walked on.
+    pub const LAND_BLOCKED: u8 = 0x1;
+    /// Terrain flag: the tile is a bridge, anything on the level above is collided on this level.
+    pub const LAND_BRIDGE: u8 = 0x2;
+    /// Terrain flag: roofs are hidden while standing here.
+    pub const LAND_REMOVE_ROOFS: u8 = 0x4;
+    /// The client only ever walks the first 25 steps of a route.
+    pub const MAX_WAYPOINTS: u8 = 25;
+
+    pub fn new() -> GameMap {
+        GameMap {
+            mapsquares: FnvHashMap::default(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn mapsquare_id(mapsquare_x: i32, mapsquare_z: i32) -> i32 {
+        (mapsquare_x << 8) | mapsquare_z
+    }
+
+    #[inline]
+    pub fn get(&self, mapsquare_x: i32, mapsquare_z: i32) -> Option<&MapSquare> {
+        self.mapsquares.get(&GameMap::mapsquare_id(mapsquare_x, mapsquare_z))
+    }
+
+    /// Load every map square in the maps archive.
+    ///
+    /// A square whose loc file can't be decrypted is still loaded with its terrain, and reported,
+    /// so a bad key shows up as missing scenery instead of missing land.
+    pub fn init(&mut self) {
+        let start = Instant::now();
+        LocType::load();
+
+        let mut loaded = 0;
+        let mut failed: Vec<(i32, i32, MapLoadError)> = Vec::new();
+
+        for mapsquare_x in 0..256 {
+            for mapsquare_z in 0..256 {
+                let terrain_group = match get_group_id(MAPS as u8, &format!("m{}_{}", mapsquare_x, mapsquare_z)) {
+                    Some(group) => group,
+                    None => continue,
+                };
+
+                let lands = match Self::load_lands(terrain_group) {
+                    Ok(lands) => lands,
+                    Err(err) => {
+                        failed.push((mapsquare_x, mapsquare_z, MapLoadError::Terrain(err)));
+                        continue;
+                    }
+                };
+
+                let locs = match get_group_id(MAPS as u8, &format!("l{}_{}", mapsquare_x, mapsquare_z)) {
+                    Some(group) => match Self::load_locs(group, mapsquare_x, mapsquare_z) {
+                        Ok(locs) => locs,
+                        Err(err) => {
+                            failed.push((mapsquare_x, mapsquare_z, err));
+                            Vec::new()
+                        }
+                    },
+                    None => Vec::new(),
+                };
+
+                let square = MapSquare {
+                    mapsquare_x,
+                    mapsquare_z,
+                    lands,
+                    locs,
+                };
+                Self::build_collision(&square);
+
+                self.mapsquares.insert(GameMap::mapsquare_id(mapsquare_x, mapsquare_z), square);
+                loaded += 1;
+            }
+        }
+
+        for (mapsquare_x, mapsquare_z, err) in &failed {
+            error!("Map square {}_{} (mapsquare {}): {}", mapsquare_x, mapsquare_z, GameMap::mapsquare_id(*mapsquare_x, *mapsquare_z), err);
+        }
+
+        if !failed.is_empty() {
+            warn!("{} map squares failed to load fully, check xteaKeys.json", failed.len());
+        }
+
+        info!("Loaded {} map squares in {:?}", loaded, start.elapsed());
+    }
+
+    /// Flag everything a map square blocks in the pathfinder's collision map.
+    fn build_collision(square: &MapSquare) {
+        let base_x = square.mapsquare_x << 6;
+        let base_z = square.mapsquare_z << 6;
+
+        for level in 0..MapSquare::LEVELS {
+            for x in (0..MapSquare::SIZE).step_by(8) {
+                for z in (0..MapSquare::SIZE).step_by(8) {
+                    with_collision(|| unsafe { allocate_if_absent(base_x + x as i32, base_z + z as i32, level as i32) });
+                }
+            }
+        }
+
+        for level in 0..MapSquare::LEVELS {
+            for x in 0..MapSquare::SIZE {
+                for z in 0..MapSquare::SIZE {
+                    if square.land(x, z, level) & GameMap::LAND_BLOCKED == 0 {
+                        continue;
+                    }
+
+                    let level = Self::collision_level(square, x, z, level as i32);
+                    if level >= 0 {
+                        with_collision(|| unsafe { change_floor(base_x + x as i32, base_z + z as i32, level, true) });
+                    }
+                }
+            }
+        }
+
+        for loc in &square.locs {
+            let loc_type = match LocType::get(loc.id) {
+                Some(loc_type) => loc_type,
+                None => continue,
+            };
+
+            let x = loc.coord.x() as i32;
+            let z = loc.coord.z() as i32;
+            let level = Self::collision_level(square, (x - base_x) as usize, (z - base_z) as usize, loc.coord.y() as i32);
+            if level >= 0 {
+                Self::change_loc_collision(loc_type, loc.shape, loc.angle, x, z, level, true);
+            }
+        }
+    }
+
+    /// Anything under a bridge tile is collided one level down, where players actually walk.
+    #[inline]
+    fn collision_level(square: &MapSquare, x: usize, z: usize, level: i32) -> i32 {
+        if square.land(x, z, 1) & GameMap::LAND_BRIDGE != 0 {
+            level - 1
+        } else {
+            level
+        }
+    }
+
+    /// Add or remove the collision of a loc, for map locs as well as ones spawned at runtime.
+    pub fn change_loc_collision(loc_type: &LocType, shape: u8, angle: u8, x: i32, z: i32, level: i32, add: bool) {
+        if loc_type.blockwalk == 0 {
+            return;
+        }
+
+        with_collision(|| unsafe {
+            match LocLayer::from_shape(shape) {
+                LocLayer::Wall => {
+                    change_wall(x, z, level, angle as i32, shape as i32, loc_type.blockrange, loc_type.breakroutefinding, add);
+                },
+                LocLayer::Ground => {
+                    let (width, length) = if angle & 0x1 == 0 {
+                        (loc_type.width, loc_type.length)
+                    } else {
+                        (loc_type.length, loc_type.width)
+                    };
+                    change_loc(x, z, level, width as i32, length as i32, loc_type.blockrange, loc_type.breakroutefinding, add);
+                },
+                LocLayer::GroundDecor => {
+                    if loc_type.is_active() {
+                        change_floor(x, z, level, add);
+                    }
+                },
+                LocLayer::WallDecor => {}
+            }
+        });
+    }
+
+    /// Make sure the collision zone holding `coord` exists, a new zone starts out open.
+    pub fn allocate_zone(coord: CoordGrid) {
+        with_collision(|| unsafe { allocate_if_absent(coord.x() as i32, coord.z() as i32, coord.y() as i32) });
+    }
+
+    /// Block or unblock walking onto a single tile, the same way blocked terrain does.
+    pub fn change_floor_collision(coord: CoordGrid, add: bool) {
+        with_collision(|| unsafe { change_floor(coord.x() as i32, coord.z() as i32, coord.y() as i32, add) });
+    }
+
+    #[inline]
+    pub fn is_blocked(coord: CoordGrid) -> bool {
+        with_collision(|| unsafe { is_flagged(coord.x() as i32, coord.z() as i32, coord.y() as i32, CollisionFlag::WALK_BLOCKED as u32) })
+    }
+
+    /// Whether an entity of the given size can take a single step in the direction of `dx`, `dz`.
+    #[inline]
+    pub fn can_travel(from: CoordGrid, dx: i32, dz: i32, size: u8, collision: CollisionType) -> bool {
+        with_collision(|| unsafe { can_travel(from.y() as i32, from.x() as i32, from.z() as i32, dx as i8, dz as i8, size, 0, collision) })
+    }
+
+    /// Whether a projectile could travel between the two areas. Always false across levels.
+    pub fn has_line_of_sight(from: CoordGrid, to: CoordGrid, src_width: u8, src_length: u8, dest_width: u8, dest_length: u8) -> bool {
+        if from.y() != to.y() {
+            return false;
+        }
+
+        with_collision(|| unsafe {
+            has_line_of_sight(from.y() as i32, from.x() as i32, from.z() as i32, to.x() as i32, to.z() as i32, src_width, src_length, dest_width, dest_length, 0)
+        })
+    }
+
+    /// Whether the two areas are joined by a straight walkable line. Always false across levels.
+    pub fn has_line_of_walk(from: CoordGrid, to: CoordGrid, src_width: u8, src_length: u8, dest_width: u8, dest_length: u8) -> bool {
+        if from.y() != to.y() {
+            return false;
+        }
+
+        with_collision(|| unsafe {
+            has_line_of_walk(from.y() as i32, from.x() as i32, from.z() as i32, to.x() as i32, to.z() as i32, src_width, src_length, dest_width, dest_length, 0)
+        })
+    }
+
+    /// Smart route from `from` towards a destination of the given size, as the waypoints to walk through.
+    /// `shape` is -1 unless the destination is a loc, which is then reached from the sides its shape allows.
+    #[allow(clippy::too_many_arguments)]
+    pub fn find_path(
+        from: CoordGrid,
+        to: CoordGrid,
+        src_size: u8,
+        dest_width: u8,
+        dest_length: u8,
+        angle: u8,
+        shape: i8,
+        move_near: bool,
+        block_access_flags: u8,
+        collision: CollisionType,
+    ) -> Vec<CoordGrid> {
+        if from.y() != to.y() {
+            return Vec::new();
+        }
+
+        let waypoints = with_collision(|| unsafe {
+            find_path(
+                from.y() as i32,
+                from.x() as i32,
+                from.z() as i32,
+                to.x() as i32,
+                to.z() as i32,
+                src_size,
+                dest_width,
+                dest_length,
+                angle,
+                shape,
+                move_near,
+                block_access_flags,
+                GameMap::MAX_WAYPOINTS,
+                collision,
+            )
+        });
+
+        // Waypoints are packed the same way as a coord.
+        waypoints.into_iter().map(CoordGrid::new).collect()
+    }
+
+    fn load_lands(group: u16) -> Result<Vec<u8>, Box<dyn Error>> {
+        let data = Js5Compression::uncompress(get_data(MAPS as u8, group)?, None)?;
+        let mut buf = Packet::from(data);
+        let mut lands = vec![0u8; MapSquare::LEVELS * MapSquare::SIZE * MapSquare::SIZE];
+
+        for level in 0..MapSquare::LEVELS {
+            for x in 0..MapSquare::SIZE {
+                for z in 0..MapSquare::SIZE {
+                    loop {
+                        if buf.remaining() <= 0 {
+                            return Err("terrain ended early".into());
+                        }
+
+                        let opcode = buf.g1();
+                        if opcode == 0 {
+                            break;
+                        } else if opcode == 1 {
+                            if buf.remaining() <= 0 {
+                                return Err("terrain ended early".into());
+                            }
+                            buf.g1(); // Height
+                            break;
+                        } else if opcode <= 49 {
+                            if buf.remaining() <= 0 {
+                                return Err("terrain ended early".into());
+                            }
+                            buf.g1(); // Overlay
+                        } else if opcode <= 81 {
+                            lands[MapSquare::index(x, z, level)] = opcode - 49;
+                        }
+                        // Anything above is an underlay.
+                    }
+                }
+            }
+        }
+
+        Ok(lands)
+    }
+
+    fn load_locs(group: u16, mapsquare_x: i32, mapsquare_z: i32) -> Result<Vec<MapLoc>, MapLoadError> {
+        let key = get_xtea_key_by_mapsquare(GameMap::mapsquare_id(mapsquare_x, mapsquare_z));
+        let data = get_data(MAPS as u8, group).map_err(MapLoadError::Locs)?;
+
+        // Empty loc files are stored unencrypted, so a missing key only matters if reading without one fails.
+        let data = if key.is_zero() {
+            Js5Compression::uncompress(data, None).map_err(|_| MapLoadError::MissingKey)?
+        } else {
+            Js5Compression::uncompress(data, Some(key.to_array())).map_err(|_| MapLoadError::InvalidKey)?
+        };
+
+        Self::decode_locs(data, mapsquare_x, mapsquare_z).map_err(MapLoadError::Locs)
+    }
+
+    /// Decode a loc file, a truncated one is an error rather than a read past the end.
+    pub(crate) fn decode_locs(data: Vec<u8>, mapsquare_x: i32, mapsquare_z: i32) -> Result<Vec<MapLoc>, Box<dyn Error>> {
+        let mut buf = Packet::from(data);
+        let mut locs = Vec::new();
+        let base_x = mapsquare_x << 6;
+        let base_z = mapsquare_z << 6;
+
+        let mut id = -1;
+        // An empty file has no locs, not even the terminator.
+        while buf.remaining() > 0 {
+            let offset = Self::read_smart(&mut buf)?;
+            if offset == 0 {
+                break;
+            }
+            id += offset;
+
+            let mut packed = 0;
+            loop {
+                let offset = Self::read_smart(&mut buf)?;
+                if offset == 0 {
+                    break;
+                }
+                packed += offset - 1;
+
+                let z = packed & 0x3f;
+                let x = (packed >> 6) & 0x3f;
+                let level = (packed >> 12) & 0x3;
+                if buf.remaining() < 1 {
+                    return Err("locs ended early".into());
+                }
+                let info = buf.g1();
+
+                locs.push(MapLoc {
+                    id,
+                    coord: CoordGrid::from((base_x + x) as u16, level as u8, (base_z + z) as u16),
+                    shape: info >> 2,
+                    angle: info & 0x3,
+                });
+            }
+        }
+
+        Ok(locs)
+    }
+
+    fn read_smart(buf: &mut Packet) -> Result<i32, Box<dyn Error>> {
+        let size = match buf.data.get(buf.position) {
+            Some(&byte) if byte < 128 => 1,
+            Some(_) => 2,
+            None => return Err("locs ended early".into()),
+        };
+        if buf.remaining() < size {
+            return Err("locs ended early".into());
+        }
+        Ok(buf.gsmart())
+    }
+}
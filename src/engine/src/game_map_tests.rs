@@ -0,0 +1,32 @@
+use crate::game_map::GameMap;
+
+#[test]
+fn test_decode_locs() {
+    // Loc 5 at (1, 2) on level 0 as shape 10 angle 1, then loc 7 at (0, 0) on level 1 as shape 0 angle 0.
+    let packed = (1 << 6) | 2;
+    let data = vec![6, (packed + 1) as u8, (10 << 2) | 1, 0, 2, 0x90, 0x01, 0, 0, 0];
+    let locs = GameMap::decode_locs(data, 50, 50).unwrap();
+
+    assert_eq!(locs.len(), 2);
+    assert_eq!(locs[0].id, 5);
+    assert_eq!((locs[0].coord.x(), locs[0].coord.y(), locs[0].coord.z()), (3201, 0, 3202));
+    assert_eq!((locs[0].shape, locs[0].angle), (10, 1));
+    assert_eq!(locs[1].id, 7);
+    assert_eq!((locs[1].coord.x(), locs[1].coord.y(), locs[1].coord.z()), (3200, 1, 3200));
+}
+
+#[test]
+fn test_decode_locs_empty() {
+    assert!(GameMap::decode_locs(Vec::new(), 50, 50).unwrap().is_empty());
+    assert!(GameMap::decode_locs(vec![0], 50, 50).unwrap().is_empty());
+}
+
+#[test]
+fn test_decode_locs_truncated() {
+    // Missing the info byte.
+    assert!(GameMap::decode_locs(vec![6, 1], 50, 50).is_err());
+    // Half of a two byte smart.
+    assert!(GameMap::decode_locs(vec![6, 0x90], 50, 50).is_err());
+    // Missing the terminator of the loc's coords.
+    assert!(GameMap::decode_locs(vec![6, 1, 0], 50, 50).is_err());
+}
@@ -10,7 +10,7 @@ impl CoordGrid {
     }
     
     #[inline(always)]
-    pub fn from(x: u16, y: u8, z: u16) -> CoordGrid {
+    pub const fn from(x: u16, y: u8, z: u16) -> CoordGrid {
         CoordGrid {
             coord: ((z & 0x3FFF) as u32)
             | (((x & 0x3FFF) as u32) << 14)
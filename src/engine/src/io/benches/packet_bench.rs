@@ -37,10 +37,39 @@ fn benchmark_packet_g1(c: &mut Criterion) {
     });
 }
 
+fn benchmark_packet_pbit(c: &mut Criterion) {
+    c.bench_function("packet_pbit", |b| {
+        let mut packet = Packet::new(0);
+        b.iter(|| {
+            packet.position = 0;
+            packet.bits();
+            for i in 0..10000 {
+                packet.pbit(black_box(11), black_box(i % 2048));
+            }
+            packet.bytes();
+        });
+    });
+}
+
+fn benchmark_packet_gbit(c: &mut Criterion) {
+    c.bench_function("packet_gbit", |b| {
+        let mut packet = Packet::from(vec![0; 13750]);
+        b.iter(|| {
+            packet.position = 0;
+            packet.bits();
+            for _ in 0..10000 {
+                black_box(packet.gbit(black_box(11)));
+            }
+        });
+    });
+}
+
 criterion_group!(
     benches,
     benchmark_packet_p1,
     benchmark_packet_p2,
-    benchmark_packet_g1
+    benchmark_packet_g1,
+    benchmark_packet_pbit,
+    benchmark_packet_gbit
 );
 criterion_main!(benches);
@@ -0,0 +1,21 @@
+use crate::io::client::codec::message_decoder::MessageDecoder;
+use crate::io::client::model::move_click::MoveClickMessage;
+use crate::io::client::protocol::client_protocol::ClientProtocol;
+use crate::io::packet::Packet;
+
+pub struct MoveGameClickDecoder;
+
+impl MessageDecoder for MoveGameClickDecoder {
+    type Message = MoveClickMessage;
+
+    fn protocol(&self) -> &ClientProtocol {
+        &ClientProtocol::MOVE_GAMECLICK
+    }
+
+    fn decode(&self, packet: &mut Packet, _length: usize) -> Box<Self::Message> {
+        let x = packet.ig2();
+        let ctrl_held = packet.g1() == 1;
+        let z = packet.g2add();
+        Box::new(MoveClickMessage { x, z, ctrl_held })
+    }
+}
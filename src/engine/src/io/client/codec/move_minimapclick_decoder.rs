@@ -0,0 +1,22 @@
+use crate::io::client::codec::message_decoder::MessageDecoder;
+use crate::io::client::model::move_click::MoveClickMessage;
+use crate::io::client::protocol::client_protocol::ClientProtocol;
+use crate::io::packet::Packet;
+
+pub struct MoveMinimapClickDecoder;
+
+impl MessageDecoder for MoveMinimapClickDecoder {
+    type Message = MoveClickMessage;
+
+    fn protocol(&self) -> &ClientProtocol {
+        &ClientProtocol::MOVE_MINIMAPCLICK
+    }
+
+    /// Same layout as a game click, followed by 14 bytes of minimap and camera state that aren't needed.
+    fn decode(&self, packet: &mut Packet, _length: usize) -> Box<Self::Message> {
+        let x = packet.ig2();
+        let ctrl_held = packet.g1() == 1;
+        let z = packet.g2add();
+        Box::new(MoveClickMessage { x, z, ctrl_held })
+    }
+}
@@ -0,0 +1,19 @@
+use crate::io::client::codec::message_decoder::MessageDecoder;
+use crate::io::client::model::resume_p_countdialog::ResumePCountDialogMessage;
+use crate::io::client::protocol::client_protocol::ClientProtocol;
+use crate::io::packet::Packet;
+
+pub struct ResumePCountDialogDecoder;
+
+impl MessageDecoder for ResumePCountDialogDecoder {
+    type Message = ResumePCountDialogMessage;
+
+    fn protocol(&self) -> &ClientProtocol {
+        &ClientProtocol::RESUME_P_COUNTDIALOG
+    }
+
+    fn decode(&self, packet: &mut Packet, _length: usize) -> Box<Self::Message> {
+        let input = packet.g4();
+        Box::new(ResumePCountDialogMessage{input})
+    }
+}
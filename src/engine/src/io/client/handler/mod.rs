@@ -1,3 +1,6 @@
 pub mod message_handler;
 pub mod window_status_handler;
-pub mod verification_handler;
\ No newline at end of file
+pub mod verification_handler;
+pub mod move_click_handler;
+pub mod resume_pausebutton_handler;
+pub mod resume_p_countdialog_handler;
\ No newline at end of file
@@ -0,0 +1,34 @@
+use crate::entity::entity::EntityBehavior;
+use crate::entity::player::Player;
+use crate::grid::coord_grid::CoordGrid;
+use crate::io::client::handler::message_handler::MessageHandler;
+use crate::io::client::model::move_click::MoveClickMessage;
+
+/// The client only has 104x104 tiles around the build area origin loaded.
+const SCENE_SIZE: i32 = 104;
+
+pub struct MoveClickHandler;
+
+impl MessageHandler for MoveClickHandler {
+    type Message = MoveClickMessage;
+
+    fn handle(&self, message: &Self::Message, player: &mut Player) -> bool {
+        if player.delayed() {
+            return false;
+        }
+
+        let origin = player.get_origin_coord();
+        let local_x = message.x as i32 - ((origin.central_x() as i32 - 6) << 3);
+        let local_z = message.z as i32 - ((origin.central_z() as i32 - 6) << 3);
+        if !(0..SCENE_SIZE).contains(&local_x) || !(0..SCENE_SIZE).contains(&local_z) {
+            return false;
+        }
+
+        // Only the destination is trusted, the route itself is found again in [Player::path_to_move_click].
+        player.clear_pending_action();
+        player.user_path.clear();
+        player.user_path.push(CoordGrid::from(message.x, player.coord().y(), message.z).coord as i32);
+        player.temp_run = message.ctrl_held;
+        true
+    }
+}
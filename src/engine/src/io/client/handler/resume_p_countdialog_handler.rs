@@ -0,0 +1,21 @@
+use crate::entity::player::Player;
+use crate::io::client::handler::message_handler::MessageHandler;
+use crate::io::client::model::resume_p_countdialog::ResumePCountDialogMessage;
+use crate::script::script_state::ScriptState;
+
+pub struct ResumePCountDialogHandler;
+
+impl MessageHandler for ResumePCountDialogHandler {
+    type Message = ResumePCountDialogMessage;
+
+    fn handle(&self, message: &Self::Message, player: &mut Player) -> bool {
+        match player.active_script.take_if(|script| script.execution == ScriptState::COUNTDIALOG) {
+            Some(mut script) => {
+                script.last_int = message.input;
+                player.resumed_script = Some(script);
+                true
+            },
+            None => false,
+        }
+    }
+}
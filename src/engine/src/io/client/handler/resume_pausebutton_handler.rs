@@ -0,0 +1,21 @@
+use crate::entity::player::Player;
+use crate::io::client::handler::message_handler::MessageHandler;
+use crate::io::client::model::resume_pausebutton::ResumePauseButtonMessage;
+use crate::script::script_state::ScriptState;
+
+pub struct ResumePauseButtonHandler;
+
+impl MessageHandler for ResumePauseButtonHandler {
+    type Message = ResumePauseButtonMessage;
+
+    fn handle(&self, _message: &Self::Message, player: &mut Player) -> bool {
+        // Only a script waiting on a button gets resumed, anything else is a stale click.
+        match player.active_script.take_if(|script| script.execution == ScriptState::PAUSEBUTTON) {
+            Some(script) => {
+                player.resumed_script = Some(script);
+                true
+            },
+            None => false,
+        }
+    }
+}
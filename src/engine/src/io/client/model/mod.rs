@@ -2,4 +2,7 @@ mod no_timeout;
 pub mod window_status;
 pub mod verification;
 pub mod event_camera_position;
-pub mod event_applet_focus;
\ No newline at end of file
+pub mod event_applet_focus;
+pub mod move_click;
+pub mod resume_pausebutton;
+pub mod resume_p_countdialog;
\ No newline at end of file
@@ -0,0 +1,20 @@
+use std::any::Any;
+use crate::io::client::incoming_message::IncomingMessage;
+use crate::io::client::protocol::client_protocol_category::ClientProtocolCategory;
+
+/// A walk request from clicking the game scene or the minimap.
+pub struct MoveClickMessage {
+    pub(crate) x: u16,
+    pub(crate) z: u16,
+    pub(crate) ctrl_held: bool,
+}
+
+impl IncomingMessage for MoveClickMessage {
+    fn category(&self) -> ClientProtocolCategory {
+        ClientProtocolCategory::USER_EVENT
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
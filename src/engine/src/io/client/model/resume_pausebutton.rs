@@ -0,0 +1,18 @@
+use std::any::Any;
+use crate::io::client::incoming_message::IncomingMessage;
+use crate::io::client::protocol::client_protocol_category::ClientProtocolCategory;
+
+pub struct ResumePauseButtonMessage {
+    pub(crate) slot: u16,
+    pub(crate) component: i32,
+}
+
+impl IncomingMessage for ResumePauseButtonMessage {
+    fn category(&self) -> ClientProtocolCategory {
+        ClientProtocolCategory::USER_EVENT
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -19,6 +19,10 @@ impl ClientProtocol {
     pub const VERIFICATION: Self = ClientProtocol { id: ProtocolId(20), length: 4 };
     pub const WINDOW_STATUS: Self = ClientProtocol { id: ProtocolId(243), length: 6 };
     pub const TRANSMITVAR_VERIFYID: Self = ClientProtocol { id: ProtocolId(177), length: 2 };
+    pub const MOVE_GAMECLICK: Self = ClientProtocol { id: ProtocolId(215), length: -1 };
+    pub const MOVE_MINIMAPCLICK: Self = ClientProtocol { id: ProtocolId(39), length: -1 };
+    pub const RESUME_PAUSEBUTTON: Self = ClientProtocol { id: ProtocolId(132), length: 6 };
+    pub const RESUME_P_COUNTDIALOG: Self = ClientProtocol { id: ProtocolId(23), length: 4 };
     
     pub const LOC_ACTION_EXAMINE: Self = ClientProtocol { id: ProtocolId(94), length: 2 };
 }
@@ -34,6 +38,10 @@ lazy_static! {
             ClientProtocol::VERIFICATION,
             ClientProtocol::WINDOW_STATUS,
             ClientProtocol::TRANSMITVAR_VERIFYID,
+            ClientProtocol::MOVE_GAMECLICK,
+            ClientProtocol::MOVE_MINIMAPCLICK,
+            ClientProtocol::RESUME_PAUSEBUTTON,
+            ClientProtocol::RESUME_P_COUNTDIALOG,
         ];
         
         let mut map = HashMap::new();
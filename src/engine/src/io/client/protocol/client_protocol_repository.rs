@@ -6,9 +6,16 @@ use crate::entity::player::Player;
 use crate::io::client::codec::event_applet_focus_decoder::EventAppletFocusDecoder;
 use crate::io::client::codec::event_camera_position_decoder::EventCameraPositionDecoder;
 use crate::io::client::codec::message_decoder::MessageDecoder;
+use crate::io::client::codec::move_gameclick_decoder::MoveGameClickDecoder;
+use crate::io::client::codec::move_minimapclick_decoder::MoveMinimapClickDecoder;
+use crate::io::client::codec::resume_p_countdialog_decoder::ResumePCountDialogDecoder;
+use crate::io::client::codec::resume_pausebutton_decoder::ResumePauseButtonDecoder;
 use crate::io::client::codec::verification_decoder::VerificationDecoder;
 use crate::io::client::codec::window_status_decoder::WindowStatusDecoder;
 use crate::io::client::handler::message_handler::MessageHandler;
+use crate::io::client::handler::move_click_handler::MoveClickHandler;
+use crate::io::client::handler::resume_p_countdialog_handler::ResumePCountDialogHandler;
+use crate::io::client::handler::resume_pausebutton_handler::ResumePauseButtonHandler;
 use crate::io::client::handler::verification_handler::VerificationHandler;
 use crate::io::client::handler::window_status_handler::WindowStatusHandler;
 use crate::io::client::incoming_message::IncomingMessage;
@@ -169,6 +176,10 @@ impl ClientProtocolRepository {
         register_protocol!(VerificationDecoder, VerificationHandler);
         register_protocol!(EventCameraPositionDecoder);
         register_protocol!(EventAppletFocusDecoder);
+        register_protocol!(MoveGameClickDecoder, MoveClickHandler);
+        register_protocol!(MoveMinimapClickDecoder, MoveClickHandler);
+        register_protocol!(ResumePauseButtonDecoder, ResumePauseButtonHandler);
+        register_protocol!(ResumePCountDialogDecoder, ResumePCountDialogHandler);
 
         repository
     }
@@ -0,0 +1,94 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use constants::proxy::proxy::BUFFER_SIZE;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+
+/// The game's end of a socket owned by the [Network](crate::io::network::Network).
+///
+/// A reader task pushes whatever arrives onto `inbound` and a writer task sends whatever is pushed onto `outbound`,
+/// so the tick only ever polls and queues, it never waits on the socket itself.
+#[derive(Debug)]
+pub struct ClientSocket {
+    pub inbound: mpsc::Receiver<Vec<u8>>,
+    pub outbound: mpsc::Sender<Vec<u8>>,
+    pub peer_addr: Option<SocketAddr>,
+    /// Set by either task once the socket has closed or errored.
+    closed: Arc<AtomicBool>,
+    reader: AbortHandle,
+}
+
+impl ClientSocket {
+    /// Reads held for the tick before the reader stops pulling from the socket.
+    const INBOUND_CHUNKS: usize = 64;
+    /// Flushes a client can fall behind on before it's dropped, a little over half a minute of ticks.
+    pub const OUTBOUND_FLUSHES: usize = 64;
+    /// Longest a single write may wait on the client, so a peer that stopped reading can't hold the writer forever.
+    const WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Hand a socket to a reader and a writer task, must be called from inside the network runtime.
+    pub fn spawn(stream: TcpStream) -> ClientSocket {
+        let peer_addr = stream.peer_addr().ok();
+        let _ = stream.set_nodelay(true);
+        let (mut read_half, mut write_half) = stream.into_split();
+
+        let closed = Arc::new(AtomicBool::new(false));
+        let (inbound_tx, inbound) = mpsc::channel::<Vec<u8>>(Self::INBOUND_CHUNKS);
+        let (outbound, mut outbound_rx) = mpsc::channel::<Vec<u8>>(Self::OUTBOUND_FLUSHES);
+
+        let reader_closed = Arc::clone(&closed);
+        let reader = tokio::spawn(async move {
+            let mut buffer = vec![0u8; BUFFER_SIZE];
+            loop {
+                match read_half.read(&mut buffer).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => {
+                        if inbound_tx.send(buffer[..read].to_vec()).await.is_err() {
+                            break;
+                        }
+                    },
+                }
+            }
+            reader_closed.store(true, Ordering::SeqCst);
+        });
+
+        // Keeps going until the game drops its sender, so anything queued before a disconnect still goes out.
+        let writer_closed = Arc::clone(&closed);
+        tokio::spawn(async move {
+            while let Some(data) = outbound_rx.recv().await {
+                match tokio::time::timeout(Self::WRITE_TIMEOUT, write_half.write_all(&data)).await {
+                    Ok(Ok(())) => {},
+                    _ => break,
+                }
+            }
+            writer_closed.store(true, Ordering::SeqCst);
+            let _ = tokio::time::timeout(Self::WRITE_TIMEOUT, write_half.shutdown()).await;
+        });
+
+        ClientSocket {
+            inbound,
+            outbound,
+            peer_addr,
+            closed,
+            reader: reader.abort_handle(),
+        }
+    }
+
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        !self.closed.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for ClientSocket {
+    /// A reader on a half-open socket would otherwise wait on it forever.
+    /// The writer stops by itself once `outbound` is dropped with it, or at the latest after [ClientSocket::WRITE_TIMEOUT].
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.reader.abort();
+    }
+}
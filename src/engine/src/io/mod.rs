@@ -1,9 +1,13 @@
 pub mod client;
 pub mod server;
+pub mod client_socket;
 pub mod connection;
+pub mod network;
 pub mod packet;
 pub mod client_state;
 pub mod crc;
 mod packet_tests;
+#[cfg(test)]
+mod network_tests;
 pub mod rsa;
 pub mod isaac;
\ No newline at end of file
@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use log::{debug, error, info};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::{Builder, Runtime};
+use crate::auth::authenticator::Authenticator;
+use crate::auth::login_throttle::LoginThrottle;
+use crate::engine::Engine;
+use crate::entity::player::Player;
+use crate::io::client_socket::ClientSocket;
+
+/// Owns every game socket, on a runtime of its own so nothing the tick does waits on a client.
+///
+/// New sockets go through the login handshake here, logged in players are handed over through `new_players`.
+pub struct Network {
+    // Dropping the runtime closes every socket, so it lives as long as the engine does.
+    _runtime: Runtime,
+}
+
+impl Network {
+    /// Longest a socket gets to finish logging in.
+    const LOGIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+    pub fn start(
+        listen_addr: &str,
+        new_players: Arc<Mutex<Vec<Player>>>,
+        authenticator: Arc<dyn Authenticator>,
+        login_throttle: Arc<LoginThrottle>,
+    ) -> std::io::Result<Network> {
+        let runtime = Builder::new_multi_thread()
+            .thread_name("network")
+            .enable_all()
+            .build()?;
+
+        let listener = runtime.block_on(TcpListener::bind(listen_addr))
+            .map_err(|e| std::io::Error::new(e.kind(), format!("failed to bind to {}: {}", listen_addr, e)))?;
+        info!("Starting server on {}", listen_addr);
+
+        runtime.spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(Self::login(stream, Arc::clone(&new_players), Arc::clone(&authenticator), Arc::clone(&login_throttle)));
+                    },
+                    Err(e) => error!("Connection failed: {}", e),
+                }
+            }
+        });
+
+        Ok(Network { _runtime: runtime })
+    }
+
+    /// Walk a new socket through the title packets, queueing the player for [Engine::process_logins] once they're in.
+    async fn login(
+        mut stream: TcpStream,
+        new_players: Arc<Mutex<Vec<Player>>>,
+        authenticator: Arc<dyn Authenticator>,
+        login_throttle: Arc<LoginThrottle>,
+    ) {
+        let login = tokio::time::timeout(Self::LOGIN_TIMEOUT, async {
+            loop {
+                if let Some(player) = Engine::on_new_connection(&mut stream, &authenticator, &login_throttle).await? {
+                    return Ok::<Player, std::io::Error>(player);
+                }
+            }
+        }).await;
+
+        match login {
+            Ok(Ok(mut player)) => {
+                player.client.socket = Some(ClientSocket::spawn(stream));
+                new_players.lock().unwrap().push(player);
+            },
+            Ok(Err(e)) => {
+                debug!("Connection closed during login: {}", e);
+                let _ = stream.shutdown().await;
+            },
+            Err(_) => {
+                debug!("Connection timed out during login");
+                let _ = stream.shutdown().await;
+            },
+        }
+    }
+}
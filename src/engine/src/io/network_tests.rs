@@ -0,0 +1,37 @@
+use std::sync::Arc;
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpListener, TcpStream};
+use crate::auth::file_authenticator::FileAuthenticator;
+use crate::auth::login_throttle::LoginThrottle;
+use crate::io::client_socket::ClientSocket;
+use crate::io::network::Network;
+
+#[test]
+fn test_start_fails_when_port_taken() {
+    let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = taken.local_addr().unwrap().to_string();
+
+    let network = Network::start(
+        &addr,
+        Default::default(),
+        Arc::new(FileAuthenticator::new(std::env::temp_dir().join("unused_accounts.txt"))),
+        Arc::new(LoginThrottle::new()),
+    );
+    assert!(network.is_err());
+}
+
+#[tokio::test]
+async fn test_dropped_socket_sends_queued_data_then_closes() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+    let (server, _) = listener.accept().await.unwrap();
+
+    let socket = ClientSocket::spawn(server);
+    socket.outbound.try_send(vec![1, 2, 3]).unwrap();
+    drop(socket);
+
+    // The client never closes its side, the server still lets go of the socket.
+    let mut received = Vec::new();
+    client.read_to_end(&mut received).await.unwrap();
+    assert_eq!(received, vec![1, 2, 3]);
+}
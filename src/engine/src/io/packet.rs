@@ -5,6 +5,9 @@ pub struct Packet {
     pub bit_position: usize,
 }
 
+/// Masks for the lowest `n` bits of a byte, indexed by `n`.
+const BITMASK: [u8; 9] = [0x0, 0x1, 0x3, 0x7, 0xf, 0x1f, 0x3f, 0x7f, 0xff];
+
 // Helper function for branch prediction optimization
 macro_rules! likely {
     ($expr:expr) => {
@@ -13,12 +16,12 @@ macro_rules! likely {
 }
 
 impl Packet {
-    /// Create a 'Packet' with a fixed sized allocated buffer.
+    /// Create an empty 'Packet' with `size` bytes reserved up front.
+    /// The buffer grows past that as it is written to, so `data` only ever holds what was written.
+    /// Anything that needs to write at an offset should start from a zeroed [Packet::from] instead.
     pub fn new(size: usize) -> Packet {
-        let mut data = Vec::with_capacity(size);
-        data.resize(size, 0);
         Packet {
-            data,
+            data: Vec::with_capacity(size),
             position: 0,
             bit_position: 0,
         }
@@ -78,6 +81,17 @@ impl Packet {
         }
         self.position += 1;
     }
+
+    #[inline(always)]
+    pub fn p1neg(&mut self, value: i32) {
+        let value_u8 = value.wrapping_neg() as u8;
+        if self.position < self.data.len() {
+            self.data[self.position] = value_u8;
+        } else {
+            self.data.push(value_u8);
+        }
+        self.position += 1;
+    }
     
     #[inline(always)]
     pub fn p2(&mut self, value: i32) {
@@ -559,4 +573,71 @@ impl Packet {
     pub fn bytes(&mut self) {
         self.position = (self.bit_position + 7) >> 3;
     }
-}
\ No newline at end of file
+
+    /// The byte length written so far in bit access, a partly written byte counting as a whole one.
+    #[inline(always)]
+    pub fn bit_length(&self) -> usize {
+        (self.bit_position + 7) >> 3
+    }
+
+    /// Writes the lowest `n` bits of `value` at the current bit position, most significant bit first.
+    /// The underlying buffer grows as needed, call [Packet::bytes] once done to resume byte access.
+    #[inline(always)]
+    pub fn pbit(&mut self, mut n: usize, value: i32) {
+        if n == 0 {
+            return;
+        }
+
+        let position = self.bit_position;
+        self.bit_position += n;
+
+        let end = (self.bit_position + 7) >> 3;
+        if end > self.data.len() {
+            self.data.resize(end, 0);
+        }
+
+        let value = value as u32;
+        let mut byte_position = position >> 3;
+        let mut remaining = 8 - (position & 7);
+
+        while n > remaining {
+            let mask = BITMASK[remaining];
+            let bits = (value >> (n - remaining)) as u8 & mask;
+            self.data[byte_position] = (self.data[byte_position] & !mask) | bits;
+            byte_position += 1;
+            n -= remaining;
+            remaining = 8;
+        }
+
+        let shift = remaining - n;
+        let mask = BITMASK[n] << shift;
+        let bits = (value as u8 & BITMASK[n]) << shift;
+        self.data[byte_position] = (self.data[byte_position] & !mask) | bits;
+    }
+
+    /// Reads `n` bits from the current bit position, most significant bit first.
+    /// Bits past the end of the buffer read as zero.
+    #[inline(always)]
+    pub fn gbit(&mut self, mut n: usize) -> i32 {
+        let mut byte_position = self.bit_position >> 3;
+        let mut remaining = 8 - (self.bit_position & 7);
+        let mut value: u32 = 0;
+        self.bit_position += n;
+
+        if n == 0 {
+            return 0;
+        }
+
+        while n > remaining {
+            let byte = self.data.get(byte_position).copied().unwrap_or(0);
+            value |= ((byte & BITMASK[remaining]) as u32) << (n - remaining);
+            byte_position += 1;
+            n -= remaining;
+            remaining = 8;
+        }
+
+        let byte = self.data.get(byte_position).copied().unwrap_or(0);
+        value |= ((byte >> (remaining - n)) & BITMASK[n]) as u32;
+        value as i32
+    }
+}
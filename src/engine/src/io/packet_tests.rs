@@ -24,6 +24,14 @@ fn test_p1() {
     assert_eq!(packet.data, vec![255]);
 }
 
+#[test]
+fn test_p1neg() {
+    let mut packet = Packet::new(10);
+    packet.p1neg(5);
+    packet.p1neg(-128);
+    assert_eq!(packet.data, vec![251, 128]);
+}
+
 #[test]
 fn test_p2() {
     let mut packet = Packet::new(10);
@@ -54,4 +62,146 @@ fn test_g2() {
 fn test_g4() {
     let mut packet = Packet::from(vec![0x01, 0x02, 0x03, 0x04]);
     assert_eq!(packet.g4(), 0x01020304);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_pbit_within_byte() {
+    let mut packet = Packet::from(Vec::new());
+    packet.bits();
+    packet.pbit(1, 1);
+    packet.pbit(2, 2);
+    packet.pbit(5, 0b10101);
+    packet.bytes();
+    assert_eq!(packet.data, vec![0b1101_0101]);
+    assert_eq!(packet.position, 1);
+}
+
+#[test]
+fn test_pbit_across_bytes() {
+    let mut packet = Packet::from(Vec::new());
+    packet.bits();
+    packet.pbit(3, 0b101);
+    packet.pbit(11, 2047);
+    packet.pbit(13, 0x1abc);
+    packet.bytes();
+    assert_eq!(packet.data, vec![0b1011_1111, 0b1111_1111, 0b0101_0111, 0b1000_0000]);
+    assert_eq!(packet.position, 4);
+}
+
+#[test]
+fn test_pbit_masks_value() {
+    let mut packet = Packet::from(Vec::new());
+    packet.bits();
+    packet.pbit(5, -1);
+    packet.pbit(3, 0);
+    packet.bytes();
+    assert_eq!(packet.data, vec![0b1111_1000]);
+}
+
+#[test]
+fn test_pbit_overwrites_existing() {
+    let mut packet = Packet::from(vec![0xff, 0xff]);
+    packet.bits();
+    packet.pbit(12, 0);
+    assert_eq!(packet.data, vec![0x00, 0x0f]);
+}
+
+#[test]
+fn test_pbit_after_bytes() {
+    let mut packet = Packet::from(Vec::new());
+    packet.p1(0x12);
+    packet.bits();
+    packet.pbit(4, 0xf);
+    packet.bytes();
+    packet.p1(0x34);
+    assert_eq!(packet.data, vec![0x12, 0xf0, 0x34]);
+}
+
+#[test]
+fn test_pbit_32() {
+    let mut packet = Packet::from(Vec::new());
+    packet.bits();
+    packet.pbit(4, 0);
+    packet.pbit(32, 0x12345678);
+    packet.bytes();
+    assert_eq!(packet.data, vec![0x01, 0x23, 0x45, 0x67, 0x80]);
+}
+
+#[test]
+fn test_gbit() {
+    let mut packet = Packet::from(vec![0b1011_1111, 0b1111_1111, 0b0101_0111, 0b1000_0000]);
+    packet.bits();
+    assert_eq!(packet.gbit(3), 0b101);
+    assert_eq!(packet.gbit(11), 2047);
+    assert_eq!(packet.gbit(13), 0x1abc);
+    packet.bytes();
+    assert_eq!(packet.position, 4);
+}
+
+#[test]
+fn test_gbit_past_end() {
+    let mut packet = Packet::from(vec![0xff]);
+    packet.bits();
+    assert_eq!(packet.gbit(4), 0xf);
+    assert_eq!(packet.gbit(8), 0xf0);
+    assert_eq!(packet.gbit(8), 0);
+}
+
+#[test]
+fn test_bit_roundtrip() {
+    let widths = [1, 2, 3, 5, 7, 8, 11, 13, 16, 24, 31, 32];
+    let mut packet = Packet::from(Vec::new());
+    packet.bits();
+    for (i, &n) in widths.iter().enumerate() {
+        packet.pbit(n, (0x5a5a5a5a_u32 >> i) as i32);
+    }
+    packet.bytes();
+
+    packet.position = 0;
+    packet.bits();
+    for (i, &n) in widths.iter().enumerate() {
+        let mask = if n == 32 { u32::MAX } else { (1 << n) - 1 };
+        assert_eq!(packet.gbit(n) as u32, (0x5a5a5a5a_u32 >> i) & mask);
+    }
+}
+
+#[test]
+fn test_packet_new_reserves_only() {
+    let packet = Packet::new(64);
+    assert!(packet.data.is_empty());
+    assert!(packet.data.capacity() >= 64);
+}
+
+#[test]
+fn test_packet_new_grows_on_write() {
+    let mut packet = Packet::new(1);
+    packet.p1(1);
+    packet.p2(0x0203);
+    packet.p3(0x040506);
+    packet.p4(0x0708090a);
+    packet.p8(0x0b0c0d0e0f101112);
+    packet.pjstr("ab", 10);
+    packet.pbytes(&[0x13, 0x14], 0, 2);
+    assert_eq!(packet.len(), packet.position);
+    assert_eq!(packet.data, vec![
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a,
+        0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, b'a', b'b', 10, 0x13, 0x14
+    ]);
+
+    packet.position = 0;
+    assert_eq!(packet.g1(), 1);
+    assert_eq!(packet.g2(), 0x0203);
+    assert_eq!(packet.g3(), 0x040506);
+    assert_eq!(packet.g4(), 0x0708090a);
+    assert_eq!(packet.g8(), 0x0b0c0d0e0f101112);
+}
+
+#[test]
+fn test_packet_new_grows_on_pbit() {
+    let mut packet = Packet::new(0);
+    packet.bits();
+    packet.pbit(11, 2047);
+    packet.bytes();
+    assert_eq!(packet.data, vec![0xff, 0xe0]);
+    assert_eq!(packet.len(), packet.position);
+}
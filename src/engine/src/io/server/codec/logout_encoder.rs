@@ -0,0 +1,22 @@
+use crate::io::packet::Packet;
+use crate::io::server::codec::message_encoder::MessageEncoder;
+use crate::io::server::model::logout::Logout;
+use crate::io::server::protocol::server_protocol::ServerProtocol;
+
+pub struct LogoutEncoder;
+
+impl LogoutEncoder {
+    #[inline]
+    pub fn new() -> Self {
+        LogoutEncoder
+    }
+}
+
+impl MessageEncoder<Logout> for LogoutEncoder {
+    #[inline]
+    fn protocol(&self) -> ServerProtocol {
+        ServerProtocol::LOGOUT
+    }
+
+    fn encode(&self, _: &mut Packet, _: Logout) {}
+}
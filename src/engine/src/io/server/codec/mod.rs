@@ -2,4 +2,10 @@ pub mod message_encoder;
 pub mod rebuild_normal_encoder;
 pub mod if_opentop_encoder;
 pub mod if_opensub_encoder;
-pub mod message_game_encoder;
\ No newline at end of file
+pub mod message_game_encoder;
+pub mod player_info_encoder;
+pub mod npc_info_encoder;
+pub mod logout_encoder;
+pub mod update_reboot_time_encoder;
+pub mod varp_small_encoder;
+pub mod varp_large_encoder;
\ No newline at end of file
@@ -0,0 +1,26 @@
+use crate::io::packet::Packet;
+use crate::io::server::codec::message_encoder::MessageEncoder;
+use crate::io::server::model::npc_info::NpcInfo;
+use crate::io::server::protocol::server_protocol::ServerProtocol;
+
+pub struct NpcInfoEncoder;
+
+impl NpcInfoEncoder {
+    #[inline]
+    pub fn new() -> Self {
+        NpcInfoEncoder
+    }
+}
+
+impl MessageEncoder<NpcInfo> for NpcInfoEncoder {
+    #[inline]
+    fn protocol(&self) -> ServerProtocol {
+        ServerProtocol::NPC_INFO
+    }
+
+    fn encode(&self, packet: &mut Packet, message: NpcInfo) {
+        let info = message.info();
+        packet.p2(info.len() as i32);
+        packet.pbytes(info, 0, info.len());
+    }
+}
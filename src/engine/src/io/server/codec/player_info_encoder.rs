@@ -0,0 +1,26 @@
+use crate::io::packet::Packet;
+use crate::io::server::codec::message_encoder::MessageEncoder;
+use crate::io::server::model::player_info::PlayerInfo;
+use crate::io::server::protocol::server_protocol::ServerProtocol;
+
+pub struct PlayerInfoEncoder;
+
+impl PlayerInfoEncoder {
+    #[inline]
+    pub fn new() -> Self {
+        PlayerInfoEncoder
+    }
+}
+
+impl MessageEncoder<PlayerInfo> for PlayerInfoEncoder {
+    #[inline]
+    fn protocol(&self) -> ServerProtocol {
+        ServerProtocol::PLAYER_INFO
+    }
+
+    fn encode(&self, packet: &mut Packet, message: PlayerInfo) {
+        let info = message.info();
+        packet.p2(info.len() as i32);
+        packet.pbytes(info, 0, info.len());
+    }
+}
@@ -0,0 +1,25 @@
+use crate::io::packet::Packet;
+use crate::io::server::codec::message_encoder::MessageEncoder;
+use crate::io::server::model::update_reboot_time::UpdateRebootTime;
+use crate::io::server::protocol::server_protocol::ServerProtocol;
+
+pub struct UpdateRebootTimeEncoder;
+
+impl UpdateRebootTimeEncoder {
+    #[inline]
+    pub fn new() -> Self {
+        UpdateRebootTimeEncoder
+    }
+}
+
+impl MessageEncoder<UpdateRebootTime> for UpdateRebootTimeEncoder {
+    #[inline]
+    fn protocol(&self) -> ServerProtocol {
+        ServerProtocol::UPDATE_REBOOT_TIME
+    }
+
+    fn encode(&self, packet: &mut Packet, message: UpdateRebootTime) {
+        // The client counts this down itself, 30 of its cycles to a tick.
+        packet.p2(message.ticks().clamp(0, 0xffff));
+    }
+}
@@ -0,0 +1,25 @@
+use crate::io::packet::Packet;
+use crate::io::server::codec::message_encoder::MessageEncoder;
+use crate::io::server::model::varp_large::VarpLarge;
+use crate::io::server::protocol::server_protocol::ServerProtocol;
+
+pub struct VarpLargeEncoder;
+
+impl VarpLargeEncoder {
+    #[inline]
+    pub fn new() -> Self {
+        VarpLargeEncoder
+    }
+}
+
+impl MessageEncoder<VarpLarge> for VarpLargeEncoder {
+    #[inline]
+    fn protocol(&self) -> ServerProtocol {
+        ServerProtocol::VARP_LARGE
+    }
+
+    fn encode(&self, packet: &mut Packet, message: VarpLarge) {
+        packet.p4(message.value());
+        packet.p2add(message.id() as i32);
+    }
+}
@@ -0,0 +1,25 @@
+use crate::io::packet::Packet;
+use crate::io::server::codec::message_encoder::MessageEncoder;
+use crate::io::server::model::varp_small::VarpSmall;
+use crate::io::server::protocol::server_protocol::ServerProtocol;
+
+pub struct VarpSmallEncoder;
+
+impl VarpSmallEncoder {
+    #[inline]
+    pub fn new() -> Self {
+        VarpSmallEncoder
+    }
+}
+
+impl MessageEncoder<VarpSmall> for VarpSmallEncoder {
+    #[inline]
+    fn protocol(&self) -> ServerProtocol {
+        ServerProtocol::VARP_SMALL
+    }
+
+    fn encode(&self, packet: &mut Packet, message: VarpSmall) {
+        packet.p2add(message.id() as i32);
+        packet.p1neg(message.value());
+    }
+}
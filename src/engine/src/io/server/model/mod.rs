@@ -2,4 +2,9 @@ pub mod rebuild_normal;
 pub mod if_opentop;
 pub mod if_opensub;
 pub mod message_game;
-pub mod logout;
\ No newline at end of file
+pub mod logout;
+pub mod player_info;
+pub mod npc_info;
+pub mod update_reboot_time;
+pub mod varp_small;
+pub mod varp_large;
\ No newline at end of file
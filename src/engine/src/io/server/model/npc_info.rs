@@ -0,0 +1,13 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct NpcInfo {
+    info: Vec<u8>,
+}
+
+impl NpcInfo {
+    /// `info` is the payload already packed by the npc renderer for this viewer.
+    pub fn new(info: Vec<u8>) -> NpcInfo {
+        NpcInfo { info }
+    }
+
+    pub fn info(&self) -> &[u8] { &self.info }
+}
@@ -0,0 +1,13 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerInfo {
+    info: Vec<u8>,
+}
+
+impl PlayerInfo {
+    /// `info` is the payload already packed by the player renderer for this viewer.
+    pub fn new(info: Vec<u8>) -> PlayerInfo {
+        PlayerInfo { info }
+    }
+
+    pub fn info(&self) -> &[u8] { &self.info }
+}
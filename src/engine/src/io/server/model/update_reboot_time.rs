@@ -0,0 +1,12 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateRebootTime {
+    ticks: i32,
+}
+
+impl UpdateRebootTime {
+    pub fn new(ticks: i32) -> UpdateRebootTime {
+        UpdateRebootTime { ticks }
+    }
+
+    pub fn ticks(&self) -> i32 { self.ticks }
+}
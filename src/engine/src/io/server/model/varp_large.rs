@@ -0,0 +1,14 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarpLarge {
+    id: u16,
+    value: i32,
+}
+
+impl VarpLarge {
+    pub fn new(id: u16, value: i32) -> VarpLarge {
+        VarpLarge { id, value }
+    }
+
+    pub fn id(&self) -> u16 { self.id }
+    pub fn value(&self) -> i32 { self.value }
+}
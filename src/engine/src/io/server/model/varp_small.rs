@@ -0,0 +1,14 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarpSmall {
+    id: u16,
+    value: i32,
+}
+
+impl VarpSmall {
+    pub fn new(id: u16, value: i32) -> VarpSmall {
+        VarpSmall { id, value }
+    }
+
+    pub fn id(&self) -> u16 { self.id }
+    pub fn value(&self) -> i32 { self.value }
+}
@@ -9,6 +9,11 @@ use crate::io::server::protocol::server_protocol_repository::ServerProtocolRepos
 use std::fmt::Debug;
 use crate::io::server::model::logout::Logout;
 use crate::io::server::model::message_game::Message_Game;
+use crate::io::server::model::npc_info::NpcInfo;
+use crate::io::server::model::player_info::PlayerInfo;
+use crate::io::server::model::update_reboot_time::UpdateRebootTime;
+use crate::io::server::model::varp_large::VarpLarge;
+use crate::io::server::model::varp_small::VarpSmall;
 
 pub trait OutgoingMessage: Debug + Send + PartialEq {
     fn priority(&self) -> ServerProtocolPriority;
@@ -40,8 +45,9 @@ macro_rules! define_outgoing_messages {
                     };
 
                     // Set protocol ID
-                    if player.client.encryptor.is_some() {
-                        // TODO - ISAAC handling 
+                    if let Some(encryptor) = player.client.encryptor.as_mut() {
+                        let encrypted = protocol.id.wrapping_add(encryptor.next());
+                        player.client.outbound.p1(encrypted);
                     } else {
                         player.client.outbound.p1(protocol.id);
                     }
@@ -49,7 +55,7 @@ macro_rules! define_outgoing_messages {
                     // Encode message directly
                     if let Some(encoder) = player.get_server_protocol_repository().get_encoder(self) {
                         encoder.encode(&mut player.client.outbound, self.clone());
-                        player.client.write_packet().expect("Write packet failed");
+                        player.client.write_packet();
                     }
                 }
             }
@@ -116,5 +122,10 @@ define_outgoing_messages!(
     (MessageGame, Message_Game, ServerProtocolPriority::IMMEDIATE),
     (IfOpenTop, If_OpenTop, ServerProtocolPriority::BUFFERED),
     (IfOpenSub, If_OpenSub, ServerProtocolPriority::BUFFERED),
-    (Logout, Logout, ServerProtocolPriority::IMMEDIATE)
+    (Logout, Logout, ServerProtocolPriority::IMMEDIATE),
+    (PlayerInfo, PlayerInfo, ServerProtocolPriority::BUFFERED),
+    (NpcInfo, NpcInfo, ServerProtocolPriority::BUFFERED),
+    (UpdateRebootTime, UpdateRebootTime, ServerProtocolPriority::IMMEDIATE),
+    (VarpSmall, VarpSmall, ServerProtocolPriority::BUFFERED),
+    (VarpLarge, VarpLarge, ServerProtocolPriority::BUFFERED)
 );
\ No newline at end of file
@@ -6,13 +6,34 @@ pub struct InfoProtocol {
 }
 
 impl InfoProtocol {
-    pub fn new(id: i32, length: i32) -> InfoProtocol {
+    pub const fn new(id: i32, length: i32) -> InfoProtocol {
         InfoProtocol {
             protocol: ServerProtocol::new(id, length),            
         }
     }
     
-    // CONSTS START HERE
+    // Player info masks, the id is the mask bit.
+    pub const PLAYER_HIT: InfoProtocol = InfoProtocol::new(0x1, 3);
+    pub const PLAYER_FACE_ENTITY: InfoProtocol = InfoProtocol::new(0x2, 2);
+    pub const PLAYER_SAY: InfoProtocol = InfoProtocol::new(0x4, -1);
+    pub const PLAYER_ANIM: InfoProtocol = InfoProtocol::new(0x8, 3);
+    pub const PLAYER_APPEARANCE: InfoProtocol = InfoProtocol::new(0x10, -1);
+    pub const PLAYER_BIG_UPDATE: InfoProtocol = InfoProtocol::new(0x20, 0);
+    pub const PLAYER_FACE_COORD: InfoProtocol = InfoProtocol::new(0x40, 4);
+    pub const PLAYER_CHAT: InfoProtocol = InfoProtocol::new(0x80, -1);
+    pub const PLAYER_SPOTANIM: InfoProtocol = InfoProtocol::new(0x100, 6);
+    pub const PLAYER_HIT2: InfoProtocol = InfoProtocol::new(0x200, 3);
+
+    // Npc info masks.
+    pub const NPC_CHANGE_TYPE: InfoProtocol = InfoProtocol::new(0x1, 2);
+    pub const NPC_HIT2: InfoProtocol = InfoProtocol::new(0x2, 3);
+    pub const NPC_FACE_ENTITY: InfoProtocol = InfoProtocol::new(0x4, 2);
+    pub const NPC_BIG_UPDATE: InfoProtocol = InfoProtocol::new(0x8, 0);
+    pub const NPC_ANIM: InfoProtocol = InfoProtocol::new(0x10, 3);
+    pub const NPC_SAY: InfoProtocol = InfoProtocol::new(0x20, -1);
+    pub const NPC_HIT: InfoProtocol = InfoProtocol::new(0x40, 3);
+    pub const NPC_SPOTANIM: InfoProtocol = InfoProtocol::new(0x80, 6);
+    pub const NPC_FACE_COORD: InfoProtocol = InfoProtocol::new(0x200, 4);
     
     pub const fn id(&self) -> i32 {
         self.protocol.id
@@ -21,4 +42,4 @@ impl InfoProtocol {
     pub const fn length(&self) -> i32 {
         self.protocol.length
     }
-}
\ No newline at end of file
+}
@@ -19,6 +19,8 @@ impl ServerProtocol {
     // var{p, c, bit}
     pub const CLIENT_SETVARC_SMALL: ServerProtocol = ServerProtocol::new(65, 5);
     pub const CLIENT_SETVARC_LARGE: ServerProtocol = ServerProtocol::new(69, 8);
+    pub const VARP_SMALL: ServerProtocol = ServerProtocol::new(60, 3);
+    pub const VARP_LARGE: ServerProtocol = ServerProtocol::new(226, 6);
     
     // Interfaces
     pub const IF_OPENTOP: ServerProtocol = ServerProtocol::new(145, 5);
@@ -2,13 +2,25 @@ use std::any::{Any, TypeId};
 use fnv::FnvHashMap;
 use crate::io::server::codec::if_opensub_encoder::If_OpenSub_Encoder;
 use crate::io::server::codec::if_opentop_encoder::If_OpenTop_Encoder;
+use crate::io::server::codec::logout_encoder::LogoutEncoder;
 use crate::io::server::codec::message_encoder::MessageEncoder;
 use crate::io::server::codec::message_game_encoder::Message_Game_Encoder;
+use crate::io::server::codec::npc_info_encoder::NpcInfoEncoder;
+use crate::io::server::codec::player_info_encoder::PlayerInfoEncoder;
 use crate::io::server::codec::rebuild_normal_encoder::RebuildNormalEncoder;
+use crate::io::server::codec::update_reboot_time_encoder::UpdateRebootTimeEncoder;
+use crate::io::server::codec::varp_large_encoder::VarpLargeEncoder;
+use crate::io::server::codec::varp_small_encoder::VarpSmallEncoder;
+use crate::io::server::model::logout::Logout;
 use crate::io::server::model::if_opensub::If_OpenSub;
 use crate::io::server::model::if_opentop::If_OpenTop;
 use crate::io::server::model::message_game::Message_Game;
+use crate::io::server::model::npc_info::NpcInfo;
+use crate::io::server::model::player_info::PlayerInfo;
 use crate::io::server::model::rebuild_normal::RebuildNormal;
+use crate::io::server::model::update_reboot_time::UpdateRebootTime;
+use crate::io::server::model::varp_large::VarpLarge;
+use crate::io::server::model::varp_small::VarpSmall;
 use crate::io::server::outgoing_message::OutgoingMessage;
 use crate::io::server::protocol::server_protocol::ServerProtocol;
 
@@ -28,6 +40,12 @@ impl ServerProtocolRepository {
             .with::<If_OpenTop>(If_OpenTop_Encoder::new())
             .with::<If_OpenSub>(If_OpenSub_Encoder::new())
             .with::<Message_Game>(Message_Game_Encoder::new())
+            .with::<PlayerInfo>(PlayerInfoEncoder::new())
+            .with::<NpcInfo>(NpcInfoEncoder::new())
+            .with::<Logout>(LogoutEncoder::new())
+            .with::<UpdateRebootTime>(UpdateRebootTimeEncoder::new())
+            .with::<VarpSmall>(VarpSmallEncoder::new())
+            .with::<VarpLarge>(VarpLargeEncoder::new())
             .build()
     }
 
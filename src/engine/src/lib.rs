@@ -1,8 +1,16 @@
+pub mod auth;
 pub mod engine;
 pub mod entity;
 pub mod grid;
 mod engine_stat;
 mod game_connection;
+pub mod game_map;
 pub mod io;
 pub mod util;
-pub mod script;
\ No newline at end of file
+pub mod script;
+pub mod renderer;
+
+#[cfg(test)]
+mod engine_tests;
+#[cfg(test)]
+mod game_map_tests;
@@ -1,11 +1,21 @@
+use config::server_config::ServerConfig;
 use engine::engine::Engine;
+use log::error;
 
 fn main() {
     if std::env::var_os("RUST_LOG").is_none() {
         std::env::set_var("RUST_LOG", "debug");
     }
     env_logger::init();
+
+    if let Err(e) = ServerConfig::init() {
+        error!("Failed to load configuration: {}", e);
+        std::process::exit(1);
+    }
     
-    Engine::init();
-    Engine::get().start(true);
+    let mut engine = Engine::new();
+    if let Err(e) = engine.start(true) {
+        error!("Failed to start the world: {}", e);
+        std::process::exit(1);
+    }
 }
\ No newline at end of file
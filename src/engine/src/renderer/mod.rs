@@ -0,0 +1,6 @@
+pub mod player_renderer;
+pub mod npc_renderer;
+#[cfg(test)]
+mod npc_renderer_tests;
+#[cfg(test)]
+mod player_renderer_tests;
@@ -0,0 +1,221 @@
+use crate::entity::build_area::BuildArea;
+use crate::entity::entity::EntityBehavior;
+use crate::entity::entity_list::NPCList;
+use crate::entity::npc::NPC;
+use crate::entity::player::Player;
+use crate::io::packet::Packet;
+use crate::io::server::protocol::info_protocol::InfoProtocol;
+
+/// Builds the NPC_INFO payload for every viewer.
+///
+/// Npcs carry nothing as expensive as a player appearance, so every block is written straight from the npc.
+pub struct NpcRenderer;
+
+impl NpcRenderer {
+    /// The client reads the whole packet into a fixed 5000 byte buffer.
+    const MAX_BYTES: usize = 4900;
+    const MAX_LOCAL_NPCS: usize = 255;
+
+    /// Order the client reads the extended info blocks in.
+    const ORDER: [InfoProtocol; 8] = [
+        InfoProtocol::NPC_HIT,
+        InfoProtocol::NPC_HIT2,
+        InfoProtocol::NPC_ANIM,
+        InfoProtocol::NPC_FACE_ENTITY,
+        InfoProtocol::NPC_SPOTANIM,
+        InfoProtocol::NPC_CHANGE_TYPE,
+        InfoProtocol::NPC_SAY,
+        InfoProtocol::NPC_FACE_COORD,
+    ];
+
+    pub fn new() -> NpcRenderer {
+        NpcRenderer
+    }
+
+    /// Encode the bit-packed local npc list for `viewer`, adding and removing npcs in its build area.
+    pub fn encode_info(&self, viewer: &Player, build_area: &mut BuildArea, npcs: &NPCList) -> Packet {
+        let mut buf = Packet::from(Vec::with_capacity(256));
+        let mut updates: Vec<usize> = Vec::new();
+        let mut extended_size = 0;
+
+        buf.bits();
+
+        // Npcs already known to the client.
+        let locals = std::mem::take(&mut build_area.npcs);
+        let mut retained = Vec::with_capacity(locals.len());
+        buf.pbit(8, locals.len() as i32);
+
+        for nid in locals {
+            let npc = match npcs.get(nid) {
+                Some(npc) if npc.active() && !npc.pathing_entity.tele && build_area.npc_within_view(viewer, npc) => npc,
+                _ => {
+                    buf.pbit(1, 1);
+                    buf.pbit(2, 3);
+                    continue;
+                }
+            };
+
+            let mut extended = npc.pathing_entity.masks != 0;
+            if extended {
+                let size = self.extended_size(npc);
+                if buf.bit_length() + extended_size + size > Self::MAX_BYTES {
+                    extended = false;
+                } else {
+                    extended_size += size;
+                    updates.push(nid);
+                }
+            }
+
+            self.write_movement(&mut buf, npc, extended);
+            retained.push(nid);
+        }
+
+        build_area.retain_npcs(retained);
+
+        // Newly visible npcs.
+        for nid in build_area.get_nearby_npcs(viewer, npcs) {
+            if build_area.npcs.len() >= Self::MAX_LOCAL_NPCS {
+                break;
+            }
+
+            let npc = match npcs.get(nid) {
+                Some(npc) => npc,
+                None => continue,
+            };
+
+            let extended = npc.pathing_entity.masks != 0;
+            let size = if extended { self.extended_size(npc) } else { 0 };
+            // 15 nid + 5 dz + 3 orientation + 1 jump + 1 extended + 5 dx + 14 type, and the terminator.
+            if buf.bit_length() + 8 + extended_size + size > Self::MAX_BYTES {
+                break;
+            }
+
+            let dx = npc.coord().x() as i32 - viewer.coord().x() as i32;
+            let dz = npc.coord().z() as i32 - viewer.coord().z() as i32;
+
+            buf.pbit(15, nid as i32);
+            buf.pbit(5, dz);
+            buf.pbit(3, npc.pathing_entity.orientation as i32);
+            buf.pbit(1, 1);
+            buf.pbit(1, extended as i32);
+            buf.pbit(5, dx);
+            buf.pbit(14, npc.id as i32);
+
+            build_area.add_npc(nid);
+            if extended {
+                extended_size += size;
+                updates.push(nid);
+            }
+        }
+
+        if !updates.is_empty() {
+            buf.pbit(15, BuildArea::MAX_NID as i32);
+        }
+
+        buf.bytes();
+
+        for nid in updates {
+            if let Some(npc) = npcs.get(nid) {
+                self.write_extended(&mut buf, npc);
+            }
+        }
+
+        buf.data.truncate(buf.position);
+        buf
+    }
+
+    fn write_movement(&self, buf: &mut Packet, npc: &NPC, extended: bool) {
+        let entity = &npc.pathing_entity;
+
+        if entity.run_dir != -1 {
+            buf.pbit(1, 1);
+            buf.pbit(2, 2);
+            buf.pbit(3, entity.walk_dir as i32);
+            buf.pbit(3, entity.run_dir as i32);
+            buf.pbit(1, extended as i32);
+        } else if entity.walk_dir != -1 {
+            buf.pbit(1, 1);
+            buf.pbit(2, 1);
+            buf.pbit(3, entity.walk_dir as i32);
+            buf.pbit(1, extended as i32);
+        } else if extended {
+            buf.pbit(1, 1);
+            buf.pbit(2, 0);
+        } else {
+            buf.pbit(1, 0);
+        }
+    }
+
+    fn write_extended(&self, buf: &mut Packet, npc: &NPC) {
+        let entity = &npc.pathing_entity;
+        let mut masks = entity.masks;
+
+        if masks > 0xff {
+            masks |= InfoProtocol::NPC_BIG_UPDATE.id() as u32;
+            buf.p1((masks & 0xff) as i32);
+            buf.p1((masks >> 8) as i32);
+        } else {
+            buf.p1(masks as i32);
+        }
+
+        for prot in Self::ORDER {
+            if masks & prot.id() as u32 == 0 {
+                continue;
+            }
+
+            match prot.id() {
+                id if id == InfoProtocol::NPC_HIT.id() => {
+                    buf.p1(entity.damage_taken);
+                    buf.p1(entity.damage_type);
+                    buf.p1(npc.pathing_entity.health_ratio());
+                },
+                id if id == InfoProtocol::NPC_HIT2.id() => {
+                    buf.p1(entity.damage_taken2);
+                    buf.p1(entity.damage_type2);
+                    buf.p1(npc.pathing_entity.health_ratio());
+                },
+                id if id == InfoProtocol::NPC_ANIM.id() => {
+                    buf.p2(entity.anim_id);
+                    buf.p1(entity.anim_delay);
+                },
+                id if id == InfoProtocol::NPC_FACE_ENTITY.id() => {
+                    buf.p2(entity.face_entity);
+                },
+                id if id == InfoProtocol::NPC_SPOTANIM.id() => {
+                    buf.p2(entity.spotanim_id);
+                    buf.p4(entity.spotanim_height << 16 | (entity.spotanim_delay & 0xffff));
+                },
+                id if id == InfoProtocol::NPC_CHANGE_TYPE.id() => {
+                    buf.p2(npc.id as i32);
+                },
+                id if id == InfoProtocol::NPC_SAY.id() => {
+                    buf.pjstr(entity.say.as_deref().unwrap_or(""), 0);
+                },
+                id if id == InfoProtocol::NPC_FACE_COORD.id() => {
+                    buf.p2(entity.face_x * 2 + 1);
+                    buf.p2(entity.face_z * 2 + 1);
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn extended_size(&self, npc: &NPC) -> usize {
+        let masks = npc.pathing_entity.masks;
+        let mut size = if masks > 0xff { 2 } else { 1 };
+
+        for prot in Self::ORDER {
+            if masks & prot.id() as u32 == 0 {
+                continue;
+            }
+
+            size += if prot.id() == InfoProtocol::NPC_SAY.id() {
+                npc.pathing_entity.say.as_ref().map_or(0, String::len) + 1
+            } else {
+                prot.length() as usize
+            };
+        }
+
+        size
+    }
+}
@@ -0,0 +1,87 @@
+use crate::entity::block_walk::BlockWalk;
+use crate::entity::build_area::BuildArea;
+use crate::entity::entity::EntityBehavior;
+use crate::entity::entity_lifecycle::EntityLifeCycle;
+use crate::entity::entity_list::NPCList;
+use crate::entity::move_restrict::MoveRestrict;
+use crate::entity::npc::NPC;
+use crate::entity::player::Player;
+use crate::grid::coord_grid::CoordGrid;
+use crate::io::packet::Packet;
+use crate::io::server::protocol::info_protocol::InfoProtocol;
+use crate::renderer::npc_renderer::NpcRenderer;
+
+const NID: usize = 1;
+const TYPE: u16 = 7;
+
+fn viewer() -> Player {
+    Player::new_dummy(CoordGrid::from(3222, 0, 3218), 0, 1)
+}
+
+/// A world with one npc a couple of tiles north east of the viewer.
+fn npcs() -> NPCList {
+    let mut npc = NPC::new(CoordGrid::from(3224, 0, 3220), 1, 1, EntityLifeCycle::FOREVER, NID as i32, TYPE, MoveRestrict::Normal, BlockWalk::Npc);
+    npc.set_active(true);
+    let mut npcs = NPCList::new(16);
+    npcs.set(NID, npc).unwrap();
+    npcs
+}
+
+fn encode(viewer: &Player, npcs: &NPCList, build_area: &mut BuildArea) -> Packet {
+    let mut buf = Packet::from(NpcRenderer::new().encode_info(viewer, build_area, npcs).data);
+    buf.bits();
+    buf
+}
+
+#[test]
+fn test_nearby_npc_added() {
+    let (viewer, npcs) = (viewer(), npcs());
+    let mut build_area = BuildArea::new();
+    let mut buf = encode(&viewer, &npcs, &mut build_area);
+
+    assert_eq!(buf.gbit(8), 0);
+    assert_eq!(buf.gbit(15), NID as i32);
+    assert_eq!(buf.gbit(5), 2);
+    assert_eq!(buf.gbit(3), npcs.get(NID).unwrap().pathing_entity.orientation as i32);
+    assert_eq!(buf.gbit(1), 1);
+    assert_eq!(buf.gbit(1), 0);
+    assert_eq!(buf.gbit(5), 2);
+    assert_eq!(buf.gbit(14), TYPE as i32);
+    buf.bytes();
+    assert_eq!(buf.remaining(), 0);
+    assert_eq!(build_area.npcs, vec![NID]);
+}
+
+#[test]
+fn test_npc_hit_encoded() {
+    let (viewer, mut npcs) = (viewer(), npcs());
+    let mut build_area = BuildArea::new();
+    encode(&viewer, &npcs, &mut build_area);
+
+    npcs.get_mut(NID).unwrap().apply_damage(5, 1, 5, 10);
+    let mut buf = encode(&viewer, &npcs, &mut build_area);
+    assert_eq!(buf.gbit(8), 1);
+    assert_eq!(buf.gbit(1), 1);
+    assert_eq!(buf.gbit(2), 0);
+    assert_eq!(buf.gbit(15), BuildArea::MAX_NID as i32);
+    buf.bytes();
+    assert_eq!(buf.g1() as i32, InfoProtocol::NPC_HIT.id());
+    assert_eq!(buf.g1(), 5);
+    assert_eq!(buf.g1(), 1);
+    assert_eq!(buf.g1(), 127);
+    assert_eq!(buf.remaining(), 0);
+}
+
+#[test]
+fn test_npc_out_of_view_removed() {
+    let (viewer, mut npcs) = (viewer(), npcs());
+    let mut build_area = BuildArea::new();
+    encode(&viewer, &npcs, &mut build_area);
+
+    npcs.get_mut(NID).unwrap().set_coord(CoordGrid::from(3260, 0, 3220));
+    let mut buf = encode(&viewer, &npcs, &mut build_area);
+    assert_eq!(buf.gbit(8), 1);
+    assert_eq!(buf.gbit(1), 1);
+    assert_eq!(buf.gbit(2), 3);
+    assert!(build_area.npcs.is_empty());
+}
@@ -0,0 +1,325 @@
+use fnv::FnvHashMap;
+use crate::entity::build_area::BuildArea;
+use crate::entity::entity::EntityBehavior;
+use crate::entity::entity_list::PlayerList;
+use crate::entity::player::Player;
+use crate::io::packet::Packet;
+use crate::io::server::protocol::info_protocol::InfoProtocol;
+use crate::util::base37::encode37;
+
+/// Builds the PLAYER_INFO payload for every viewer.
+///
+/// Appearances are expensive to pack and rarely change, so they are cached per pid until the
+/// player generates a new one. Everything else is small enough to write straight from the player.
+pub struct PlayerRenderer {
+    appearances: FnvHashMap<usize, Vec<u8>>,
+}
+
+impl PlayerRenderer {
+    /// The client reads the whole packet into a fixed 5000 byte buffer.
+    const MAX_BYTES: usize = 4900;
+    const MAX_LOCAL_PLAYERS: usize = 255;
+
+    /// Order the client reads the extended info blocks in.
+    const ORDER: [InfoProtocol; 9] = [
+        InfoProtocol::PLAYER_SPOTANIM,
+        InfoProtocol::PLAYER_ANIM,
+        InfoProtocol::PLAYER_SAY,
+        InfoProtocol::PLAYER_CHAT,
+        InfoProtocol::PLAYER_FACE_ENTITY,
+        InfoProtocol::PLAYER_APPEARANCE,
+        InfoProtocol::PLAYER_FACE_COORD,
+        InfoProtocol::PLAYER_HIT,
+        InfoProtocol::PLAYER_HIT2,
+    ];
+
+    /// Body part shown in each worn slot when nothing is equipped there, -1 for none.
+    const BODY_SLOTS: [i32; 12] = [-1, -1, -1, -1, 2, -1, 3, 5, 0, 4, 6, 1];
+    const DEFAULT_RENDER_ANIM: i32 = 1426;
+
+    pub fn new() -> PlayerRenderer {
+        PlayerRenderer {
+            appearances: FnvHashMap::default(),
+        }
+    }
+
+    /// Refresh the shared buffers for a player before any viewer is encoded.
+    pub fn compute_info(&mut self, player: &Player) {
+        let pid = player.get_pid();
+        let masks = player.pathing_entity.masks;
+
+        if masks & InfoProtocol::PLAYER_APPEARANCE.id() as u32 != 0 || !self.appearances.contains_key(&pid) {
+            self.appearances.insert(pid, Self::appearance(player));
+        }
+    }
+
+    pub fn remove_permanent(&mut self, pid: usize) {
+        self.appearances.remove(&pid);
+    }
+
+    /// Encode the bit-packed local player list for `viewer`, adding and removing players in its build area.
+    pub fn encode_info(&self, viewer: &Player, build_area: &mut BuildArea, players: &PlayerList) -> Packet {
+        let mut buf = Packet::from(Vec::with_capacity(256));
+        let mut updates: Vec<(usize, u32)> = Vec::new();
+        let mut extended_size = 0;
+
+        buf.bits();
+
+        // Local player, who never sees their own chat echoed back.
+        let viewer_pid = viewer.get_pid();
+        let masks = self.masks_for(viewer, build_area) & !(InfoProtocol::PLAYER_CHAT.id() as u32);
+        self.write_movement(&mut buf, viewer, masks != 0, true);
+        if masks != 0 {
+            extended_size += self.extended_size(viewer, masks);
+            updates.push((viewer_pid, masks));
+        }
+
+        // Players already known to the client.
+        let locals = std::mem::take(&mut build_area.players);
+        let mut retained = Vec::with_capacity(locals.len());
+        buf.pbit(8, locals.len() as i32);
+
+        for pid in locals {
+            let other = match players.get(pid) {
+                Some(other) if other.active() && !other.pathing_entity.tele && build_area.within_view(viewer, other) => other,
+                _ => {
+                    buf.pbit(1, 1);
+                    buf.pbit(2, 3);
+                    continue;
+                }
+            };
+
+            let mut masks = self.masks_for(other, build_area);
+            if masks != 0 {
+                let size = self.extended_size(other, masks);
+                if buf.bit_length() + extended_size + size > Self::MAX_BYTES {
+                    masks = 0;
+                } else {
+                    extended_size += size;
+                    updates.push((pid, masks));
+                }
+            }
+
+            self.write_movement(&mut buf, other, masks != 0, false);
+            retained.push(pid);
+        }
+
+        build_area.retain_players(retained);
+
+        // Newly visible players.
+        for pid in build_area.get_nearby_players(viewer, players) {
+            if build_area.players.len() >= Self::MAX_LOCAL_PLAYERS {
+                break;
+            }
+
+            let other = match players.get(pid) {
+                Some(other) => other,
+                None => continue,
+            };
+
+            let masks = self.masks_for(other, build_area);
+            let size = if masks != 0 { self.extended_size(other, masks) } else { 0 };
+            // 11 pid + 1 extended + 5 dz + 3 orientation + 1 jump + 5 dx, and the terminator.
+            if buf.bit_length() + 5 + extended_size + size > Self::MAX_BYTES {
+                break;
+            }
+
+            let dx = other.coord().x() as i32 - viewer.coord().x() as i32;
+            let dz = other.coord().z() as i32 - viewer.coord().z() as i32;
+
+            buf.pbit(11, pid as i32);
+            buf.pbit(1, (masks != 0) as i32);
+            buf.pbit(5, dz);
+            buf.pbit(3, other.pathing_entity.orientation as i32);
+            buf.pbit(1, 1);
+            buf.pbit(5, dx);
+
+            build_area.add_player(pid);
+            if masks != 0 {
+                extended_size += size;
+                updates.push((pid, masks));
+            }
+        }
+
+        if !updates.is_empty() {
+            buf.pbit(11, BuildArea::MAX_PID as i32);
+        }
+
+        buf.bytes();
+
+        for (pid, masks) in updates {
+            let other = if pid == viewer_pid { Some(viewer) } else { players.get(pid) };
+            if let Some(other) = other {
+                self.write_extended(&mut buf, other, masks);
+                if masks & InfoProtocol::PLAYER_APPEARANCE.id() as u32 != 0 {
+                    build_area.appearances.insert(pid, other.last_appearance);
+                }
+            }
+        }
+
+        buf.data.truncate(buf.position);
+        buf
+    }
+
+    /// The masks `other` has to be sent with, forcing the appearance if the viewer has an outdated one.
+    fn masks_for(&self, other: &Player, build_area: &BuildArea) -> u32 {
+        let appearance = InfoProtocol::PLAYER_APPEARANCE.id() as u32;
+        let mut masks = other.pathing_entity.masks & !appearance;
+
+        if build_area.appearances.get(&other.get_pid()) != Some(&other.last_appearance) {
+            masks |= appearance;
+        }
+
+        masks
+    }
+
+    fn write_movement(&self, buf: &mut Packet, player: &Player, extended: bool, local: bool) {
+        let entity = &player.pathing_entity;
+
+        if local && entity.tele {
+            let origin = player.get_origin_coord();
+            let coord = player.coord();
+            buf.pbit(1, 1);
+            buf.pbit(2, 3);
+            buf.pbit(7, coord.z() as i32 - ((origin.central_z() as i32 - 6) << 3));
+            buf.pbit(1, entity.jump as i32);
+            buf.pbit(2, coord.y() as i32);
+            buf.pbit(1, extended as i32);
+            buf.pbit(7, coord.x() as i32 - ((origin.central_x() as i32 - 6) << 3));
+        } else if entity.run_dir != -1 {
+            buf.pbit(1, 1);
+            buf.pbit(2, 2);
+            buf.pbit(1, 1);
+            buf.pbit(3, entity.walk_dir as i32);
+            buf.pbit(3, entity.run_dir as i32);
+            buf.pbit(1, extended as i32);
+        } else if entity.walk_dir != -1 {
+            buf.pbit(1, 1);
+            buf.pbit(2, 1);
+            buf.pbit(3, entity.walk_dir as i32);
+            buf.pbit(1, extended as i32);
+        } else if extended {
+            buf.pbit(1, 1);
+            buf.pbit(2, 0);
+        } else {
+            buf.pbit(1, 0);
+        }
+    }
+
+    fn write_extended(&self, buf: &mut Packet, player: &Player, mut masks: u32) {
+        let entity = &player.pathing_entity;
+
+        if masks > 0xff {
+            masks |= InfoProtocol::PLAYER_BIG_UPDATE.id() as u32;
+            buf.p1((masks & 0xff) as i32);
+            buf.p1((masks >> 8) as i32);
+        } else {
+            buf.p1(masks as i32);
+        }
+
+        for prot in Self::ORDER {
+            if masks & prot.id() as u32 == 0 {
+                continue;
+            }
+
+            match prot.id() {
+                id if id == InfoProtocol::PLAYER_SPOTANIM.id() => {
+                    buf.p2(entity.spotanim_id);
+                    buf.p4(entity.spotanim_height << 16 | (entity.spotanim_delay & 0xffff));
+                },
+                id if id == InfoProtocol::PLAYER_ANIM.id() => {
+                    buf.p2(entity.anim_id);
+                    buf.p1(entity.anim_delay);
+                },
+                id if id == InfoProtocol::PLAYER_SAY.id() => {
+                    buf.pjstr(entity.say.as_deref().unwrap_or(""), 0);
+                },
+                id if id == InfoProtocol::PLAYER_CHAT.id() => {
+                    let message = player.message.as_deref().unwrap_or(&[]);
+                    buf.p2(player.message_color << 8 | player.message_effect);
+                    buf.p1(player.message_type);
+                    buf.p1(message.len() as i32);
+                    buf.pbytes(message, 0, message.len());
+                },
+                id if id == InfoProtocol::PLAYER_FACE_ENTITY.id() => {
+                    buf.p2(entity.face_entity);
+                },
+                id if id == InfoProtocol::PLAYER_APPEARANCE.id() => {
+                    let appearance = self.appearances.get(&player.get_pid()).map(Vec::as_slice).unwrap_or(&[]);
+                    buf.p1(appearance.len() as i32);
+                    buf.pbytes(appearance, 0, appearance.len());
+                },
+                id if id == InfoProtocol::PLAYER_FACE_COORD.id() => {
+                    buf.p2(entity.face_x * 2 + 1);
+                    buf.p2(entity.face_z * 2 + 1);
+                },
+                id if id == InfoProtocol::PLAYER_HIT.id() => {
+                    buf.p1(entity.damage_taken);
+                    buf.p1(entity.damage_type);
+                    buf.p1(player.pathing_entity.health_ratio());
+                },
+                id if id == InfoProtocol::PLAYER_HIT2.id() => {
+                    buf.p1(entity.damage_taken2);
+                    buf.p1(entity.damage_type2);
+                    buf.p1(player.pathing_entity.health_ratio());
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn extended_size(&self, player: &Player, masks: u32) -> usize {
+        let mut size = if masks > 0xff { 2 } else { 1 };
+
+        for prot in Self::ORDER {
+            if masks & prot.id() as u32 == 0 {
+                continue;
+            }
+
+            size += match prot.id() {
+                id if id == InfoProtocol::PLAYER_SAY.id() => {
+                    player.pathing_entity.say.as_ref().map_or(0, String::len) + 1
+                },
+                id if id == InfoProtocol::PLAYER_CHAT.id() => {
+                    4 + player.message.as_ref().map_or(0, Vec::len)
+                },
+                id if id == InfoProtocol::PLAYER_APPEARANCE.id() => {
+                    1 + self.appearances.get(&player.get_pid()).map_or(0, Vec::len)
+                },
+                _ => prot.length() as usize,
+            };
+        }
+
+        size
+    }
+
+    fn appearance(player: &Player) -> Vec<u8> {
+        let mut buf = Packet::from(Vec::with_capacity(64));
+
+        buf.p1(player.gender as i32 & 0x1);
+        buf.p1(-1); // Skull icon
+        buf.p1(-1); // Prayer icon
+
+        // TODO - worn objs once inventories exist.
+        for part in Self::BODY_SLOTS {
+            let kit = if part == -1 { -1 } else { player.body[part as usize] };
+            if kit == -1 {
+                buf.p1(0);
+            } else {
+                buf.p2(0x100 + kit);
+            }
+        }
+
+        for color in player.colors {
+            buf.p1(color);
+        }
+
+        buf.p2(Self::DEFAULT_RENDER_ANIM);
+        buf.p8(encode37(&player.username.to_lowercase()).unwrap_or(0));
+        buf.p1(player.combat_level as i32);
+        buf.p2(0); // Total level, only shown on worlds without combat levels.
+
+        buf.data.truncate(buf.position);
+        buf.data
+    }
+}
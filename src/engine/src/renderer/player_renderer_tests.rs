@@ -0,0 +1,113 @@
+use crate::engine::Engine;
+use crate::entity::build_area::BuildArea;
+use crate::entity::entity_list::PlayerList;
+use crate::entity::player::Player;
+use crate::grid::coord_grid::CoordGrid;
+use crate::io::packet::Packet;
+use crate::io::server::protocol::info_protocol::InfoProtocol;
+use crate::renderer::player_renderer::PlayerRenderer;
+
+const VIEWER: usize = 1;
+const OTHER: usize = 2;
+
+fn player(pid: usize, x: u16, z: u16) -> Player {
+    let mut player = Player::new_dummy(CoordGrid::from(x, 0, z), 0, pid);
+    player.set_active(true);
+    player
+}
+
+fn world(players: Vec<Player>) -> (PlayerRenderer, PlayerList) {
+    let mut renderer = PlayerRenderer::new();
+    let mut list = PlayerList::new(16);
+    for player in players {
+        renderer.compute_info(&player);
+        list.set(player.get_pid(), player).unwrap();
+    }
+    (renderer, list)
+}
+
+/// Encode for the viewer and get the result back ready to read bits from the start.
+fn encode(renderer: &PlayerRenderer, players: &PlayerList, build_area: &mut BuildArea) -> Packet {
+    let mut buf = Packet::from(renderer.encode_info(players.get(VIEWER).unwrap(), build_area, players).data);
+    buf.bits();
+    buf
+}
+
+#[test]
+fn test_first_info_sends_own_appearance() {
+    let (renderer, players) = world(vec![player(VIEWER, 3222, 3218)]);
+    let mut build_area = BuildArea::new();
+    let mut buf = encode(&renderer, &players, &mut build_area);
+
+    assert_eq!(buf.gbit(1), 1);
+    assert_eq!(buf.gbit(2), 0);
+    assert_eq!(buf.gbit(8), 0);
+    assert_eq!(buf.gbit(11), BuildArea::MAX_PID as i32);
+    buf.bytes();
+    assert_eq!(buf.g1() as i32, InfoProtocol::PLAYER_APPEARANCE.id());
+    let length = buf.g1() as usize;
+    assert!(length > 0);
+    assert_eq!(buf.remaining() as usize, length);
+    assert_eq!(build_area.appearances.get(&VIEWER), Some(&players.get(VIEWER).unwrap().last_appearance));
+}
+
+#[test]
+fn test_idle_viewer_sends_nothing_once_up_to_date() {
+    let (renderer, players) = world(vec![player(VIEWER, 3222, 3218)]);
+    let mut build_area = BuildArea::new();
+    encode(&renderer, &players, &mut build_area);
+
+    let buf = encode(&renderer, &players, &mut build_area);
+    assert_eq!(buf.data, vec![0, 0]);
+}
+
+#[test]
+fn test_nearby_player_added() {
+    let (renderer, players) = world(vec![player(VIEWER, 3222, 3218), player(OTHER, 3224, 3217)]);
+    let mut build_area = BuildArea::new();
+    let mut buf = encode(&renderer, &players, &mut build_area);
+
+    assert_eq!(buf.gbit(3), 0b100);
+    assert_eq!(buf.gbit(8), 0);
+    assert_eq!(buf.gbit(11), OTHER as i32);
+    assert_eq!(buf.gbit(1), 1);
+    assert_eq!(buf.gbit(5), 0x1f); // dz of -1 in five bits
+    assert_eq!(buf.gbit(3), players.get(OTHER).unwrap().pathing_entity.orientation as i32);
+    assert_eq!(buf.gbit(1), 1);
+    assert_eq!(buf.gbit(5), 2);
+    assert_eq!(buf.gbit(11), BuildArea::MAX_PID as i32);
+    assert_eq!(build_area.players, vec![OTHER]);
+    assert!(build_area.appearances.contains_key(&OTHER));
+}
+
+#[test]
+fn test_player_out_of_view_removed() {
+    let (renderer, mut players) = world(vec![player(VIEWER, 3222, 3218), player(OTHER, 3224, 3217)]);
+    let mut build_area = BuildArea::new();
+    encode(&renderer, &players, &mut build_area);
+
+    players.get_mut(OTHER).unwrap().set_active(false);
+    let mut buf = encode(&renderer, &players, &mut build_area);
+    assert_eq!(buf.gbit(1), 0);
+    assert_eq!(buf.gbit(8), 1);
+    assert_eq!(buf.gbit(1), 1);
+    assert_eq!(buf.gbit(2), 3);
+    assert!(build_area.players.is_empty());
+}
+
+#[test]
+fn test_logout_forgets_appearance() {
+    let mut engine = Engine::new();
+    for player in [player(VIEWER, 3222, 3218), player(OTHER, 3224, 3217)] {
+        engine.player_renderer.compute_info(&player);
+        engine.players.set(player.get_pid(), player).unwrap();
+    }
+
+    let mut build_area = std::mem::take(&mut engine.players.get_mut(VIEWER).unwrap().build_area);
+    engine.player_renderer.encode_info(engine.players.get(VIEWER).unwrap(), &mut build_area, &engine.players);
+    engine.players.get_mut(VIEWER).unwrap().build_area = build_area;
+    assert!(engine.players.get(VIEWER).unwrap().build_area.appearances.contains_key(&OTHER));
+
+    engine.remove_player(OTHER);
+    assert!(!engine.players.get(VIEWER).unwrap().build_area.appearances.contains_key(&OTHER));
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use log::error;
+use crate::script::script_opcode::ScriptOpcode;
+use crate::engine::Engine;
+use crate::script::script_runner::CommandHandlers;
+use crate::script::script_state::ScriptState;
+use crate::util::cache::config::enum_type::EnumType;
+use crate::util::cache::config::param_type::ParamType;
+use crate::util::cache::config::struct_type::StructType;
+use crate::util::cache::param_helper::ParamValue;
+
+pub fn get_config_ops() -> &'static CommandHandlers {
+    static HANDLERS: OnceLock<CommandHandlers> = OnceLock::new();
+
+    HANDLERS.get_or_init(|| {
+        let mut handlers: CommandHandlers = HashMap::with_capacity(3);
+
+        handlers.insert(
+            ScriptOpcode::ENUM as i32,
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let [input_type, output_type, enum_id, key] = state.pop_ints(4)[..] else {
+                    return;
+                };
+
+                let Some(enum_type) = EnumType::get(enum_id) else {
+                    error!("Unknown enum: {}", enum_id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                };
+
+                // The compiler passes the types it checked the call against, they have to agree with the config.
+                if enum_type.inputtype as i32 != input_type || enum_type.outputtype as i32 != output_type {
+                    error!(
+                        "Enum {} maps {} to {}, script expected {} to {}",
+                        enum_id,
+                        enum_type.inputtype as char,
+                        enum_type.outputtype as char,
+                        char::from_u32(input_type as u32).unwrap_or('?'),
+                        char::from_u32(output_type as u32).unwrap_or('?')
+                    );
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                }
+
+                if enum_type.outputtype == b's' {
+                    state.push_string(enum_type.get_string(key).to_string());
+                } else {
+                    state.push_int(enum_type.get_int(key));
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::ENUM_GETOUTPUTCOUNT as i32,
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let enum_id = state.pop_int();
+
+                let Some(enum_type) = EnumType::get(enum_id) else {
+                    error!("Unknown enum: {}", enum_id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                };
+
+                state.push_int(enum_type.values.len() as i32);
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::STRUCT_PARAM as i32,
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let [struct_id, param_id] = state.pop_ints(2)[..] else {
+                    return;
+                };
+
+                let Some(param_type) = ParamType::get(param_id) else {
+                    error!("Unknown param: {}", param_id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                };
+                let Some(struct_type) = StructType::get(struct_id) else {
+                    error!("Unknown struct: {}", struct_id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                };
+
+                // A value of the wrong kind is treated like a missing one, the declared type wins.
+                if param_type.is_string() {
+                    let value = match struct_type.param(param_id) {
+                        Some(ParamValue::String(value)) => value.clone(),
+                        _ => param_type.default_string.clone(),
+                    };
+                    state.push_string(value);
+                } else {
+                    let value = match struct_type.param(param_id) {
+                        Some(ParamValue::Integer(value)) => *value,
+                        _ => param_type.default_int,
+                    };
+                    state.push_int(value);
+                }
+            }
+        );
+
+        handlers
+    })
+}
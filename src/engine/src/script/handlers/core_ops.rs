@@ -2,10 +2,16 @@ use crate::script::script_opcode::ScriptOpcode;
 use crate::script::script_runner::CommandHandlers;
 use crate::script::script_state::ScriptState;
 use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 use log::error;
 use crate::script::script_file::ScriptFile;
 use crate::script::script_provider::ScriptProvider;
+use crate::engine::Engine;
+use crate::script::script_pointer::PROTECTED_ACTIVE_PLAYER;
+use crate::util::cache::config::var_bit_type::VarBitType;
+use crate::util::cache::config::var_npc_type::VarNpcType;
+use crate::util::cache::config::var_shared_type::VarSharedType;
+use crate::util::cache::config::varp_type::VarpType;
 
 pub fn get_core_ops() -> &'static CommandHandlers {
     static HANDLERS: OnceLock<CommandHandlers> = OnceLock::new();
@@ -15,28 +21,52 @@ pub fn get_core_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::PUSH_CONSTANT_INT as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 state.push_int(state.get_int_operand())
             }
         );
         
         handlers.insert(
             ScriptOpcode::PUSH_VARP as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let operand = state.get_int_operand();
+                let Some(pid) = varp_player(engine, state, operand) else {
+                    return;
+                };
+
+                let value = engine.players.get(pid).map_or(0, |player| player.get_var((operand & 0xffff) as usize));
+                state.push_int(value);
             }
         );
         
         handlers.insert(
             ScriptOpcode::POP_VARP as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let operand = state.get_int_operand();
+                let id = operand & 0xffff;
+                let value = state.pop_int();
+
+                let Some(varp) = VarpType::get(id) else {
+                    error!("Unknown varp: {}", id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                };
+                if !varp_writable(state, operand, varp) {
+                    return;
+                }
+                let Some(pid) = varp_player(engine, state, operand) else {
+                    return;
+                };
+
+                if let Some(player) = engine.players.get_mut(pid) {
+                    player.set_var(id as usize, value);
+                }
             }
         );
 
         handlers.insert(
             ScriptOpcode::PUSH_CONSTANT_STRING as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 match state.get_string_operand().parse() {
                     Ok(str_value) => state.push_string(str_value),
                     Err(e) => {
@@ -49,27 +79,48 @@ pub fn get_core_ops() -> &'static CommandHandlers {
         
         handlers.insert(
             ScriptOpcode::PUSH_VARN as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let operand = state.get_int_operand();
+                let Some(nid) = varn_npc(engine, state, operand) else {
+                    return;
+                };
+
+                let value = engine.npcs.get(nid).map_or(0, |npc| npc.get_var((operand & 0xffff) as usize));
+                state.push_int(value);
             }
         );
         
         handlers.insert(
             ScriptOpcode::POP_VARN as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let operand = state.get_int_operand();
+                let id = operand & 0xffff;
+                let value = state.pop_int();
+
+                if VarNpcType::get(id).is_none() {
+                    error!("Unknown varn: {}", id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                }
+                let Some(nid) = varn_npc(engine, state, operand) else {
+                    return;
+                };
+
+                if let Some(npc) = engine.npcs.get_mut(nid) {
+                    npc.set_var(id as usize, value);
+                }
             }
         );
         handlers.insert(
             ScriptOpcode::BRANCH as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 state.pc += state.get_int_operand();
             }
         );
         
         handlers.insert(
             ScriptOpcode::BRANCH_NOT as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
                 
@@ -81,7 +132,7 @@ pub fn get_core_ops() -> &'static CommandHandlers {
         
         handlers.insert(
             ScriptOpcode::BRANCH_EQUALS as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
                 
@@ -93,7 +144,7 @@ pub fn get_core_ops() -> &'static CommandHandlers {
         
         handlers.insert(
             ScriptOpcode::BRANCH_LESS_THAN as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
                 
@@ -105,7 +156,7 @@ pub fn get_core_ops() -> &'static CommandHandlers {
         
         handlers.insert(
             ScriptOpcode::BRANCH_GREATER_THAN as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
                 
@@ -117,21 +168,30 @@ pub fn get_core_ops() -> &'static CommandHandlers {
         
         handlers.insert(
             ScriptOpcode::PUSH_VARS as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let id = state.get_int_operand();
+                state.push_int(engine.get_var(id as usize));
             }
         );
         
         handlers.insert(
             ScriptOpcode::POP_VARS as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let id = state.get_int_operand();
+                let value = state.pop_int();
+
+                if VarSharedType::get(id).is_none() {
+                    error!("Unknown vars: {}", id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                }
+                engine.set_var(id as usize, value);
             }
         );
 
         handlers.insert(
             ScriptOpcode::RETURN as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 if state.fp == 0 {
                     state.execution = ScriptState::FINISHED;
                     return;
@@ -142,12 +202,12 @@ pub fn get_core_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::GOSUB as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 if state.fp >= 50 {
                     error!("Stack overflow");
                 }
 
-                let proc: Option<ScriptFile> = ScriptProvider::get(state.pop_int() as usize);
+                let proc: Option<Arc<ScriptFile>> = ScriptProvider::get(state.pop_int() as usize);
                 if let Some(proc) = proc {
                     state.gosub_frame(proc)
                 } else {
@@ -158,18 +218,18 @@ pub fn get_core_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::JUMP as i32,
-            |state: &mut ScriptState| {
-                let label: Option<ScriptFile> = ScriptProvider::get(state.pop_int() as usize);
-                if label.is_some() {
-                    error!("Unable to find label: {:?}", label);
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let id = state.pop_int();
+                match ScriptProvider::get(id as usize) {
+                    Some(label) => state.goto_frame(label),
+                    None => error!("Unable to find label: {}", id),
                 }
-                state.goto_frame(label.unwrap());
             }
         );
         
         handlers.insert(
             ScriptOpcode::SWITCH as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 /*let key = state.pop_int();
                 let operand = state.get_int_operand() as usize;
                 let table: Option<SwitchTable> = &state.script.switch_tables[operand];
@@ -188,21 +248,57 @@ pub fn get_core_ops() -> &'static CommandHandlers {
         
         handlers.insert(
             ScriptOpcode::PUSH_VARBIT as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let operand = state.get_int_operand();
+                let id = operand & 0xffff;
+
+                let Some(varbit) = VarBitType::get(id) else {
+                    error!("Unknown varbit: {}", id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                };
+                let Some(pid) = varp_player(engine, state, operand) else {
+                    return;
+                };
+
+                let value = engine.players.get(pid).map_or(0, |player| player.get_varbit(varbit));
+                state.push_int(value);
             }
         );
         
         handlers.insert(
             ScriptOpcode::POP_VARBIT as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let operand = state.get_int_operand();
+                let id = operand & 0xffff;
+                let value = state.pop_int();
+
+                let Some(varbit) = VarBitType::get(id) else {
+                    error!("Unknown varbit: {}", id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                };
+                let Some(varp) = VarpType::get(varbit.basevar as i32) else {
+                    error!("Unknown varp: {} for varbit {}", varbit.basevar, id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                };
+                if !varp_writable(state, operand, varp) {
+                    return;
+                }
+                let Some(pid) = varp_player(engine, state, operand) else {
+                    return;
+                };
+
+                if let Some(player) = engine.players.get_mut(pid) {
+                    player.set_varbit(varbit, value);
+                }
             }
         );
         
         handlers.insert(
             ScriptOpcode::BRANCH_LESS_THAN_OR_EQUALS as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
                 
@@ -214,7 +310,7 @@ pub fn get_core_ops() -> &'static CommandHandlers {
         
         handlers.insert(
             ScriptOpcode::BRANCH_GREATER_THAN_OR_EQUALS as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
                 
@@ -226,14 +322,14 @@ pub fn get_core_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::PUSH_INT_LOCAL as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 state.push_int(state.int_locals[state.get_int_operand() as usize])
             }
         );
 
         handlers.insert(
             ScriptOpcode::POP_INT_LOCAL as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let operand = state.get_int_operand() as usize; 
                 state.int_locals[operand] = state.pop_int();
             }
@@ -241,21 +337,21 @@ pub fn get_core_ops() -> &'static CommandHandlers {
 
         /*handlers.insert(
             ScriptOpcode::PUSH_STRING_LOCAL as i32,
-            |state: &mut ScriptState| {
+            |engine: &mut Engine, state: &mut ScriptState| {
                 state.push_string(std::mem::replace(&mut state.string_locals[state.get_int_operand() as usize], String::new()));
             }
         );*/
         
         /*handlers.insert(
             ScriptOpcode::POP_STRING_LOCAL as i32,
-            |state: &mut ScriptState| {
+            |engine: &mut Engine, state: &mut ScriptState| {
                 state.string_locals[state.get_int_operand() as usize] = state.pop_string();
             }
         );*/
         
         handlers.insert(
             ScriptOpcode::JOIN_STRING as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let count = state.get_int_operand();
                 
                 let mut strings = Vec::with_capacity(count as usize);
@@ -269,26 +365,26 @@ pub fn get_core_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::POP_INT_DISCARD as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 state.isp -= 1
             }
         );
 
         handlers.insert(
             ScriptOpcode::POP_STRING_DISCARD as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 state.ssp -= 1
             }
         );
         
         handlers.insert(
             ScriptOpcode::GOSUB_WITH_PARAMS as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 if state.fp >= 50 {
                     error!("Stack overflow");
                 }
 
-                let proc: Option<ScriptFile> = ScriptProvider::get(state.get_int_operand() as usize);
+                let proc: Option<Arc<ScriptFile>> = ScriptProvider::get(state.get_int_operand() as usize);
                 if let Some(proc) = proc {
                     state.gosub_frame(proc)
                 } else {
@@ -299,50 +395,145 @@ pub fn get_core_ops() -> &'static CommandHandlers {
         
         handlers.insert(
             ScriptOpcode::JUMP_WITH_PARAMS as i32,
-            |state: &mut ScriptState| {
-                let label: Option<ScriptFile> = ScriptProvider::get(state.get_int_operand() as usize);
-                if label.is_some() {
-                    error!("Unable to find label: {:?}", label);
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let id = state.get_int_operand();
+                match ScriptProvider::get(id as usize) {
+                    Some(label) => state.goto_frame(label),
+                    None => error!("Unable to find label: {}", id),
                 }
-                state.goto_frame(label.unwrap());
             }
         );
         
         handlers.insert(
             ScriptOpcode::PUSH_VARC_INT as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 error!("Unimplemented");
             }
         );
         
         handlers.insert(
             ScriptOpcode::POP_VARC_INT as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 error!("Unimplemented");
             }
         );
         
         handlers.insert(
             ScriptOpcode::DEFINE_ARRAY as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let operand = state.get_int_operand();
+                let id = (operand >> 16) as usize;
+                let size = state.pop_int();
+
+                if !(0..=ScriptState::MAX_ARRAY_SIZE).contains(&size) {
+                    array_error(state, format!("Invalid array size: {}", size));
+                    return;
+                }
+
+                if state.int_arrays.len() <= id {
+                    state.int_arrays.resize_with(id + 1, Vec::new);
+                }
+                state.int_arrays[id] = vec![array_default(operand & 0xffff); size as usize];
             }
         );
         
         handlers.insert(
             ScriptOpcode::PUSH_ARRAY_INT as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let id = state.get_int_operand() as usize;
+                let index = state.pop_int();
+
+                if let Some(index) = array_index(state, id, index) {
+                    let value = state.int_arrays[id][index];
+                    state.push_int(value);
+                }
             }
         );
         
         handlers.insert(
             ScriptOpcode::POP_ARRAY_INT as i32,
-            |state: &mut ScriptState| {
-                error!("Unimplemented");
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let id = state.get_int_operand() as usize;
+                let value = state.pop_int();
+                let index = state.pop_int();
+
+                if let Some(index) = array_index(state, id, index) {
+                    state.int_arrays[id][index] = value;
+                }
             }
         );
         
         handlers
     })
-}
\ No newline at end of file
+}
+
+/// Player a varp op reads or writes, bit 16 of the operand selecting the secondary player.
+fn varp_player(engine: &Engine, state: &mut ScriptState, operand: i32) -> Option<usize> {
+    match state.get_active_player_pid(engine, (operand >> 16) & 1 == 1) {
+        Ok(pid) => Some(pid),
+        Err(e) => {
+            error!("{}", e);
+            state.execution = ScriptState::ABORTED;
+            None
+        }
+    }
+}
+
+/// Npc a varn op reads or writes, bit 16 of the operand selecting the secondary npc.
+fn varn_npc(engine: &Engine, state: &mut ScriptState, operand: i32) -> Option<usize> {
+    match state.get_active_npc_nid(engine, (operand >> 16) & 1 == 1) {
+        Ok(nid) => Some(nid),
+        Err(e) => {
+            error!("{}", e);
+            state.execution = ScriptState::ABORTED;
+            None
+        }
+    }
+}
+
+/// Protected varps can only be written with protected access to the player.
+fn varp_writable(state: &mut ScriptState, operand: i32, varp: &VarpType) -> bool {
+    if !varp.protect {
+        return true;
+    }
+
+    let pointer = PROTECTED_ACTIVE_PLAYER[((operand >> 16) & 1) as usize];
+    if let Err(e) = state.pointer_check(&[pointer]) {
+        error!("Varp {} is protected: {}", varp.id, e);
+        state.execution = ScriptState::ABORTED;
+        return false;
+    }
+    true
+}
+
+/// Arrays start filled with their type's default, -1 for everything that isn't a plain number or boolean.
+fn array_default(type_: i32) -> i32 {
+    match u8::try_from(type_).map(char::from) {
+        Ok('i') | Ok('1') => 0,
+        _ => -1,
+    }
+}
+
+/// Checked index into one of the running script's arrays, none if the script was aborted for being out of range.
+fn array_index(state: &mut ScriptState, id: usize, index: i32) -> Option<usize> {
+    let size = match state.int_arrays.get(id) {
+        Some(array) => array.len(),
+        None => {
+            array_error(state, format!("Array {} is not defined", id));
+            return None;
+        }
+    };
+
+    match usize::try_from(index) {
+        Ok(index) if index < size => Some(index),
+        _ => {
+            array_error(state, format!("Array {} index out of range: {} (size {})", id, index, size));
+            None
+        }
+    }
+}
+
+fn array_error(state: &mut ScriptState, message: String) {
+    error!("{} in {} line {}", message, state.script.name(), state.script.line_number(state.pc));
+    state.execution = ScriptState::ABORTED;
+}
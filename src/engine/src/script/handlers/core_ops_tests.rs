@@ -0,0 +1,96 @@
+use std::sync::Arc;
+use crate::engine::Engine;
+use crate::script::script_file::ScriptFile;
+use crate::script::script_opcode::ScriptOpcode;
+use crate::script::script_runner::ScriptRunner;
+use crate::script::script_state::ScriptState;
+
+/// Operand for DEFINE_ARRAY of int array `id`.
+const fn int_array(id: i32) -> i32 {
+    id << 16 | b'i' as i32
+}
+
+/// Run a script of `(opcode, operand)` pairs, giving back the state it stopped in.
+fn run(ops: &[(ScriptOpcode, i32)]) -> ScriptState {
+    let mut script = ScriptFile::new(0);
+    script.info.lookup_key = 0;
+    for &(opcode, operand) in ops {
+        script.opcodes.push(opcode);
+        script.int_operands.push(operand);
+    }
+
+    let mut state = ScriptState::new(Arc::new(script), None);
+    ScriptRunner::execute(&mut Engine::new(), &mut state, false, false);
+    state
+}
+
+#[test]
+fn test_array_store_and_load() {
+    let state = run(&[
+        (ScriptOpcode::PUSH_CONSTANT_INT, 3),
+        (ScriptOpcode::DEFINE_ARRAY, int_array(0)),
+        (ScriptOpcode::PUSH_CONSTANT_INT, 2),
+        (ScriptOpcode::PUSH_CONSTANT_INT, 42),
+        (ScriptOpcode::POP_ARRAY_INT, 0),
+        (ScriptOpcode::PUSH_CONSTANT_INT, 2),
+        (ScriptOpcode::PUSH_ARRAY_INT, 0),
+    ]);
+
+    assert_eq!(state.execution, ScriptState::FINISHED);
+    assert_eq!(state.int_arrays[0], vec![0, 0, 42]);
+    assert_eq!(state.isp, 1);
+    assert_eq!(state.int_stack[0], 42);
+}
+
+#[test]
+fn test_array_load_out_of_range_aborts() {
+    let state = run(&[
+        (ScriptOpcode::PUSH_CONSTANT_INT, 3),
+        (ScriptOpcode::DEFINE_ARRAY, int_array(0)),
+        (ScriptOpcode::PUSH_CONSTANT_INT, 3),
+        (ScriptOpcode::PUSH_ARRAY_INT, 0),
+        (ScriptOpcode::PUSH_CONSTANT_INT, 7),
+    ]);
+
+    // Stopped on the bad load, nothing after it ran.
+    assert_eq!(state.execution, ScriptState::ABORTED);
+    assert_eq!(state.pc, 3);
+    assert_eq!(state.isp, 0);
+}
+
+#[test]
+fn test_array_store_negative_index_aborts() {
+    let state = run(&[
+        (ScriptOpcode::PUSH_CONSTANT_INT, 3),
+        (ScriptOpcode::DEFINE_ARRAY, int_array(0)),
+        (ScriptOpcode::PUSH_CONSTANT_INT, -1),
+        (ScriptOpcode::PUSH_CONSTANT_INT, 42),
+        (ScriptOpcode::POP_ARRAY_INT, 0),
+    ]);
+
+    assert_eq!(state.execution, ScriptState::ABORTED);
+    assert_eq!(state.pc, 4);
+    assert_eq!(state.int_arrays[0], vec![0, 0, 0]);
+}
+
+#[test]
+fn test_undefined_array_aborts() {
+    let state = run(&[
+        (ScriptOpcode::PUSH_CONSTANT_INT, 0),
+        (ScriptOpcode::PUSH_ARRAY_INT, 1),
+    ]);
+
+    assert_eq!(state.execution, ScriptState::ABORTED);
+    assert_eq!(state.pc, 1);
+}
+
+#[test]
+fn test_array_too_large_aborts() {
+    let state = run(&[
+        (ScriptOpcode::PUSH_CONSTANT_INT, ScriptState::MAX_ARRAY_SIZE + 1),
+        (ScriptOpcode::DEFINE_ARRAY, int_array(0)),
+    ]);
+
+    assert_eq!(state.execution, ScriptState::ABORTED);
+    assert!(state.int_arrays.is_empty());
+}
@@ -1,74 +1,77 @@
 use std::collections::HashMap;
-use std::mem::offset_of;
 use std::sync::OnceLock;
+use log::error;
+use rand::Rng;
 use crate::script::script_opcode::ScriptOpcode;
+use crate::engine::Engine;
 use crate::script::script_runner::CommandHandlers;
 use crate::script::script_state::ScriptState;
 use crate::util::bits::{bitcount, clear_bit_range, set_bit_range, MASK};
 use crate::util::trig::Trig;
 
+/// Integer math as RuneScript defines it: 32-bit two's complement, so anything that overflows wraps.
+///
+/// Dividing by zero aborts the script rather than pushing a made up result.
 pub fn get_math_ops() -> &'static CommandHandlers {
     static HANDLERS: OnceLock<CommandHandlers> = OnceLock::new();
 
     HANDLERS.get_or_init(|| {
-        let mut handlers: CommandHandlers = HashMap::with_capacity(28);
+        let mut handlers: CommandHandlers = HashMap::with_capacity(29);
 
         handlers.insert(
             ScriptOpcode::ADD as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
 
-                state.push_int(a + b);
+                state.push_int(a.wrapping_add(b));
             }
         );
 
         handlers.insert(
             ScriptOpcode::SUB as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
 
-                state.push_int(a - b);
+                state.push_int(a.wrapping_sub(b));
             }
         );
 
         handlers.insert(
             ScriptOpcode::MULTIPLY as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
 
-                state.push_int(a * b);
+                state.push_int(a.wrapping_mul(b));
             }
         );
 
         handlers.insert(
             ScriptOpcode::DIVIDE as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let b = state.pop_int();
                 let a = state.pop_int();
 
-                state.push_int(a / b);
+                if b == 0 {
+                    error!("Division by zero: {} / {}", a, b);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                }
+                state.push_int(a.wrapping_div(b));
             }
         );
 
         handlers.insert(
             ScriptOpcode::RANDOM as i32,
-            |state: &mut ScriptState| {
-                use rand::rngs::ThreadRng;
-                use rand::Rng;
-
-                thread_local! {
-                    static RNG: std::cell::RefCell<ThreadRng> = std::cell::RefCell::new(rand::thread_rng());
-                }
-
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let a = state.pop_int();
 
                 let random_value = if a <= 0 {
                     0
                 } else {
-                    RNG.with(|rng| rng.borrow_mut().random_range(0..a))
+                    rand::rng().random_range(0..a)
                 };
 
                 state.push_int(random_value);
@@ -77,20 +80,13 @@ pub fn get_math_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::RANDOMINC as i32,
-            |state: &mut ScriptState| {
-                use rand::rngs::ThreadRng;
-                use rand::Rng;
-
-                thread_local! {
-                    static RNG: std::cell::RefCell<ThreadRng> = std::cell::RefCell::new(rand::thread_rng());
-                }
-
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let a = state.pop_int();
 
-                let random_value = if a < 0 {
+                let random_value = if a <= 0 {
                     0
                 } else {
-                    RNG.with(|rng| rng.borrow_mut().gen_range(0..=a))
+                    rand::rng().random_range(0..=a)
                 };
 
                 state.push_int(random_value);
@@ -99,20 +95,17 @@ pub fn get_math_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::INTERPOLATE as i32,
-            |state: &mut ScriptState| {
-                let x = state.pop_int();
-                let x1 = state.pop_int();
-                let x0 = state.pop_int();
-                let y1 = state.pop_int();
-                let y0 = state.pop_int();
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let [y0, y1, x0, x1, x] = state.pop_ints(5)[..] else {
+                    return;
+                };
 
-                // Avoid division by zero
+                // A flat range has nothing to interpolate along.
                 let lerp = if x1 == x0 {
                     y0
                 } else {
-                    // Calculate linear interpolation
-                    let slope = (y1 - y0) / (x1 / x0);
-                    (slope * (x - x0)) + y0
+                    let slope = ((y1 as f64 - y0 as f64) / (x1 as f64 - x0 as f64)).floor() as i32;
+                    slope.wrapping_mul(x.wrapping_sub(x0)).wrapping_add(y0)
                 };
 
                 state.push_int(lerp);
@@ -121,133 +114,154 @@ pub fn get_math_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::ADDPERCENT as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let percent = state.pop_int();
                 let num = state.pop_int();
 
-                let result = ((num * percent) / 100) + num;
-
-                state.push_int(result);
+                state.push_int((num.wrapping_mul(percent) / 100).wrapping_add(num));
             }
         );
 
         handlers.insert(
             ScriptOpcode::SETBIT as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let bit = state.pop_int();
                 let value = state.pop_int();
 
-                state.push_int(value | (1 << bit));
+                state.push_int(value | 1i32.wrapping_shl(bit as u32));
             }
         );
 
         handlers.insert(
             ScriptOpcode::CLEARBIT as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let bit = state.pop_int();
                 let value = state.pop_int();
 
-                state.push_int(value & !(1 << bit));
+                state.push_int(value & !1i32.wrapping_shl(bit as u32));
             }
         );
 
         handlers.insert(
             ScriptOpcode::TESTBIT as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let bit = state.pop_int();
                 let value = state.pop_int();
 
-                // Test if the bit is set, return 1 if set, 0 if not
-                state.push_int(if (value & (1 << bit)) != 0 { 1 } else { 0 });
+                state.push_int(((value & 1i32.wrapping_shl(bit as u32)) != 0) as i32);
             }
         );
 
         handlers.insert(
             ScriptOpcode::MODULO as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(2);
-                state.push_int(values[0] % values[1]);
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let b = state.pop_int();
+                let a = state.pop_int();
+
+                if b == 0 {
+                    error!("Modulo by zero: {} % {}", a, b);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                }
+                state.push_int(a.wrapping_rem(b));
             }
         );
 
         handlers.insert(
             ScriptOpcode::POW as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let exponent = state.pop_int();
                 let base = state.pop_int();
 
-                let result = (base as f64).powf(exponent as f64) as i32;
+                let result = if exponent < 0 {
+                    // Only a base of 1 or -1 has a whole number result.
+                    (base as f64).powi(exponent) as i32
+                } else {
+                    base.wrapping_pow(exponent as u32)
+                };
                 state.push_int(result);
             }
         );
 
         handlers.insert(
             ScriptOpcode::INVPOW as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let n2 = state.pop_int();
                 let n1 = state.pop_int();
 
-                if n1 == 0 || n2 == 0 {
-                    state.push_int(0)
-                } else {
-                    match n2 {
-                        1 => state.push_int(n1),
-                        2 => state.push_int((n1 as f64).sqrt() as i32),
-                        3 => state.push_int((n1 as f64).cbrt() as i32),
-                        4 => state.push_int((n1 as f64).sqrt().cbrt() as i32),
-
-                        _ => {
-                            let result = (n1 as f64).powf(1.0 / n2 as f64) as i32;
-                            state.push_int(result);
-                        }
-                    }
-                }
+                // Roots of negatives come out as NaN, which casts to 0.
+                let result = match n2 {
+                    _ if n1 == 0 || n2 == 0 => 0,
+                    1 => n1,
+                    2 => (n1 as f64).sqrt() as i32,
+                    3 => (n1 as f64).cbrt() as i32,
+                    4 => (n1 as f64).sqrt().sqrt() as i32,
+                    _ => (n1 as f64).powf(1.0 / n2 as f64) as i32,
+                };
+                state.push_int(result);
             }
         );
 
         handlers.insert(
             ScriptOpcode::AND as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(2);
-                state.push_int(values[0] & values[1]);
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let b = state.pop_int();
+                let a = state.pop_int();
+
+                state.push_int(a & b);
             }
         );
 
         handlers.insert(
             ScriptOpcode::OR as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(2);
-                state.push_int(values[0] | values[1]);
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let b = state.pop_int();
+                let a = state.pop_int();
+
+                state.push_int(a | b);
             }
         );
 
         handlers.insert(
             ScriptOpcode::MIN as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(2);
-                state.push_int(std::cmp::min(values[0], values[1]));
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let b = state.pop_int();
+                let a = state.pop_int();
+
+                state.push_int(a.min(b));
             }
         );
 
         handlers.insert(
             ScriptOpcode::MAX as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(2);
-                state.push_int(std::cmp::max(values[0], values[1]));
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let b = state.pop_int();
+                let a = state.pop_int();
+
+                state.push_int(a.max(b));
             }
         );
 
         handlers.insert(
             ScriptOpcode::SCALE as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(3);
-                state.push_int((values[0] * values[1]) / values[2]);
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let [a, b, c] = state.pop_ints(3)[..] else {
+                    return;
+                };
+
+                // a * c / b, the product is kept in 64 bits so only the final result wraps.
+                if b == 0 {
+                    error!("Scale by zero: {} * {} / {}", a, c, b);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                }
+                state.push_int((a as i64 * c as i64 / b as i64) as i32);
             }
         );
 
         handlers.insert(
             ScriptOpcode::BITCOUNT as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let value = state.pop_int();
                 state.push_int(bitcount(value));
             }
@@ -255,88 +269,122 @@ pub fn get_math_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::TOGGLEBIT as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(2);
-                state.push_int(values[0] ^ (1 << values[1]));
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let bit = state.pop_int();
+                let value = state.pop_int();
+
+                state.push_int(value ^ 1i32.wrapping_shl(bit as u32));
             }
         );
 
         handlers.insert(
             ScriptOpcode::SETBIT_RANGE as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(3);
-                state.push_int(set_bit_range(values[0], values[1], values[2]));
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let [num, start_bit, end_bit] = state.pop_ints(3)[..] else {
+                    return;
+                };
+
+                if check_bit_range(state, start_bit, end_bit) {
+                    state.push_int(set_bit_range(num, start_bit, end_bit));
+                }
             }
         );
 
         handlers.insert(
-            ScriptOpcode::CLEARBIT as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(3);
-                state.push_int(clear_bit_range(values[0], values[1], values[2]));
+            ScriptOpcode::CLEARBIT_RANGE as i32,
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let [num, start_bit, end_bit] = state.pop_ints(3)[..] else {
+                    return;
+                };
+
+                if check_bit_range(state, start_bit, end_bit) {
+                    state.push_int(clear_bit_range(num, start_bit, end_bit));
+                }
             }
         );
 
         handlers.insert(
             ScriptOpcode::GETBIT_RANGE as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(3);
-                let a = 31 - values[2];
-
-                let shifted_left = (values[0] << a) as u32;
-                let result = (shifted_left >> (values[1] + a)) as i32;
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let [num, start_bit, end_bit] = state.pop_ints(3)[..] else {
+                    return;
+                };
 
-                state.push_int(result);
+                if check_bit_range(state, start_bit, end_bit) {
+                    state.push_int((num >> start_bit) & MASK[(end_bit - start_bit + 1) as usize]);
+                }
             }
         );
 
         handlers.insert(
             ScriptOpcode::SETBIT_RANGE_TOINT as i32,
-            |state: &mut ScriptState| {
-                let values = state.pop_ints(4);
-                let cleared_bit_range = clear_bit_range(values[0], values[2], values[3]);
-                let max_value = MASK[(values[3] - values[2] + 1) as usize];
-                let mut assign_value = values[1];
-                if values[1] > max_value {
-                    assign_value = max_value;
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let [num, value, start_bit, end_bit] = state.pop_ints(4)[..] else {
+                    return;
+                };
+
+                if !check_bit_range(state, start_bit, end_bit) {
+                    return;
                 }
-                state.push_int(cleared_bit_range | (assign_value << values[2]));
+
+                // Values too big for the range are capped rather than cut, compared unsigned so negatives count as too big.
+                let max_value = MASK[(end_bit - start_bit + 1) as usize] as u32;
+                let cleared = clear_bit_range(num, start_bit, end_bit);
+                state.push_int(cleared | (((value as u32).min(max_value) as i32) << start_bit));
             }
         );
 
         handlers.insert(
             ScriptOpcode::SIN_DEG as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let value = state.pop_int();
-                //state.push_int(Trig::sin(value));
-                // TODO
+                state.push_int(trig().sin(value));
             }
         );
-        
+
         handlers.insert(
             ScriptOpcode::COS_DEG as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let value = state.pop_int();
-                // TODO
+                state.push_int(trig().cos(value));
             }
         );
-        
+
         handlers.insert(
             ScriptOpcode::ATAN2_DEG as i32,
-            |state: &mut ScriptState| {
-                let value = state.pop_int();
-                // TODO
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let x = state.pop_int();
+                let y = state.pop_int();
+
+                state.push_int(Trig::atan2(y, x));
             }
         );
 
         handlers.insert(
             ScriptOpcode::ABS as i32,
-            |state: &mut ScriptState| {
+            |_engine: &mut Engine, state: &mut ScriptState| {
                 let value = state.pop_int();
-                state.push_int(value.abs());
+                // -2147483648 has no positive counterpart and stays as it is.
+                state.push_int(value.wrapping_abs());
             }
         );
 
         handlers
     })
-}
\ No newline at end of file
+}
+
+#[inline]
+fn trig() -> &'static Trig {
+    static TRIG: OnceLock<Trig> = OnceLock::new();
+    TRIG.get_or_init(Trig::new)
+}
+
+/// Bit ranges have to fit inside an int with the start at or before the end.
+fn check_bit_range(state: &mut ScriptState, start_bit: i32, end_bit: i32) -> bool {
+    if !(0..32).contains(&start_bit) || !(start_bit..32).contains(&end_bit) {
+        error!("Invalid bit range: {} to {}", start_bit, end_bit);
+        state.execution = ScriptState::ABORTED;
+        return false;
+    }
+    true
+}
@@ -0,0 +1,246 @@
+use std::sync::Arc;
+use crate::engine::Engine;
+use crate::script::script_file::ScriptFile;
+use crate::script::script_opcode::ScriptOpcode;
+use crate::script::script_runner::ScriptRunner;
+use crate::script::script_state::ScriptState;
+
+/// Push `args` then run `opcode` through the script runner, giving back the finished state.
+fn run(opcode: ScriptOpcode, args: &[i32]) -> ScriptState {
+    let mut script = ScriptFile::new(0);
+    script.info.lookup_key = 0;
+    for &arg in args {
+        script.opcodes.push(ScriptOpcode::PUSH_CONSTANT_INT);
+        script.int_operands.push(arg);
+    }
+    script.opcodes.push(opcode);
+    script.int_operands.push(0);
+
+    let mut state = ScriptState::new(Arc::new(script), None);
+    ScriptRunner::execute(&mut Engine::new(), &mut state, false, false);
+    state
+}
+
+/// The single value `opcode` leaves on the stack.
+fn eval(opcode: ScriptOpcode, args: &[i32]) -> i32 {
+    let state = run(opcode, args);
+    assert_eq!(state.execution, ScriptState::FINISHED, "{:?}{:?} did not finish", opcode, args);
+    assert_eq!(state.isp, 1, "{:?}{:?} left {} values", opcode, args, state.isp);
+    state.int_stack[0]
+}
+
+fn aborts(opcode: ScriptOpcode, args: &[i32]) -> bool {
+    run(opcode, args).execution == ScriptState::ABORTED
+}
+
+#[test]
+fn test_add() {
+    assert_eq!(eval(ScriptOpcode::ADD, &[2, 3]), 5);
+    assert_eq!(eval(ScriptOpcode::ADD, &[i32::MAX, 1]), i32::MIN);
+}
+
+#[test]
+fn test_sub() {
+    assert_eq!(eval(ScriptOpcode::SUB, &[2, 3]), -1);
+    assert_eq!(eval(ScriptOpcode::SUB, &[i32::MIN, 1]), i32::MAX);
+}
+
+#[test]
+fn test_multiply() {
+    assert_eq!(eval(ScriptOpcode::MULTIPLY, &[-4, 3]), -12);
+    assert_eq!(eval(ScriptOpcode::MULTIPLY, &[65536, 65536]), 0);
+}
+
+#[test]
+fn test_divide() {
+    assert_eq!(eval(ScriptOpcode::DIVIDE, &[7, 2]), 3);
+    assert_eq!(eval(ScriptOpcode::DIVIDE, &[-7, 2]), -3);
+    assert_eq!(eval(ScriptOpcode::DIVIDE, &[i32::MIN, -1]), i32::MIN);
+    assert!(aborts(ScriptOpcode::DIVIDE, &[7, 0]));
+}
+
+#[test]
+fn test_random() {
+    for _ in 0..100 {
+        assert!((0..10).contains(&eval(ScriptOpcode::RANDOM, &[10])));
+    }
+    assert_eq!(eval(ScriptOpcode::RANDOM, &[1]), 0);
+    assert_eq!(eval(ScriptOpcode::RANDOM, &[-5]), 0);
+}
+
+#[test]
+fn test_randominc() {
+    for _ in 0..100 {
+        assert!((0..=2).contains(&eval(ScriptOpcode::RANDOMINC, &[2])));
+    }
+    assert_eq!(eval(ScriptOpcode::RANDOMINC, &[0]), 0);
+    assert_eq!(eval(ScriptOpcode::RANDOMINC, &[-5]), 0);
+}
+
+#[test]
+fn test_interpolate() {
+    // y0, y1, x0, x1, x
+    assert_eq!(eval(ScriptOpcode::INTERPOLATE, &[0, 100, 0, 10, 5]), 50);
+    assert_eq!(eval(ScriptOpcode::INTERPOLATE, &[10, 20, 2, 4, 3]), 15);
+    // The slope rounds down.
+    assert_eq!(eval(ScriptOpcode::INTERPOLATE, &[0, -5, 0, 2, 2]), -6);
+    assert_eq!(eval(ScriptOpcode::INTERPOLATE, &[7, 9, 3, 3, 5]), 7);
+}
+
+#[test]
+fn test_addpercent() {
+    assert_eq!(eval(ScriptOpcode::ADDPERCENT, &[200, 10]), 220);
+    assert_eq!(eval(ScriptOpcode::ADDPERCENT, &[5, 10]), 5);
+    assert_eq!(eval(ScriptOpcode::ADDPERCENT, &[100, -50]), 50);
+}
+
+#[test]
+fn test_setbit() {
+    assert_eq!(eval(ScriptOpcode::SETBIT, &[0, 3]), 8);
+    assert_eq!(eval(ScriptOpcode::SETBIT, &[0, 31]), i32::MIN);
+}
+
+#[test]
+fn test_clearbit() {
+    assert_eq!(eval(ScriptOpcode::CLEARBIT, &[15, 1]), 13);
+    assert_eq!(eval(ScriptOpcode::CLEARBIT, &[-1, 31]), i32::MAX);
+}
+
+#[test]
+fn test_testbit() {
+    assert_eq!(eval(ScriptOpcode::TESTBIT, &[8, 3]), 1);
+    assert_eq!(eval(ScriptOpcode::TESTBIT, &[8, 2]), 0);
+    assert_eq!(eval(ScriptOpcode::TESTBIT, &[i32::MIN, 31]), 1);
+}
+
+#[test]
+fn test_modulo() {
+    assert_eq!(eval(ScriptOpcode::MODULO, &[7, 3]), 1);
+    assert_eq!(eval(ScriptOpcode::MODULO, &[-7, 3]), -1);
+    assert_eq!(eval(ScriptOpcode::MODULO, &[i32::MIN, -1]), 0);
+    assert!(aborts(ScriptOpcode::MODULO, &[7, 0]));
+}
+
+#[test]
+fn test_pow() {
+    assert_eq!(eval(ScriptOpcode::POW, &[2, 10]), 1024);
+    assert_eq!(eval(ScriptOpcode::POW, &[-3, 3]), -27);
+    assert_eq!(eval(ScriptOpcode::POW, &[5, 0]), 1);
+    assert_eq!(eval(ScriptOpcode::POW, &[2, 32]), 0);
+    assert_eq!(eval(ScriptOpcode::POW, &[2, -1]), 0);
+    assert_eq!(eval(ScriptOpcode::POW, &[-1, -3]), -1);
+}
+
+#[test]
+fn test_invpow() {
+    assert_eq!(eval(ScriptOpcode::INVPOW, &[81, 1]), 81);
+    assert_eq!(eval(ScriptOpcode::INVPOW, &[81, 2]), 9);
+    assert_eq!(eval(ScriptOpcode::INVPOW, &[27, 3]), 3);
+    assert_eq!(eval(ScriptOpcode::INVPOW, &[81, 4]), 3);
+    assert_eq!(eval(ScriptOpcode::INVPOW, &[1024, 10]), 2);
+    assert_eq!(eval(ScriptOpcode::INVPOW, &[81, 0]), 0);
+    assert_eq!(eval(ScriptOpcode::INVPOW, &[-4, 2]), 0);
+}
+
+#[test]
+fn test_and() {
+    assert_eq!(eval(ScriptOpcode::AND, &[0b1100, 0b1010]), 0b1000);
+}
+
+#[test]
+fn test_or() {
+    assert_eq!(eval(ScriptOpcode::OR, &[0b1100, 0b1010]), 0b1110);
+}
+
+#[test]
+fn test_min() {
+    assert_eq!(eval(ScriptOpcode::MIN, &[-2, 5]), -2);
+    assert_eq!(eval(ScriptOpcode::MIN, &[5, -2]), -2);
+}
+
+#[test]
+fn test_max() {
+    assert_eq!(eval(ScriptOpcode::MAX, &[-2, 5]), 5);
+    assert_eq!(eval(ScriptOpcode::MAX, &[5, -2]), 5);
+}
+
+#[test]
+fn test_scale() {
+    // a * c / b
+    assert_eq!(eval(ScriptOpcode::SCALE, &[50, 100, 30]), 15);
+    assert_eq!(eval(ScriptOpcode::SCALE, &[1_000_000, 1_000, 1_000]), 1_000_000);
+    assert!(aborts(ScriptOpcode::SCALE, &[1, 0, 1]));
+}
+
+#[test]
+fn test_bitcount() {
+    assert_eq!(eval(ScriptOpcode::BITCOUNT, &[0b1011]), 3);
+    assert_eq!(eval(ScriptOpcode::BITCOUNT, &[-1]), 32);
+    assert_eq!(eval(ScriptOpcode::BITCOUNT, &[i32::MIN]), 1);
+}
+
+#[test]
+fn test_togglebit() {
+    assert_eq!(eval(ScriptOpcode::TOGGLEBIT, &[0b101, 0]), 0b100);
+    assert_eq!(eval(ScriptOpcode::TOGGLEBIT, &[0b101, 1]), 0b111);
+}
+
+#[test]
+fn test_setbit_range() {
+    assert_eq!(eval(ScriptOpcode::SETBIT_RANGE, &[0, 4, 7]), 0xf0);
+    assert_eq!(eval(ScriptOpcode::SETBIT_RANGE, &[0, 0, 31]), -1);
+    assert!(aborts(ScriptOpcode::SETBIT_RANGE, &[0, 7, 4]));
+}
+
+#[test]
+fn test_clearbit_range() {
+    assert_eq!(eval(ScriptOpcode::CLEARBIT_RANGE, &[0xff, 4, 7]), 0x0f);
+    assert_eq!(eval(ScriptOpcode::CLEARBIT_RANGE, &[-1, 0, 31]), 0);
+    assert!(aborts(ScriptOpcode::CLEARBIT_RANGE, &[0, 0, 32]));
+}
+
+#[test]
+fn test_getbit_range() {
+    assert_eq!(eval(ScriptOpcode::GETBIT_RANGE, &[0xab, 4, 7]), 0xa);
+    assert_eq!(eval(ScriptOpcode::GETBIT_RANGE, &[i32::MIN, 31, 31]), 1);
+    assert_eq!(eval(ScriptOpcode::GETBIT_RANGE, &[-1, 0, 31]), -1);
+}
+
+#[test]
+fn test_setbit_range_toint() {
+    // num, value, start, end
+    assert_eq!(eval(ScriptOpcode::SETBIT_RANGE_TOINT, &[0xff, 5, 4, 7]), 0x5f);
+    // Too big for four bits, capped at 15.
+    assert_eq!(eval(ScriptOpcode::SETBIT_RANGE_TOINT, &[0, 100, 4, 7]), 0xf0);
+    assert_eq!(eval(ScriptOpcode::SETBIT_RANGE_TOINT, &[0, -1, 4, 7]), 0xf0);
+    assert_eq!(eval(ScriptOpcode::SETBIT_RANGE_TOINT, &[0, -5, 0, 31]), -5);
+}
+
+#[test]
+fn test_sin_deg() {
+    assert_eq!(eval(ScriptOpcode::SIN_DEG, &[0]), 0);
+    assert_eq!(eval(ScriptOpcode::SIN_DEG, &[4096]), 16384);
+    assert_eq!(eval(ScriptOpcode::SIN_DEG, &[16384 + 4096]), 16384);
+}
+
+#[test]
+fn test_cos_deg() {
+    assert_eq!(eval(ScriptOpcode::COS_DEG, &[0]), 16384);
+    assert_eq!(eval(ScriptOpcode::COS_DEG, &[8192]), -16384);
+}
+
+#[test]
+fn test_atan2_deg() {
+    // y, x
+    assert_eq!(eval(ScriptOpcode::ATAN2_DEG, &[0, 1]), 0);
+    assert_eq!(eval(ScriptOpcode::ATAN2_DEG, &[1, 0]), 4096);
+    assert_eq!(eval(ScriptOpcode::ATAN2_DEG, &[0, -1]), 8192);
+    assert_eq!(eval(ScriptOpcode::ATAN2_DEG, &[-1, 0]), 12288);
+}
+
+#[test]
+fn test_abs() {
+    assert_eq!(eval(ScriptOpcode::ABS, &[-5]), 5);
+    assert_eq!(eval(ScriptOpcode::ABS, &[5]), 5);
+    assert_eq!(eval(ScriptOpcode::ABS, &[i32::MIN]), i32::MIN);
+}
@@ -1,3 +1,12 @@
 pub mod player_ops;
 pub mod core_ops;
-mod math_ops;
\ No newline at end of file
+pub mod server_ops;
+pub mod math_ops;
+pub mod config_ops;
+pub mod npc_ops;
+#[cfg(test)]
+mod core_ops_tests;
+#[cfg(test)]
+mod math_ops_tests;
+#[cfg(test)]
+mod npc_ops_tests;
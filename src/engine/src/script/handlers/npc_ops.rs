@@ -0,0 +1,93 @@
+use crate::script::script_opcode::ScriptOpcode;
+use crate::script::script_runner::CommandHandlers;
+use crate::script::script_state::ScriptState;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use log::error;
+use crate::engine::Engine;
+use crate::script::script_provider::ScriptProvider;
+use crate::script::server_trigger_types::ServerTriggerTypes;
+
+pub fn get_npc_ops() -> &'static CommandHandlers {
+    static HANDLERS: OnceLock<CommandHandlers> = OnceLock::new();
+
+    HANDLERS.get_or_init(|| {
+        let mut handlers: CommandHandlers = HashMap::with_capacity(64); // TODO - Update as need be.
+
+        handlers.insert(
+            ScriptOpcode::NPC_DELAY as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let delay = state.pop_int();
+
+                let Some(nid) = active_npc(engine, state) else {
+                    return;
+                };
+
+                if let Some(npc) = engine.npcs.get_mut(nid) {
+                    npc.pathing_entity.delay(engine.current_tick, delay);
+                }
+                state.execution = ScriptState::NPC_SUSPENDED;
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::NPC_QUEUE as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let [queue_id, arg, delay] = state.pop_ints(3)[..] else {
+                    return;
+                };
+
+                let trigger = Some(queue_id)
+                    .filter(|queue_id| (1..=20).contains(queue_id))
+                    .and_then(|queue_id| ServerTriggerTypes::try_from(ServerTriggerTypes::AI_QUEUE1 as i32 + queue_id - 1).ok());
+                let Some(trigger) = trigger else {
+                    error!("Invalid ai_queue: {}", queue_id);
+                    state.execution = ScriptState::ABORTED;
+                    return;
+                };
+
+                let Some(nid) = active_npc(engine, state) else {
+                    return;
+                };
+
+                if let Some(npc) = engine.npcs.get_mut(nid) {
+                    // TODO - look up by category as well once npc types are loaded
+                    if let Some(script) = ScriptProvider::get_by_trigger(trigger, npc.id as i32, -1) {
+                        npc.enqueue_script(script, delay, arg);
+                    }
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::NPC_SETTIMER as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let interval = state.pop_int();
+
+                let Some(nid) = active_npc(engine, state) else {
+                    return;
+                };
+
+                if let Some(npc) = engine.npcs.get_mut(nid) {
+                    // TODO - look up by category as well once npc types are loaded
+                    let script = ScriptProvider::get_by_trigger(ServerTriggerTypes::AI_TIMER, npc.id as i32, -1);
+                    npc.set_timer(script, interval);
+                }
+            }
+        );
+
+        handlers
+    })
+}
+
+/// Nid of the active npc, the operand picking the secondary one.
+fn active_npc(engine: &Engine, state: &mut ScriptState) -> Option<usize> {
+    match state.get_active_npc_nid(engine, state.get_int_operand() == 1) {
+        Ok(nid) => Some(nid),
+        Err(e) => {
+            error!("{}", e);
+            state.execution = ScriptState::ABORTED;
+            None
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+use crate::engine::Engine;
+use crate::script::script_file::ScriptFile;
+use crate::script::script_opcode::ScriptOpcode;
+use crate::script::script_runner::ScriptRunner;
+use crate::script::script_state::ScriptState;
+
+/// Run npc_queue with `queue_id`, no npc is needed for the queue id to be checked.
+fn npc_queue(queue_id: i32) -> ScriptState {
+    let mut script = ScriptFile::new(0);
+    script.info.lookup_key = 0;
+    for arg in [queue_id, 0, 0] {
+        script.opcodes.push(ScriptOpcode::PUSH_CONSTANT_INT);
+        script.int_operands.push(arg);
+    }
+    script.opcodes.push(ScriptOpcode::NPC_QUEUE);
+    script.int_operands.push(0);
+
+    let mut state = ScriptState::new(Arc::new(script), None);
+    ScriptRunner::execute(&mut Engine::new(), &mut state, false, false);
+    state
+}
+
+#[test]
+fn test_npc_queue_out_of_range_aborts() {
+    for queue_id in [0, 21, i32::MAX, i32::MIN] {
+        assert_eq!(npc_queue(queue_id).execution, ScriptState::ABORTED, "ai_queue{}", queue_id);
+    }
+}
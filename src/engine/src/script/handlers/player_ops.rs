@@ -1,10 +1,16 @@
 use crate::script::script_opcode::ScriptOpcode;
 use crate::script::script_runner::CommandHandlers;
 use crate::script::script_state::ScriptState;
+use crate::script::script_file::ScriptFile;
 use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
+use log::error;
 use crate::engine::Engine;
+use crate::entity::entity_queue_request::PlayerQueueType;
+use crate::entity::entity_timer::PlayerTimerType;
 use crate::io::server::model::message_game::Message_Game;
+use crate::script::script_pointer::PROTECTED_ACTIVE_PLAYER;
+use crate::script::script_provider::ScriptProvider;
 
 pub fn get_player_ops() -> &'static CommandHandlers {
     static HANDLERS: OnceLock<CommandHandlers> = OnceLock::new();
@@ -14,13 +20,233 @@ pub fn get_player_ops() -> &'static CommandHandlers {
 
         handlers.insert(
             ScriptOpcode::MES as i32,
-            |state: &mut ScriptState| {
-                let pid = state.get_active_player().expect("No active player found").get_pid();
-                let player = Engine::get().players.get_mut(pid).expect(format!("No player found for PID: {}", pid).as_str());
-                player.write(Message_Game::new(state.pop_string()));
+            |engine: &mut Engine, state: &mut ScriptState| {
+                match state.get_active_player(engine) {
+                    Ok(player) => player.write(Message_Game::new(state.pop_string())),
+                    Err(err) => {
+                        error!("{}", err);
+                        state.execution = ScriptState::ABORTED;
+                    },
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::LAST_INT as i32,
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                state.push_int(state.last_int);
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::P_DELAY as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let delay = state.pop_int();
+
+                if let Some(pid) = protected_player(engine, state) {
+                    if let Some(player) = engine.players.get_mut(pid) {
+                        player.pathing_entity.delay(engine.current_tick, delay);
+                    }
+                    state.execution = ScriptState::SUSPENDED;
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::P_PAUSEBUTTON as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                if protected_player(engine, state).is_some() {
+                    state.execution = ScriptState::PAUSEBUTTON;
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::P_COUNTDIALOG as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                // The script opens the prompt itself, all that's left is to wait on the answer.
+                if protected_player(engine, state).is_some() {
+                    state.execution = ScriptState::COUNTDIALOG;
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::QUEUE as i32,
+            |engine: &mut Engine, state: &mut ScriptState| queue_script(engine, state, PlayerQueueType::Normal)
+        );
+
+        handlers.insert(
+            ScriptOpcode::WEAKQUEUE as i32,
+            |engine: &mut Engine, state: &mut ScriptState| queue_script(engine, state, PlayerQueueType::Weak)
+        );
+
+        handlers.insert(
+            ScriptOpcode::STRONGQUEUE as i32,
+            |engine: &mut Engine, state: &mut ScriptState| queue_script(engine, state, PlayerQueueType::Strong)
+        );
+
+        handlers.insert(
+            ScriptOpcode::LONGQUEUE as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let args = state.pop_script_args();
+                let [script_id, delay, logout_action] = state.pop_ints(3)[..] else {
+                    return;
+                };
+
+                let Some(script) = script_target(state, script_id) else {
+                    return;
+                };
+
+                if let Some(pid) = active_player(engine, state) {
+                    if let Some(player) = engine.players.get_mut(pid) {
+                        player.enqueue_script(script, PlayerQueueType::Long, delay, args).logout_action = logout_action;
+                    }
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::CLEARQUEUE as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let script_id = state.pop_int();
+
+                if let Some(pid) = active_player(engine, state) {
+                    if let Some(player) = engine.players.get_mut(pid) {
+                        player.unlink_queued_script(script_id);
+                    }
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::GETQUEUE as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let script_id = state.pop_int();
+
+                if let Some(pid) = active_player(engine, state) {
+                    state.push_int(engine.players.get(pid).map_or(0, |player| player.queued_script_count(script_id)));
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::SETTIMER as i32,
+            |engine: &mut Engine, state: &mut ScriptState| set_timer(engine, state, PlayerTimerType::Normal)
+        );
+
+        handlers.insert(
+            ScriptOpcode::SOFTTIMER as i32,
+            |engine: &mut Engine, state: &mut ScriptState| set_timer(engine, state, PlayerTimerType::Soft)
+        );
+
+        handlers.insert(
+            ScriptOpcode::CLEARTIMER as i32,
+            |engine: &mut Engine, state: &mut ScriptState| clear_timer(engine, state, PlayerTimerType::Normal)
+        );
+
+        handlers.insert(
+            ScriptOpcode::CLEARSOFTTIME as i32,
+            |engine: &mut Engine, state: &mut ScriptState| clear_timer(engine, state, PlayerTimerType::Soft)
+        );
+
+        handlers.insert(
+            ScriptOpcode::GETTIMER as i32,
+            |engine: &mut Engine, state: &mut ScriptState| {
+                let script_id = state.pop_int();
+
+                // Ticks until the timer next fires, -1 if there isn't one.
+                if let Some(pid) = active_player(engine, state) {
+                    let clock = engine.players.get(pid)
+                        .and_then(|player| player.timers.get(&script_id))
+                        .map_or(-1, |timer| timer.clock.max(0));
+                    state.push_int(clock);
+                }
             }
         );
 
         handlers  
     })
+}
+
+/// Start a timer on the active player, the script id and interval sit under its args.
+fn set_timer(engine: &mut Engine, state: &mut ScriptState, timer_type: PlayerTimerType) {
+    let args = state.pop_script_args();
+    let [script_id, interval] = state.pop_ints(2)[..] else {
+        return;
+    };
+
+    let Some(script) = script_target(state, script_id) else {
+        return;
+    };
+
+    if let Some(pid) = active_player(engine, state) {
+        if let Some(player) = engine.players.get_mut(pid) {
+            player.set_timer(timer_type, script, args, interval);
+        }
+    }
+}
+
+fn clear_timer(engine: &mut Engine, state: &mut ScriptState, timer_type: PlayerTimerType) {
+    let script_id = state.pop_int();
+
+    if let Some(pid) = active_player(engine, state) {
+        if let Some(player) = engine.players.get_mut(pid) {
+            player.clear_timer(timer_type, script_id);
+        }
+    }
+}
+
+/// Queue a script on the active player, the script id and delay sit under its args.
+fn queue_script(engine: &mut Engine, state: &mut ScriptState, queue_type: PlayerQueueType) {
+    let args = state.pop_script_args();
+    let [script_id, delay] = state.pop_ints(2)[..] else {
+        return;
+    };
+
+    let Some(script) = script_target(state, script_id) else {
+        return;
+    };
+
+    if let Some(pid) = active_player(engine, state) {
+        if let Some(player) = engine.players.get_mut(pid) {
+            player.enqueue_script(script, queue_type, delay, args);
+        }
+    }
+}
+
+/// Script a queue or timer op runs, aborting if it doesn't exist.
+fn script_target(state: &mut ScriptState, script_id: i32) -> Option<Arc<ScriptFile>> {
+    let script = ScriptProvider::get(script_id as usize);
+    if script.is_none() {
+        error!("Unable to find script: {}", script_id);
+        state.execution = ScriptState::ABORTED;
+    }
+    script
+}
+
+/// Pid of the active player, the operand picking the secondary one.
+fn active_player(engine: &Engine, state: &mut ScriptState) -> Option<usize> {
+    match state.get_active_player_pid(engine, state.get_int_operand() == 1) {
+        Ok(pid) => Some(pid),
+        Err(e) => {
+            error!("{}", e);
+            state.execution = ScriptState::ABORTED;
+            None
+        }
+    }
+}
+
+/// Pid of the active player, only if the script has protected access to them.
+fn protected_player(engine: &Engine, state: &mut ScriptState) -> Option<usize> {
+    let secondary = state.get_int_operand() == 1;
+    let pointer = PROTECTED_ACTIVE_PLAYER[secondary as usize];
+    match state.pointer_check(&[pointer]).and_then(|_| state.get_active_player_pid(engine, secondary)) {
+        Ok(pid) => Some(pid),
+        Err(e) => {
+            error!("{}", e);
+            state.execution = ScriptState::ABORTED;
+            None
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+use crate::script::script_opcode::ScriptOpcode;
+use crate::engine::Engine;
+use crate::script::script_runner::CommandHandlers;
+use crate::script::script_state::ScriptState;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use log::error;
+use crate::game_map::GameMap;
+use crate::grid::coord_grid::CoordGrid;
+
+pub fn get_server_ops() -> &'static CommandHandlers {
+    static HANDLERS: OnceLock<CommandHandlers> = OnceLock::new();
+
+    HANDLERS.get_or_init(|| {
+        let mut handlers: CommandHandlers = HashMap::with_capacity(64); // TODO - Update as need be.
+
+        handlers.insert(
+            ScriptOpcode::LINEOFSIGHT as i32,
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let to = state.pop_int();
+                let from = state.pop_int();
+
+                if let (Some(from), Some(to)) = (check_coord(state, from), check_coord(state, to)) {
+                    state.push_int(GameMap::has_line_of_sight(from, to, 1, 1, 1, 1) as i32);
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::LINEOFWALK as i32,
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let to = state.pop_int();
+                let from = state.pop_int();
+
+                if let (Some(from), Some(to)) = (check_coord(state, from), check_coord(state, to)) {
+                    state.push_int(GameMap::has_line_of_walk(from, to, 1, 1, 1, 1) as i32);
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::MAP_BLOCKED as i32,
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                let coord = state.pop_int();
+
+                if let Some(coord) = check_coord(state, coord) {
+                    state.push_int(GameMap::is_blocked(coord) as i32);
+                }
+            }
+        );
+
+        handlers.insert(
+            ScriptOpcode::WORLD_DELAY as i32,
+            |_engine: &mut Engine, state: &mut ScriptState| {
+                // The delay stays on the stack for the world queue to pick up.
+                state.execution = ScriptState::WORLD_SUSPENDED;
+            }
+        );
+
+        handlers
+    })
+}
+
+/// Scripts pass coords packed the same way as [CoordGrid], anything negative is out of range.
+fn check_coord(state: &mut ScriptState, coord: i32) -> Option<CoordGrid> {
+    if coord < 0 {
+        error!("Coord out of range: {}", coord);
+        state.execution = ScriptState::ABORTED;
+        return None;
+    }
+
+    Some(CoordGrid::new(coord as u32))
+}
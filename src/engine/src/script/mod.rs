@@ -6,4 +6,8 @@ pub mod server_trigger_types;
 mod handlers;
 pub mod script_state;
 pub mod script_pointer;
-pub mod script_runner;
\ No newline at end of file
+pub mod script_runner;
+#[cfg(test)]
+mod script_provider_tests;
+#[cfg(test)]
+mod script_state_tests;
@@ -146,6 +146,19 @@ pub enum ScriptOpcode {
     LAST_USESLOT = 2064,
     LONGQUEUE = 2065,
     MES = 1000,
+    P_COUNTDIALOG = 2072,
+    P_DELAY = 2073,
+    P_PAUSEBUTTON = 2085,
+    QUEUE = 2094,
+    SETTIMER = 2097,
+    SOFTTIMER = 2098,
+    STRONGQUEUE = 2110,
+    WEAKQUEUE = 2112,
+
+    // Npc ops (2500-2999)
+    NPC_DELAY = 2507,
+    NPC_QUEUE = 2512,
+    NPC_SETTIMER = 2519,
     
     // Enum ops (4400-4499)
     ENUM = 4400,
@@ -1,15 +1,73 @@
 use std::collections::HashMap;
 use std::error::Error;
-use std::sync::OnceLock;
-use log::{debug, error};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use log::{debug, error, info};
+use once_cell::sync::Lazy;
+use config::server_config::ServerConfig;
 use crate::io::packet::Packet;
 use crate::script::script_file::ScriptFile;
 use crate::script::server_trigger_types::ServerTriggerTypes;
 
-// Static storage using OnceLock for thread safety
-static SCRIPTS: OnceLock<Vec<ScriptFile>> = OnceLock::new();
-static SCRIPT_LOOKUP: OnceLock<HashMap<usize, ScriptFile>> = OnceLock::new();
-static SCRIPT_NAMES: OnceLock<HashMap<String, usize>> = OnceLock::new();
+/// Every table built from one read of script.dat, swapped out as a whole on reload.
+#[derive(Default)]
+pub(crate) struct ScriptTables {
+    scripts: HashMap<usize, Arc<ScriptFile>>,
+    lookup: HashMap<usize, Arc<ScriptFile>>,
+    names: HashMap<String, usize>,
+}
+
+impl ScriptTables {
+    pub(crate) fn insert(&mut self, id: usize, script: Arc<ScriptFile>) {
+        if script.info.lookup_key != -1 {
+            self.lookup.insert(script.info.lookup_key as usize, Arc::clone(&script));
+        }
+
+        self.names.insert(script.info.script_name.clone(), id);
+        self.scripts.insert(id, script);
+    }
+}
+
+// Scripts are handed out as Arcs, so states already running keep the version they started with after a reload.
+static SCRIPT_TABLES: Lazy<RwLock<Arc<ScriptTables>>> = Lazy::new(Default::default);
+
+/// What changed between two loads of the scripts, by script name.
+#[derive(Debug, Default)]
+pub struct ScriptReload {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl ScriptReload {
+    /// Compare two loads by script name, sorted so the log reads the same every time.
+    pub(crate) fn between(previous: &ScriptTables, current: &ScriptTables) -> ScriptReload {
+        let mut reload = ScriptReload::default();
+        for (name, id) in &current.names {
+            match previous.names.get(name).and_then(|old| previous.scripts.get(old)) {
+                None => reload.added.push(name.clone()),
+                Some(old) if Some(old) != current.scripts.get(id) => reload.changed.push(name.clone()),
+                Some(_) => {},
+            }
+        }
+        for name in previous.names.keys() {
+            if !current.names.contains_key(name) {
+                reload.removed.push(name.clone());
+            }
+        }
+
+        reload.added.sort();
+        reload.removed.sort();
+        reload.changed.sort();
+        reload
+    }
+}
+
+impl fmt::Display for ScriptReload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} added, {} removed, {} changed", self.added.len(), self.removed.len(), self.changed.len())
+    }
+}
 
 pub struct ScriptProvider;
 
@@ -17,33 +75,60 @@ impl ScriptProvider {
     pub const COMPILER_VERSION: u32 = 23;
 
     #[inline]
-    fn ensure_initialized() {
-        SCRIPTS.get_or_init(|| Vec::new());
-        SCRIPT_LOOKUP.get_or_init(|| HashMap::new());
-        SCRIPT_NAMES.get_or_init(|| HashMap::new());
+    fn tables() -> Arc<ScriptTables> {
+        Arc::clone(&SCRIPT_TABLES.read().unwrap())
     }
 
     pub fn load() -> u32 {
-        let dat_path = "./data/pack/server/script.dat";
-        let idx_path = "./data/pack/server/script.idx";
-
-        match (Packet::io(dat_path.parse().unwrap()), Packet::io(idx_path.parse().unwrap())) {
-            (Ok(dat), Ok(idx)) => {
-                let count = Self::parse(dat, idx);
+        match Self::read() {
+            Ok(tables) => {
+                let count = tables.scripts.len() as u32;
+                *SCRIPT_TABLES.write().unwrap() = Arc::new(tables);
                 debug!("Loaded {} scripts", count);
                 count
             },
-            _ => {
-                error!("Failed to load script data or index files");
+            Err(e) => {
+                error!("Failed to load scripts: {}", e);
                 0
             }
         }
     }
 
-    fn parse(mut dat: Packet, mut idx: Packet) -> u32 {
+    /// Read script.dat/script.idx again and swap the new tables in.
+    ///
+    /// The old tables stay in place if the new files can't be read.
+    pub fn reload() -> Result<ScriptReload, Box<dyn Error>> {
+        let current = Arc::new(Self::read()?);
+        let previous = std::mem::replace(&mut *SCRIPT_TABLES.write().unwrap(), Arc::clone(&current));
+
+        let reload = ScriptReload::between(&previous, &current);
+        info!("Reloaded {} scripts: {}", current.scripts.len(), reload);
+        for name in &reload.added {
+            info!("Added script {}", name);
+        }
+        for name in &reload.removed {
+            info!("Removed script {}", name);
+        }
+        for name in &reload.changed {
+            info!("Changed script {}", name);
+        }
+
+        Ok(reload)
+    }
+
+    fn read() -> Result<ScriptTables, Box<dyn Error>> {
+        let server_pack = ServerConfig::get().paths.data.join("pack/server");
+        let dat_path = server_pack.join("script.dat").to_string_lossy().into_owned();
+        let idx_path = server_pack.join("script.idx").to_string_lossy().into_owned();
+
+        let dat = Packet::io(dat_path.clone()).map_err(|e| format!("failed to read {}: {}", dat_path, e))?;
+        let idx = Packet::io(idx_path.clone()).map_err(|e| format!("failed to read {}: {}", idx_path, e))?;
+        Self::parse(dat, idx)
+    }
+
+    fn parse(mut dat: Packet, mut idx: Packet) -> Result<ScriptTables, Box<dyn Error>> {
         if dat.is_empty() || idx.is_empty() {
-            error!("No scripts data found, rebuild scripts.");
-            return 0;
+            return Err("no scripts data found, rebuild scripts".into());
         }
 
         let entries = dat.g4();
@@ -55,58 +140,46 @@ impl ScriptProvider {
         }
 
         // Pre-allocate with capacity for better performance
-        let mut scripts: Vec<ScriptFile> = Vec::with_capacity(entries as usize);
-        let mut script_names: HashMap<String, usize> = HashMap::with_capacity(entries as usize);
-        let mut script_lookup: HashMap<usize, ScriptFile> = HashMap::with_capacity(entries as usize);
+        let mut tables = ScriptTables {
+            scripts: HashMap::with_capacity(entries as usize),
+            lookup: HashMap::with_capacity(entries as usize),
+            names: HashMap::with_capacity(entries as usize),
+        };
 
-        let mut loaded = 0;
         for id in 0..entries {
             let size = idx.g4();
             if size == 0 {
                 continue;
             }
 
-            match (|| {
-                let bytes = dat.gbytes(size as usize);
-                let script = ScriptFile::decode(id as usize, Packet::from(bytes));
-
-                if script.info.lookup_key != -1 {
-                    script_lookup.insert(script.info.lookup_key as usize, script.clone());
-                }
-
-                script_names.insert(script.info.script_name.clone(), id as usize);
-                scripts.push(script);
-
-                loaded += 1;
-                Ok::<_, Box<dyn Error>>(())
-            })() {
-                Ok(_) => {},
-                Err(err) => {
-                    eprintln!("Error: {}", err);
-                    eprintln!("Warning: Failed to load script {}, something may have been partially written", id);
-                    return 0;
-                }
+            if dat.remaining() < size {
+                return Err(format!("script {} is truncated, something may have been partially written", id).into());
             }
+
+            let bytes = dat.gbytes(size as usize);
+            tables.insert(id as usize, Arc::new(ScriptFile::decode(id as usize, Packet::from(bytes))));
         }
 
-        // Set the global collections at once after loading all data
-        let _ = SCRIPTS.set(scripts);
-        let _ = SCRIPT_NAMES.set(script_names);
-        let _ = SCRIPT_LOOKUP.set(script_lookup);
+        Ok(tables)
+    }
 
-        loaded
+    /// Script by id, as used by gosub and jump.
+    #[inline]
+    pub fn get(id: usize) -> Option<Arc<ScriptFile>> {
+        Self::tables().scripts.get(&id).cloned()
     }
 
+    /// Script by name, which unlike its id stays the same across recompiles.
     #[inline]
-    pub fn get(id: usize) -> Option<ScriptFile> {
-        let lookup = SCRIPT_LOOKUP.get()?;
-        lookup.get(&id).cloned()
+    pub fn get_by_name(name: &str) -> Option<Arc<ScriptFile>> {
+        let tables = Self::tables();
+        tables.names.get(name).and_then(|id| tables.scripts.get(id)).cloned()
     }
-    
+
     #[inline]
-    pub fn get_by_trigger(trigger: ServerTriggerTypes, type_id: i32, category: i32) -> Option<ScriptFile> {
-        Self::ensure_initialized();
-        let lookup = SCRIPT_LOOKUP.get()?;
+    pub fn get_by_trigger(trigger: ServerTriggerTypes, type_id: i32, category: i32) -> Option<Arc<ScriptFile>> {
+        let tables = Self::tables();
+        let lookup = &tables.lookup;
 
         if type_id != -1 {
             // Create key: trigger | (0x2 << 8) | (type_id << 10)
@@ -129,9 +202,9 @@ impl ScriptProvider {
     }
 
     #[inline]
-    pub fn get_by_trigger_specific(trigger: ServerTriggerTypes, type_id: i32, category: i32) -> Option<ScriptFile> {
-        Self::ensure_initialized();
-        let lookup = SCRIPT_LOOKUP.get()?;
+    pub fn get_by_trigger_specific(trigger: ServerTriggerTypes, type_id: i32, category: i32) -> Option<Arc<ScriptFile>> {
+        let tables = Self::tables();
+        let lookup = &tables.lookup;
 
         // Early return pattern for clarity and performance
         if type_id != -1 {
@@ -147,4 +220,4 @@ impl ScriptProvider {
         // Fallback: return script for the trigger itself
         lookup.get(&(trigger as usize)).cloned()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+use crate::script::script_file::ScriptFile;
+use crate::script::script_opcode::ScriptOpcode;
+use crate::script::script_provider::{ScriptReload, ScriptTables};
+
+/// A script that returns straight away, `operand` standing in for a change to its body.
+fn script(id: usize, name: &str, operand: i32) -> Arc<ScriptFile> {
+    let mut script = ScriptFile::new(id as i32);
+    script.info.script_name = name.to_string();
+    script.opcodes.push(ScriptOpcode::PUSH_CONSTANT_INT);
+    script.int_operands.push(operand);
+    Arc::new(script)
+}
+
+fn tables(scripts: &[(usize, &str, i32)]) -> ScriptTables {
+    let mut tables = ScriptTables::default();
+    for &(id, name, operand) in scripts {
+        tables.insert(id, script(id, name, operand));
+    }
+    tables
+}
+
+#[test]
+fn test_reload_reports_added_changed_and_removed() {
+    let previous = tables(&[(0, "[proc,kept]", 1), (1, "[proc,edited]", 1), (2, "[proc,deleted]", 1)]);
+    let current = tables(&[(0, "[proc,kept]", 1), (1, "[proc,edited]", 2), (2, "[proc,new_b]", 1), (3, "[proc,new_a]", 1)]);
+
+    let reload = ScriptReload::between(&previous, &current);
+    assert_eq!(reload.added, vec!["[proc,new_a]", "[proc,new_b]"]);
+    assert_eq!(reload.changed, vec!["[proc,edited]"]);
+    assert_eq!(reload.removed, vec!["[proc,deleted]"]);
+}
+
+#[test]
+fn test_reload_without_changes_reports_nothing() {
+    let scripts = [(0, "[proc,a]", 1), (1, "[proc,b]", 2)];
+    let reload = ScriptReload::between(&tables(&scripts), &tables(&scripts));
+
+    assert!(reload.added.is_empty());
+    assert!(reload.changed.is_empty());
+    assert!(reload.removed.is_empty());
+}
@@ -1,17 +1,21 @@
 use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 use log::{debug, error};
+use crate::engine::Engine;
 use crate::entity::entity_queue_request::ScriptArgument;
 use crate::entity::entity_type::EntityType;
+use crate::script::handlers::config_ops::get_config_ops;
 use crate::script::handlers::core_ops::get_core_ops;
+use crate::script::handlers::math_ops::get_math_ops;
+use crate::script::handlers::npc_ops::get_npc_ops;
 use crate::script::handlers::player_ops::get_player_ops;
+use crate::script::handlers::server_ops::get_server_ops;
 use crate::script::script_file::ScriptFile;
-use crate::script::script_pointer::ScriptPointer;
 use crate::script::script_state::ScriptState;
 
-// Function type for script handlers - requires Send+Sync for thread safety
-pub type CommandHandler = fn(&mut ScriptState);
+// Function type for script handlers, given the world the script is running in
+pub type CommandHandler = fn(&mut Engine, &mut ScriptState);
 
 // Map of opcode numbers to handler functions
 pub type CommandHandlers = HashMap<i32, CommandHandler>;
@@ -35,13 +39,29 @@ impl ScriptRunner {
                 handlers.insert(*key, *func);
             }
 
+            for (key, func) in get_math_ops().iter() {
+                handlers.insert(*key, *func);
+            }
+
+            for (key, func) in get_config_ops().iter() {
+                handlers.insert(*key, *func);
+            }
+
+            for (key, func) in get_npc_ops().iter() {
+                handlers.insert(*key, *func);
+            }
+
+            for (key, func) in get_server_ops().iter() {
+                handlers.insert(*key, *func);
+            }
+
             handlers
         })
     }
 
     #[inline]
     pub fn init(
-        script: ScriptFile,
+        script: Arc<ScriptFile>,
         self_entity: Option<EntityType>,
         target_entity: Option<EntityType>,
         args: Option<Vec<ScriptArgument>>
@@ -54,79 +74,18 @@ impl ScriptRunner {
 
         if let Some(self_ent) = self_entity {
             state.self_entity = Some(self_ent);
-            
-            unsafe {
-                match &state.self_entity {
-                    Some(EntityType::Player(player)) => {
-                        state.active_player = Some(player.clone());
-                        state.pointer_add(ScriptPointer::ActivePlayer);
-                    },
-                    Some(EntityType::NPC(npc)) => {
-                        state.active_npc = Some(npc.clone());
-                        state.pointer_add(ScriptPointer::ActiveNpc);
-                    },
-                    Some(EntityType::Loc(loc)) => {
-                        state.active_loc = Some(loc.clone());
-                        state.pointer_add(ScriptPointer::ActiveLoc);
-                    },
-                    Some(EntityType::Obj(obj)) => {
-                        state.active_obj = Some(obj.clone());
-                        state.pointer_add(ScriptPointer::ActiveObj);
-                    },
-                    _ => {}
-                }
-            }
+            state.set_entity(self_ent, false);
         }
 
         if let Some(target) = target_entity {
-            let has_same_type = match (&state.self_entity, &target) {
+            let has_same_type = matches!(
+                (state.self_entity, target),
                 (Some(EntityType::Player(_)), EntityType::Player(_)) |
                 (Some(EntityType::NPC(_)), EntityType::NPC(_)) |
                 (Some(EntityType::Loc(_)), EntityType::Loc(_)) |
-                (Some(EntityType::Obj(_)), EntityType::Obj(_)) => true,
-                _ => false
-            };
-            
-            unsafe {
-                match target {
-                    EntityType::Player(player) => {
-                        if has_same_type {
-                            state.active_player2 = Some(player.clone());
-                            state.pointer_add(ScriptPointer::ActivePlayer2);
-                        } else {
-                            state.active_player = Some(player.clone());
-                            state.pointer_add(ScriptPointer::ActivePlayer);
-                        }
-                    },
-                    EntityType::NPC(npc) => {
-                        if has_same_type {
-                            state.active_npc2 = Some(npc.clone());
-                            state.pointer_add(ScriptPointer::ActiveNpc2);
-                        } else {
-                            state.active_npc = Some(npc.clone());
-                            state.pointer_add(ScriptPointer::ActiveNpc);
-                        }
-                    },
-                    EntityType::Loc(loc) => {
-                        if has_same_type {
-                            state.active_loc2 = Some(loc.clone());
-                            state.pointer_add(ScriptPointer::ActiveLoc2);
-                        } else {
-                            state.active_loc = Some(loc.clone());
-                            state.pointer_add(ScriptPointer::ActiveLoc);
-                        }
-                    },
-                    EntityType::Obj(obj) => {
-                        if has_same_type {
-                            state.active_obj2 = Some(obj.clone());
-                            state.pointer_add(ScriptPointer::ActiveObj2);
-                        } else {
-                            state.active_obj = Some(obj.clone());
-                            state.pointer_add(ScriptPointer::ActiveObj);
-                        }
-                    }
-                }
-            }
+                (Some(EntityType::Obj(_)), EntityType::Obj(_))
+            );
+            state.set_entity(target, has_same_type);
         }
 
         state
@@ -134,6 +93,7 @@ impl ScriptRunner {
 
     #[inline(always)]
     pub fn execute(
+        engine: &mut Engine,
         state: &mut ScriptState,
         reset: bool,
         benchmark: bool,
@@ -174,7 +134,7 @@ impl ScriptRunner {
             {
                 if let Some(handler) = handlers.get(&opcode) {
                     let handler_fn = *handler;
-                    handler_fn(state);
+                    handler_fn(engine, state);
                 } else {
                     error!("Unknown opcode: {}", opcode);
                     state.execution = ScriptState::ABORTED;
@@ -186,7 +146,7 @@ impl ScriptRunner {
             {
                 // SAFETY: All opcodes are validated at compile-time
                 let handler_fn = unsafe { *handlers.get(&opcode).unwrap_unchecked() };
-                handler_fn(state);
+                handler_fn(engine, state);
             }
         }
 
@@ -215,11 +175,11 @@ impl ScriptRunner {
     }
 
     #[inline(always)]
-    pub fn execute_opcode(state: &mut ScriptState, opcode: i32) -> Result<(), String> {
+    pub fn execute_opcode(engine: &mut Engine, state: &mut ScriptState, opcode: i32) -> Result<(), String> {
         let handlers = Self::get_handlers();
 
         if let Some(handler) = handlers.get(&opcode) {
-            handler(state);
+            handler(engine, state);
             Ok(())
         } else {
             Err(format!("Unknown opcode: {}", opcode))
@@ -1,13 +1,12 @@
 use std::sync::Arc;
 use reqwest::header::SERVER;
 use crate::entity::entity_queue_request::ScriptArgument;
-use crate::entity::entity_type::EntityType;
-use crate::entity::loc::Loc;
+use crate::engine::Engine;
+use crate::entity::entity_type::{EntityType, LocHandle, NpcHandle, ObjHandle, PlayerHandle};
 use crate::entity::npc::NPC;
-use crate::entity::obj::Obj;
 use crate::entity::player::Player;
 use crate::script::script_file::ScriptFile;
-use crate::script::script_pointer::ScriptPointer;
+use crate::script::script_pointer::{ScriptPointer, ACTIVE_NPC, ACTIVE_PLAYER};
 use crate::script::server_trigger_types::ServerTriggerTypes;
 
 #[derive(Clone, PartialEq)]
@@ -15,7 +14,8 @@ pub struct GosubStackFrame {
     pub script: Arc<ScriptFile>,
     pub pc: i32,
     pub int_locals: Vec<i32>,
-    pub string_locals: Vec<String>
+    pub string_locals: Vec<String>,
+    pub int_arrays: Vec<Vec<i32>>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -42,18 +42,24 @@ pub struct ScriptState {
     pub ssp: usize,
     pub int_locals: Vec<i32>,
     pub string_locals: Vec<String>,
+    /// Local int arrays of the running script, by array id.
+    pub int_arrays: Vec<Vec<i32>>,
     pub pointers: i32,
     pub self_entity: Option<EntityType>,
-    pub active_player: Option<Player>,
-    pub active_player2: Option<Player>,
-    pub active_npc: Option<NPC>,
-    pub active_npc2: Option<NPC>,
-    pub active_loc: Option<Loc>,
-    pub active_loc2: Option<Loc>,
-    pub active_obj: Option<Obj>,
-    pub active_obj2: Option<Obj>,
+    /// The active player, only valid while they're still in the world.
+    pub active_player: Option<PlayerHandle>,
+    pub active_player2: Option<PlayerHandle>,
+    /// The active npc, only valid while it's still in the world.
+    pub active_npc: Option<NpcHandle>,
+    pub active_npc2: Option<NpcHandle>,
+    pub active_loc: Option<LocHandle>,
+    pub active_loc2: Option<LocHandle>,
+    pub active_obj: Option<ObjHandle>,
+    pub active_obj2: Option<ObjHandle>,
     pub split_pages: Vec<Vec<String>>,
-    pub split_mesanim: i32
+    pub split_mesanim: i32,
+    /// Number entered into a count dialog, or the argument an npc queue was given.
+    pub last_int: i32,
 }
 
 impl ScriptState {
@@ -66,7 +72,10 @@ impl ScriptState {
     pub const NPC_SUSPENDED: i32 = 5;
     pub const WORLD_SUSPENDED: i32 = 6;
 
-    pub fn new(script: ScriptFile, args: Option<Vec<ScriptArgument>>) -> Self {
+    /// Largest array a script can define.
+    pub const MAX_ARRAY_SIZE: i32 = 5000;
+
+    pub fn new(script: Arc<ScriptFile>, args: Option<Vec<ScriptArgument>>) -> Self {
         let mut int_locals = Vec::new();
         let mut string_locals = Vec::new();
 
@@ -82,11 +91,10 @@ impl ScriptState {
             }
         }
 
-        let arc_script = Arc::new(script);
-        let trigger = ServerTriggerTypes::try_from(arc_script.info.lookup_key & 0xFF).unwrap();
+        let trigger = ServerTriggerTypes::try_from(script.info.lookup_key & 0xFF).unwrap();
 
         ScriptState {
-            script: arc_script,
+            script,
             trigger,
             execution: Self::RUNNING,
             execution_history: Vec::new(),
@@ -102,6 +110,7 @@ impl ScriptState {
             ssp: 0,
             int_locals,
             string_locals,
+            int_arrays: Vec::new(),
             pointers: 0,
             self_entity: None,
             active_player: None,
@@ -114,6 +123,7 @@ impl ScriptState {
             active_obj2: None,
             split_pages: Vec::new(),
             split_mesanim: -1,
+            last_int: 0,
         }
     }
 
@@ -155,22 +165,85 @@ impl ScriptState {
             .join(", ")
     }
 
-    pub fn get_active_player(&mut self) -> Result<&mut Player, String> {
-        match if self.get_int_operand() == 0 {
-            &mut self.active_player
-        } else {
-            &mut self.active_player2
-        } {
-            Some(player) => Ok(player),
+    /// The active player picked by the op's operand, looked up in the world as it is now.
+    pub fn get_active_player<'a>(&self, engine: &'a mut Engine) -> Result<&'a mut Player, String> {
+        let pid = self.get_active_player_pid(engine, self.get_int_operand() == 1)?;
+        engine.players.get_mut(pid).ok_or_else(|| format!("Player {} is no longer in the world", pid))
+    }
+
+    /// Pid of the active player, `secondary` picking the second one for ops that pack the flag into their operand.
+    /// Fails if the pointer isn't set or the player has since left the world, even if someone else took their slot.
+    pub fn get_active_player_pid(&self, engine: &Engine, secondary: bool) -> Result<usize, String> {
+        self.pointer_check(&[ACTIVE_PLAYER[secondary as usize]])?;
+        match if secondary { self.active_player2 } else { self.active_player } {
+            Some(handle) if engine.players.get_by_handle(handle).is_some() => Ok(handle.pid),
+            Some(handle) => Err(format!("Player {} is no longer in the world", handle.pid)),
             None => Err("Player not found".to_string()),
         }
     }
 
-    pub fn set_active_player(&mut self, player: Player) {
+    /// The active npc, `secondary` picking the second one, looked up in the world as it is now.
+    pub fn get_active_npc<'a>(&self, engine: &'a mut Engine, secondary: bool) -> Result<&'a mut NPC, String> {
+        let nid = self.get_active_npc_nid(engine, secondary)?;
+        engine.npcs.get_mut(nid).ok_or_else(|| format!("Npc {} is no longer in the world", nid))
+    }
+
+    /// Nid of the active npc, `secondary` picking the second one.
+    /// Fails if the pointer isn't set or the npc has since left the world, even if another took its slot.
+    pub fn get_active_npc_nid(&self, engine: &Engine, secondary: bool) -> Result<usize, String> {
+        self.pointer_check(&[ACTIVE_NPC[secondary as usize]])?;
+        match if secondary { self.active_npc2 } else { self.active_npc } {
+            Some(handle) if engine.npcs.get_by_handle(handle).is_some() => Ok(handle.nid),
+            Some(handle) => Err(format!("Npc {} is no longer in the world", handle.nid)),
+            None => Err("Npc not found".to_string()),
+        }
+    }
+
+    pub fn set_active_player(&mut self, player: PlayerHandle) {
         if self.get_int_operand() == 0 {
             self.active_player = Some(player);
+            self.pointer_add(ScriptPointer::ActivePlayer);
         } else {
             self.active_player2 = Some(player);
+            self.pointer_add(ScriptPointer::ActivePlayer2);
+        }
+    }
+
+    /// Make an entity active, as the second of its kind if `secondary`.
+    pub fn set_entity(&mut self, entity: EntityType, secondary: bool) {
+        match (entity, secondary) {
+            (EntityType::Player(player), false) => {
+                self.active_player = Some(player);
+                self.pointer_add(ScriptPointer::ActivePlayer);
+            },
+            (EntityType::Player(player), true) => {
+                self.active_player2 = Some(player);
+                self.pointer_add(ScriptPointer::ActivePlayer2);
+            },
+            (EntityType::NPC(npc), false) => {
+                self.active_npc = Some(npc);
+                self.pointer_add(ScriptPointer::ActiveNpc);
+            },
+            (EntityType::NPC(npc), true) => {
+                self.active_npc2 = Some(npc);
+                self.pointer_add(ScriptPointer::ActiveNpc2);
+            },
+            (EntityType::Loc(loc), false) => {
+                self.active_loc = Some(loc);
+                self.pointer_add(ScriptPointer::ActiveLoc);
+            },
+            (EntityType::Loc(loc), true) => {
+                self.active_loc2 = Some(loc);
+                self.pointer_add(ScriptPointer::ActiveLoc2);
+            },
+            (EntityType::Obj(obj), false) => {
+                self.active_obj = Some(obj);
+                self.pointer_add(ScriptPointer::ActiveObj);
+            },
+            (EntityType::Obj(obj), true) => {
+                self.active_obj2 = Some(obj);
+                self.pointer_add(ScriptPointer::ActiveObj2);
+            },
         }
     }
 
@@ -187,18 +260,13 @@ impl ScriptState {
         self.int_stack.get(self.isp).copied().unwrap_or(0)
     }
 
+    /// Pop `amount` ints, returned in the order they were pushed.
     #[inline(always)]
     pub fn pop_ints(&mut self, amount: usize) -> Vec<i32> {
-        let mut result = Vec::with_capacity(amount);
-        let start_idx = self.isp.saturating_sub(1);
-        for i in (start_idx.saturating_sub(amount-1)..=start_idx).rev() {
-            if i < self.int_stack.len() {
-                result.push(self.int_stack[i]);
-            }
+        let mut result = vec![0; amount];
+        for value in result.iter_mut().rev() {
+            *value = self.pop_int();
         }
-
-        self.isp = self.isp.saturating_sub(amount);
-
         result
     }
 
@@ -231,8 +299,28 @@ impl ScriptState {
         }
     }
 
+    /// Pop `amount` strings, returned in the order they were pushed.
     pub fn pop_strings(&mut self, amount: usize) -> Vec<String> {
-        (0..amount).rev().map(|_| self.pop_string()).collect()
+        let mut result = vec![String::new(); amount];
+        for value in result.iter_mut().rev() {
+            *value = self.pop_string();
+        }
+        result
+    }
+
+    /// Pop the arguments for a queued script, described by a string of their types pushed last.
+    pub fn pop_script_args(&mut self) -> Vec<ScriptArgument> {
+        let types = self.pop_string();
+        let mut args = Vec::with_capacity(types.len());
+        for type_ in types.chars().rev() {
+            args.push(if type_ == 's' {
+                ScriptArgument::String(self.pop_string())
+            } else {
+                ScriptArgument::Number(self.pop_int())
+            });
+        }
+        args.reverse();
+        args
     }
 
     #[inline(always)]
@@ -257,15 +345,17 @@ impl ScriptState {
         self.script = frame.script.clone();
         self.int_locals = frame.int_locals.clone();
         self.string_locals = frame.string_locals.clone();
+        self.int_arrays = std::mem::take(&mut self.frames[self.fp].int_arrays);
     }
 
-    pub fn gosub_frame(&mut self, proc: ScriptFile) {
+    pub fn gosub_frame(&mut self, proc: Arc<ScriptFile>) {
         if self.fp >= self.frames.len() {
             self.frames.push(GosubStackFrame {
                 script: Arc::clone(&self.script),
                 pc: 0,
                 int_locals: Vec::new(),
                 string_locals: Vec::new(),
+                int_arrays: Vec::new(),
             });
         }
 
@@ -282,13 +372,15 @@ impl ScriptState {
             pc: self.pc,
             int_locals: new_int_locals,
             string_locals: new_string_locals,
+            // The proc starts without arrays, the caller's come back on return.
+            int_arrays: std::mem::take(&mut self.int_arrays),
         };
 
         self.fp += 1;
         self.setup_new_script(proc);
     }
 
-    pub fn goto_frame(&mut self, label: ScriptFile) {
+    pub fn goto_frame(&mut self, label: Arc<ScriptFile>) {
         if self.debug_fp >= self.debug_frames.len() {
             self.debug_frames.push(JumpStackFrame {
                 script: Arc::clone(&self.script),
@@ -307,13 +399,11 @@ impl ScriptState {
         self.setup_new_script(label);
     }
 
-    pub fn setup_new_script(&mut self, script: ScriptFile) {
-        let arc_script = Arc::new(script);
-
-        let int_local_count = arc_script.int_local_count as usize;
-        let string_local_count = arc_script.string_local_count as usize;
-        let int_arg_count = arc_script.int_arg_count as usize;
-        let string_arg_count = arc_script.string_arg_count as usize;
+    pub fn setup_new_script(&mut self, script: Arc<ScriptFile>) {
+        let int_local_count = script.int_local_count as usize;
+        let string_local_count = script.string_local_count as usize;
+        let int_arg_count = script.int_arg_count as usize;
+        let string_arg_count = script.string_arg_count as usize;
 
         let mut int_locals = vec![0; int_local_count];
         let mut string_locals = vec![String::new(); string_local_count];
@@ -327,9 +417,10 @@ impl ScriptState {
         }
 
         self.pc = -1;
-        self.script = arc_script;
+        self.script = script;
         self.int_locals = int_locals;
         self.string_locals = string_locals;
+        self.int_arrays.clear();
     }
     
     pub fn reset(&mut self) {
@@ -342,6 +433,7 @@ impl ScriptState {
         self.ssp = 0;
         self.int_locals.clear();
         self.string_locals.clear();
+        self.int_arrays.clear();
         self.pointers = 0;
     }
 }
\ No newline at end of file
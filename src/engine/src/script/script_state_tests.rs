@@ -0,0 +1,81 @@
+use std::sync::Arc;
+use crate::engine::Engine;
+use crate::entity::block_walk::BlockWalk;
+use crate::entity::entity_lifecycle::EntityLifeCycle;
+use crate::entity::move_restrict::MoveRestrict;
+use crate::entity::npc::NPC;
+use crate::entity::player::Player;
+use crate::grid::coord_grid::CoordGrid;
+use crate::script::script_file::ScriptFile;
+use crate::script::script_runner::ScriptRunner;
+use crate::script::script_state::ScriptState;
+
+const SLOT: usize = 1;
+
+fn script() -> Arc<ScriptFile> {
+    let mut script = ScriptFile::new(0);
+    script.info.lookup_key = 0;
+    Arc::new(script)
+}
+
+fn player() -> Player {
+    Player::new_dummy(CoordGrid::from(3222, 0, 3218), 0, SLOT)
+}
+
+fn npc() -> NPC {
+    NPC::new(CoordGrid::from(3222, 0, 3218), 1, 1, EntityLifeCycle::FOREVER, SLOT as i32, 0, MoveRestrict::Normal, BlockWalk::Npc)
+}
+
+/// A script started against whoever is in the player slot right now.
+fn player_script(engine: &Engine) -> ScriptState {
+    ScriptRunner::init(script(), Some(engine.players.get(SLOT).unwrap().as_entity_type()), None, None)
+}
+
+fn npc_script(engine: &Engine) -> ScriptState {
+    ScriptRunner::init(script(), Some(engine.npcs.get(SLOT).unwrap().as_entity_type()), None, None)
+}
+
+#[test]
+fn test_active_player_resolves() {
+    let mut engine = Engine::new();
+    engine.players.set(SLOT, player()).unwrap();
+
+    let state = player_script(&engine);
+    assert_eq!(state.get_active_player_pid(&engine, false), Ok(SLOT));
+}
+
+#[test]
+fn test_active_player_gone_after_logout() {
+    let mut engine = Engine::new();
+    engine.players.set(SLOT, player()).unwrap();
+    let state = player_script(&engine);
+
+    engine.players.remove(SLOT);
+    assert!(state.get_active_player_pid(&engine, false).is_err());
+}
+
+#[test]
+fn test_reused_player_slot_is_not_active_player() {
+    let mut engine = Engine::new();
+    engine.players.set(SLOT, player()).unwrap();
+    let state = player_script(&engine);
+
+    engine.players.remove(SLOT);
+    engine.players.set(SLOT, player()).unwrap();
+    assert!(state.get_active_player_pid(&engine, false).is_err());
+    assert_eq!(player_script(&engine).get_active_player_pid(&engine, false), Ok(SLOT));
+}
+
+#[test]
+fn test_reused_npc_slot_is_not_active_npc() {
+    let mut engine = Engine::new();
+    engine.npcs.set(SLOT, npc()).unwrap();
+    let state = npc_script(&engine);
+    assert_eq!(state.get_active_npc_nid(&engine, false), Ok(SLOT));
+
+    engine.npcs.remove(SLOT);
+    engine.npcs.set(SLOT, npc()).unwrap();
+    assert!(state.get_active_npc_nid(&engine, false).is_err());
+    assert!(state.get_active_npc(&mut engine, false).is_err());
+    assert_eq!(npc_script(&engine).get_active_npc_nid(&engine, false), Ok(SLOT));
+}
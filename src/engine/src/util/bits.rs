@@ -6,10 +6,7 @@ lazy_static! {
 
 /// Returns the number of `1` bits in `value`.
 pub fn bitcount(value: i32) -> i32 {
-    let mut n = value;
-    n = n - ((n >> 1) & 0x55555555);
-    n = (n & 0x33333333) + ((n >> 2) & 0x33333333);
-    ((n + (n >> 4) & 0x0f0f0f0f).wrapping_mul(0x01010101)) >> 24
+    value.count_ones() as i32
 }
 
 /// Sets a range of bits from `start_bit` to `end_bit` (inclusive) to 1.
@@ -27,11 +24,10 @@ pub fn clear_bit_range(value: i32, start_bit: i32, end_bit: i32) -> i32 {
 /// Initialize the array of bit masks.
 fn init_mask_array() -> [i32; 33] {
     let mut data = [0; 33];
-    let mut incrementor = 2;
     
-    for i in 1..33 {
-        data[i] = (incrementor - 1);
-        incrementor += incrementor;
+    // Built in 64 bits so the top widths don't overflow, MASK[32] ends up as every bit set.
+    for (i, mask) in data.iter_mut().enumerate().skip(1) {
+        *mask = ((1i64 << i) - 1) as i32;
     }
     
     data
@@ -1,3 +1,10 @@
+use std::error::Error;
+use std::path::Path;
+use log::error;
+use config::server_config::ServerConfig;
+use rs2cache::Cache;
+use rs2cache::js5_compression::Js5Compression;
+use rs2cache::js5_index::Js5Index;
 use crate::io::packet::Packet;
 
 pub trait ConfigType {
@@ -20,4 +27,51 @@ pub trait ConfigType {
             self.decode(opcode, packet);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Read the files of a config archive, all of them or just one group's, passing each to `visit` with its group and file id.
+pub fn read_config_files(archive: u32, group: Option<u32>, mut visit: impl FnMut(u32, u16, Vec<u8>)) -> Result<(), Box<dyn Error>> {
+    let cache_path = ServerConfig::get().paths.cache.to_string_lossy();
+    let mut cache = Cache::open(&cache_path)?;
+
+    let js5_index = cache.store.read(255, archive)
+        .and_then(|data| Js5Compression::uncompress(data, None))
+        .and_then(Js5Index::read)?;
+
+    for (group_id, index_group) in js5_index.groups.iter() {
+        if group.is_some_and(|group| group != *group_id) {
+            continue;
+        }
+
+        for (file_id, _) in index_group.files.iter() {
+            match cache.read(archive as u8, *group_id, *file_id as u16, None) {
+                Ok(data) => visit(*group_id, *file_id as u16, data),
+                Err(e) => error!("Failed to read config {}/{}/{}: {}", archive, group_id, file_id, e),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Read a server-only config pack, `pack/server/<name>.dat`: a count, then each config's opcodes back to back.
+///
+/// A pack that hasn't been built yet reads as empty.
+pub fn read_server_configs(name: &str, visit: impl FnMut(u32, &mut Packet)) -> Result<usize, Box<dyn Error>> {
+    read_server_configs_in(&ServerConfig::get().paths.data.join("pack/server"), name, visit)
+}
+
+/// [read_server_configs] from a pack directory other than the configured one.
+pub fn read_server_configs_in(dir: &Path, name: &str, mut visit: impl FnMut(u32, &mut Packet)) -> Result<usize, Box<dyn Error>> {
+    let path = dir.join(format!("{}.dat", name));
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    let mut dat = Packet::io(path.to_string_lossy().into_owned())?;
+    let count = dat.g2() as u32;
+    for id in 0..count {
+        visit(id, &mut dat);
+    }
+    Ok(count as usize)
+}
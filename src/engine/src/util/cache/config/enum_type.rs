@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use log::{debug, error};
+use constants::js5_archive::js5_archive::CONFIG_ENUM;
+use crate::io::packet::Packet;
+use crate::util::cache::config::config_type::{read_config_files, ConfigType};
+use crate::util::cache::param_helper::ParamValue;
+
+static ENUM_TYPES: OnceLock<Vec<EnumType>> = OnceLock::new();
+
+/// A lookup table from keys of one type to values of another, both given as RuneScript type chars.
+#[derive(Debug)]
+pub struct EnumType {
+    pub id: u32,
+    debugname: Option<String>,
+    pub inputtype: u8,
+    pub outputtype: u8,
+    pub default_int: i32,
+    pub default_string: String,
+    pub values: HashMap<i32, ParamValue>,
+}
+
+impl EnumType {
+    pub fn new(id: u32) -> Self {
+        EnumType {
+            id,
+            debugname: None,
+            inputtype: 0,
+            outputtype: 0,
+            default_int: 0,
+            default_string: "null".to_string(),
+            values: HashMap::new(),
+        }
+    }
+
+    /// Decode every enum in the config archive, returns how many were loaded.
+    pub fn load() -> usize {
+        let mut enums: Vec<EnumType> = Vec::new();
+        let result = read_config_files(CONFIG_ENUM, None, |group_id, file_id, data| {
+            // Enums are packed 256 to a group.
+            let id = (group_id << 8) | file_id as u32;
+            let mut enum_type = EnumType::new(id);
+            enum_type.decode_type(&mut Packet::from(data), &mut Vec::new());
+
+            if enums.len() <= id as usize {
+                enums.resize_with(id as usize + 1, || EnumType::new(u32::MAX));
+            }
+            enums[id as usize] = enum_type;
+        });
+
+        if let Err(e) = result {
+            error!("Failed to read enum types: {}", e);
+            return 0;
+        }
+
+        let count = enums.len();
+        if ENUM_TYPES.set(enums).is_err() {
+            error!("Enum types were already loaded");
+        }
+        debug!("Loaded {} enum types", count);
+        count
+    }
+
+    #[inline]
+    pub fn get(id: i32) -> Option<&'static EnumType> {
+        ENUM_TYPES.get()?.get(usize::try_from(id).ok()?).filter(|enum_type| enum_type.id != u32::MAX)
+    }
+
+    pub fn get_int(&self, key: i32) -> i32 {
+        match self.values.get(&key) {
+            Some(ParamValue::Integer(value)) => *value,
+            _ => self.default_int,
+        }
+    }
+
+    pub fn get_string(&self, key: i32) -> &str {
+        match self.values.get(&key) {
+            Some(ParamValue::String(value)) => value,
+            _ => &self.default_string,
+        }
+    }
+}
+
+impl ConfigType for EnumType {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn debugname(&self) -> Option<&String> {
+        self.debugname.as_ref()
+    }
+
+    fn set_debugname(&mut self, debugname: String) {
+        self.debugname = Some(debugname);
+    }
+
+    fn decode(&mut self, opcode: u8, packet: &mut Packet) {
+        match opcode {
+            1 => self.inputtype = packet.g1(),
+            2 => self.outputtype = packet.g1(),
+            3 => self.default_string = packet.gjstr(),
+            4 => self.default_int = packet.g4(),
+            5 => {
+                let count = packet.g2();
+                self.values.reserve(count as usize);
+                for _ in 0..count {
+                    let key = packet.g4();
+                    self.values.insert(key, ParamValue::String(packet.gjstr()));
+                }
+            },
+            6 => {
+                let count = packet.g2();
+                self.values.reserve(count as usize);
+                for _ in 0..count {
+                    let key = packet.g4();
+                    self.values.insert(key, ParamValue::Integer(packet.g4()));
+                }
+            },
+            250 => self.set_debugname(packet.gjstr()),
+            _ => error!("Unrecognized enum config code: {}", opcode),
+        }
+    }
+}
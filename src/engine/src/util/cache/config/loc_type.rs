@@ -1,11 +1,18 @@
 use std::fs::File;
+use std::sync::OnceLock;
 use log::{debug, error};
-use crate::entity::loc::Loc;
+use config::server_config::ServerConfig;
+use rs2cache::Cache;
+use rs2cache::js5_compression::Js5Compression;
+use rs2cache::js5_index::Js5Index;
+use constants::js5_archive::js5_archive::CONFIG_LOC;
 use crate::io::packet::Packet;
 use crate::util::cache::config::config_type::ConfigType;
 use crate::util::cache::param_helper::{decode_params, ParamValue, Params};
 use std::io::Write;
 
+static LOC_TYPES: OnceLock<Vec<LocType>> = OnceLock::new();
+
 #[derive(Debug)]
 pub struct LocType {
     pub id: u32,
@@ -16,19 +23,19 @@ pub struct LocType {
     pub ldshapes: Vec<u32>,
     pub name: Option<String>,
     description: Option<String>,
-    width: u32,
-    length: u32,
+    pub width: u32,
+    pub length: u32,
     recol_s: Vec<u16>,
     recol_d: Vec<u16>,
     retex_s: Vec<u16>,
     retex_d: Vec<u16>,
     recol_d_palette: Vec<i8>,
-    blockwalk: u32,
-    blockrange: bool,
+    pub blockwalk: u32,
+    pub blockrange: bool,
     blocksides: u32,
     members: bool,
     op: Vec<String>,
-    active: i8,
+    pub active: i8,
     sharelight: bool,
     occlude: bool,
     anim: i32,
@@ -61,7 +68,7 @@ pub struct LocType {
     hardshadow: bool,
     mapsceneiconrotate: bool,
     forcedecor: bool,
-    breakroutefinding: bool,
+    pub breakroutefinding: bool,
     animated: bool,
     raiseobject: i32,
     mapsceneiconrotationoffset: u8,
@@ -141,6 +148,74 @@ impl LocType {
             params: Params::default(),
         }
     }
+
+    /// Decode every loc in the config archive, returns how many were loaded.
+    pub fn load() -> usize {
+        let cache_path = ServerConfig::get().paths.cache.to_string_lossy();
+
+        let mut cache = match Cache::open(&cache_path) {
+            Ok(cache) => cache,
+            Err(e) => {
+                error!("Failed to open cache for loc types: {}", e);
+                return 0;
+            }
+        };
+
+        let js5_index = match cache.store.read(255, CONFIG_LOC)
+            .and_then(|data| Js5Compression::uncompress(data, None))
+            .and_then(Js5Index::read) {
+            Ok(index) => index,
+            Err(e) => {
+                error!("Failed to read loc type index: {}", e);
+                return 0;
+            }
+        };
+
+        let mut locs: Vec<LocType> = Vec::new();
+        for (group_id, group) in js5_index.groups.iter() {
+            for (file_id, _) in group.files.iter() {
+                // Locs are packed 256 to a group.
+                let id = (*group_id << 8) | *file_id as u32;
+                let data = match cache.read(CONFIG_LOC as u8, *group_id, *file_id as u16, None) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Failed to read loc {}: {}", id, e);
+                        continue;
+                    }
+                };
+
+                let mut loc = LocType::new(id);
+                loc.decode_type(&mut Packet::from(data), &mut Vec::new());
+
+                if locs.len() <= id as usize {
+                    locs.resize_with(id as usize + 1, || LocType::new(u32::MAX));
+                }
+                locs[id as usize] = loc;
+            }
+        }
+
+        let count = locs.len();
+        if LOC_TYPES.set(locs).is_err() {
+            error!("Loc types were already loaded");
+        }
+        debug!("Loaded {} loc types", count);
+        count
+    }
+
+    #[inline]
+    pub fn get(id: i32) -> Option<&'static LocType> {
+        LOC_TYPES.get()?.get(usize::try_from(id).ok()?).filter(|loc| loc.id != u32::MAX)
+    }
+
+    /// Whether the loc can be interacted with, when the config leaves it to be worked out from the models and ops.
+    pub fn is_active(&self) -> bool {
+        if self.active != -1 {
+            return self.active == 1;
+        }
+
+        let centrepiece = !self.models.is_empty() && (self.shapes.is_empty() || self.shapes[0] == 10);
+        centrepiece || self.op.iter().any(|op| !op.is_empty())
+    }
 }
 
 impl ConfigType for LocType {
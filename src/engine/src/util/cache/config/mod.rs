@@ -1,2 +1,18 @@
 pub mod config_type;
+pub mod loc_type;
 pub mod obj_type;
+pub mod var_bit_type;
+pub mod varp_type;
+pub mod var_npc_type;
+pub mod var_shared_type;
+pub mod var_type;
+pub mod enum_type;
+pub mod param_type;
+pub mod struct_type;
+
+#[cfg(test)]
+mod var_bit_type_tests;
+#[cfg(test)]
+mod varp_type_tests;
+#[cfg(test)]
+mod var_type_tests;
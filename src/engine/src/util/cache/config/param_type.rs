@@ -0,0 +1,94 @@
+use std::sync::OnceLock;
+use log::{debug, error};
+use constants::js5_archive::js5_archive::CONFIG;
+use constants::js5_config_group::js5_config_group::PARAMTYPE;
+use crate::io::packet::Packet;
+use crate::util::cache::config::config_type::{read_config_files, ConfigType};
+
+static PARAM_TYPES: OnceLock<Vec<ParamType>> = OnceLock::new();
+
+/// The declared type and default of a param, for when a config doesn't set it.
+#[derive(Debug)]
+pub struct ParamType {
+    pub id: u32,
+    debugname: Option<String>,
+    pub type_: u8,
+    pub default_int: i32,
+    pub default_string: String,
+    pub autodisable: bool,
+}
+
+impl ParamType {
+    pub fn new(id: u32) -> Self {
+        ParamType {
+            id,
+            debugname: None,
+            type_: 0,
+            default_int: 0,
+            default_string: "null".to_string(),
+            autodisable: true,
+        }
+    }
+
+    /// Decode every param in the config archive, returns how many were loaded.
+    pub fn load() -> usize {
+        let mut params: Vec<ParamType> = Vec::new();
+        let result = read_config_files(CONFIG, Some(PARAMTYPE), |_, file_id, data| {
+            let id = file_id as u32;
+            let mut param = ParamType::new(id);
+            param.decode_type(&mut Packet::from(data), &mut Vec::new());
+
+            if params.len() <= id as usize {
+                params.resize_with(id as usize + 1, || ParamType::new(u32::MAX));
+            }
+            params[id as usize] = param;
+        });
+
+        if let Err(e) = result {
+            error!("Failed to read param types: {}", e);
+            return 0;
+        }
+
+        let count = params.len();
+        if PARAM_TYPES.set(params).is_err() {
+            error!("Param types were already loaded");
+        }
+        debug!("Loaded {} param types", count);
+        count
+    }
+
+    #[inline]
+    pub fn get(id: i32) -> Option<&'static ParamType> {
+        PARAM_TYPES.get()?.get(usize::try_from(id).ok()?).filter(|param| param.id != u32::MAX)
+    }
+
+    #[inline]
+    pub fn is_string(&self) -> bool {
+        self.type_ == b's'
+    }
+}
+
+impl ConfigType for ParamType {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn debugname(&self) -> Option<&String> {
+        self.debugname.as_ref()
+    }
+
+    fn set_debugname(&mut self, debugname: String) {
+        self.debugname = Some(debugname);
+    }
+
+    fn decode(&mut self, opcode: u8, packet: &mut Packet) {
+        match opcode {
+            1 => self.type_ = packet.g1(),
+            2 => self.default_int = packet.g4(),
+            4 => self.autodisable = false,
+            5 => self.default_string = packet.gjstr(),
+            250 => self.set_debugname(packet.gjstr()),
+            _ => error!("Unrecognized param config code: {}", opcode),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::OnceLock;
+use log::{debug, error};
+use constants::js5_archive::js5_archive::CONFIG;
+use constants::js5_config_group::js5_config_group::STRUCTTYPE;
+use crate::io::packet::Packet;
+use crate::util::cache::config::config_type::{read_config_files, ConfigType};
+use crate::util::cache::param_helper::{decode_params, ParamValue, Params};
+
+static STRUCT_TYPES: OnceLock<Vec<StructType>> = OnceLock::new();
+
+/// A bag of params with no other properties, for scripts to look data up in.
+#[derive(Debug)]
+pub struct StructType {
+    pub id: u32,
+    debugname: Option<String>,
+    params: Params,
+}
+
+impl StructType {
+    pub fn new(id: u32) -> Self {
+        StructType {
+            id,
+            debugname: None,
+            params: Params::new(),
+        }
+    }
+
+    /// Decode every struct in the config archive, returns how many were loaded.
+    pub fn load() -> usize {
+        let mut structs: Vec<StructType> = Vec::new();
+        let result = read_config_files(CONFIG, Some(STRUCTTYPE), |_, file_id, data| {
+            let id = file_id as u32;
+            let mut struct_type = StructType::new(id);
+            struct_type.decode_type(&mut Packet::from(data), &mut Vec::new());
+
+            if structs.len() <= id as usize {
+                structs.resize_with(id as usize + 1, || StructType::new(u32::MAX));
+            }
+            structs[id as usize] = struct_type;
+        });
+
+        if let Err(e) = result {
+            error!("Failed to read struct types: {}", e);
+            return 0;
+        }
+
+        let count = structs.len();
+        if STRUCT_TYPES.set(structs).is_err() {
+            error!("Struct types were already loaded");
+        }
+        debug!("Loaded {} struct types", count);
+        count
+    }
+
+    #[inline]
+    pub fn get(id: i32) -> Option<&'static StructType> {
+        STRUCT_TYPES.get()?.get(usize::try_from(id).ok()?).filter(|struct_type| struct_type.id != u32::MAX)
+    }
+
+    #[inline]
+    pub fn param(&self, id: i32) -> Option<&ParamValue> {
+        self.params.get(&id)
+    }
+}
+
+impl ConfigType for StructType {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn debugname(&self) -> Option<&String> {
+        self.debugname.as_ref()
+    }
+
+    fn set_debugname(&mut self, debugname: String) {
+        self.debugname = Some(debugname);
+    }
+
+    fn decode(&mut self, opcode: u8, packet: &mut Packet) {
+        match opcode {
+            249 => self.params = decode_params(packet),
+            250 => self.set_debugname(packet.gjstr()),
+            _ => error!("Unrecognized struct config code: {}", opcode),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::OnceLock;
+use log::{debug, error};
+use constants::js5_archive::js5_archive::CONFIG_VAR_BIT;
+use crate::io::packet::Packet;
+use crate::util::bits::MASK;
+use crate::util::cache::config::config_type::{read_config_files, ConfigType};
+
+static VAR_BIT_TYPES: OnceLock<Vec<VarBitType>> = OnceLock::new();
+
+/// A range of bits packed inside a varp.
+#[derive(Debug)]
+pub struct VarBitType {
+    pub id: u32,
+    debugname: Option<String>,
+    pub basevar: u16,
+    pub startbit: u8,
+    pub endbit: u8,
+}
+
+impl VarBitType {
+    pub fn new(id: u32) -> Self {
+        VarBitType {
+            id,
+            debugname: None,
+            basevar: 0,
+            startbit: 0,
+            endbit: 0,
+        }
+    }
+
+    /// Decode every varbit in the config archive, returns how many were loaded.
+    pub fn load() -> usize {
+        let mut varbits: Vec<VarBitType> = Vec::new();
+        let result = read_config_files(CONFIG_VAR_BIT, None, |group_id, file_id, data| {
+            // Varbits are packed 1024 to a group.
+            let id = (group_id << 10) | file_id as u32;
+            let mut varbit = VarBitType::new(id);
+            varbit.decode_type(&mut Packet::from(data), &mut Vec::new());
+
+            if varbits.len() <= id as usize {
+                varbits.resize_with(id as usize + 1, || VarBitType::new(u32::MAX));
+            }
+            varbits[id as usize] = varbit;
+        });
+
+        if let Err(e) = result {
+            error!("Failed to read varbit types: {}", e);
+            return 0;
+        }
+
+        let count = varbits.len();
+        if VAR_BIT_TYPES.set(varbits).is_err() {
+            error!("Varbit types were already loaded");
+        }
+        debug!("Loaded {} varbit types", count);
+        count
+    }
+
+    #[inline]
+    pub fn get(id: i32) -> Option<&'static VarBitType> {
+        VAR_BIT_TYPES.get()?.get(usize::try_from(id).ok()?).filter(|varbit| varbit.id != u32::MAX)
+    }
+
+    /// Mask for the width of the range, unshifted.
+    #[inline]
+    pub fn mask(&self) -> i32 {
+        MASK[(self.endbit.saturating_sub(self.startbit) as usize + 1).min(32)]
+    }
+}
+
+impl ConfigType for VarBitType {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn debugname(&self) -> Option<&String> {
+        self.debugname.as_ref()
+    }
+
+    fn set_debugname(&mut self, debugname: String) {
+        self.debugname = Some(debugname);
+    }
+
+    fn decode(&mut self, opcode: u8, packet: &mut Packet) {
+        match opcode {
+            1 => {
+                self.basevar = packet.g2();
+                self.startbit = packet.g1();
+                self.endbit = packet.g1();
+            },
+            250 => self.set_debugname(packet.gjstr()),
+            _ => error!("Unrecognized varbit config code: {}", opcode),
+        }
+    }
+}
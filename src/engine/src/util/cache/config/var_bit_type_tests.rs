@@ -0,0 +1,25 @@
+use crate::io::packet::Packet;
+use crate::util::cache::config::config_type::ConfigType;
+use crate::util::cache::config::var_bit_type::VarBitType;
+
+fn decode(data: Vec<u8>) -> VarBitType {
+    let mut varbit = VarBitType::new(1000);
+    varbit.decode_type(&mut Packet::from(data), &mut Vec::new());
+    varbit
+}
+
+#[test]
+fn test_decode() {
+    let varbit = decode(vec![1, 0x01, 0x19, 4, 7, 0]);
+    assert_eq!(varbit.id, 1000);
+    assert_eq!(varbit.basevar, 0x0119);
+    assert_eq!(varbit.startbit, 4);
+    assert_eq!(varbit.endbit, 7);
+    assert_eq!(varbit.mask(), 0xf);
+}
+
+#[test]
+fn test_mask() {
+    assert_eq!(decode(vec![1, 0, 0, 3, 3, 0]).mask(), 0x1);
+    assert_eq!(decode(vec![1, 0, 0, 0, 31, 0]).mask(), -1);
+}
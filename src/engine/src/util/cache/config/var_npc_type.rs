@@ -0,0 +1,24 @@
+use std::sync::OnceLock;
+use crate::util::cache::config::var_type::VarType;
+
+static VAR_NPC_TYPES: OnceLock<Vec<VarType>> = OnceLock::new();
+
+/// A variable every npc has its own copy of, cleared when the npc respawns.
+pub struct VarNpcType;
+
+impl VarNpcType {
+    /// Decode every varn in the server pack, returns how many were loaded.
+    pub fn load() -> usize {
+        VarType::load("varn", &VAR_NPC_TYPES)
+    }
+
+    #[inline]
+    pub fn get(id: i32) -> Option<&'static VarType> {
+        VarType::get(&VAR_NPC_TYPES, id)
+    }
+
+    #[inline]
+    pub fn count() -> usize {
+        VarType::count(&VAR_NPC_TYPES)
+    }
+}
@@ -0,0 +1,24 @@
+use std::sync::OnceLock;
+use crate::util::cache::config::var_type::VarType;
+
+static VAR_SHARED_TYPES: OnceLock<Vec<VarType>> = OnceLock::new();
+
+/// A variable shared by the whole world, like a minigame counter.
+pub struct VarSharedType;
+
+impl VarSharedType {
+    /// Decode every vars in the server pack, returns how many were loaded.
+    pub fn load() -> usize {
+        VarType::load("vars", &VAR_SHARED_TYPES)
+    }
+
+    #[inline]
+    pub fn get(id: i32) -> Option<&'static VarType> {
+        VarType::get(&VAR_SHARED_TYPES, id)
+    }
+
+    #[inline]
+    pub fn count() -> usize {
+        VarType::count(&VAR_SHARED_TYPES)
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::OnceLock;
+use log::{debug, error};
+use crate::io::packet::Packet;
+use crate::util::cache::config::config_type::{read_server_configs, ConfigType};
+
+/// A server-side variable definition, varns and vars are packed the same way and only differ in who owns the value.
+#[derive(Debug)]
+pub struct VarType {
+    pub id: u32,
+    debugname: Option<String>,
+    pub type_: u8,
+}
+
+impl VarType {
+    pub fn new(id: u32) -> Self {
+        VarType {
+            id,
+            debugname: None,
+            type_: 0,
+        }
+    }
+
+    /// Decode every var in the server pack `name` into `types`, returns how many were loaded.
+    pub(crate) fn load(name: &str, types: &OnceLock<Vec<VarType>>) -> usize {
+        let mut loaded: Vec<VarType> = Vec::new();
+        let result = read_server_configs(name, |id, packet| {
+            let mut var = VarType::new(id);
+            var.decode_type(packet, &mut Vec::new());
+            loaded.push(var);
+        });
+
+        if let Err(e) = result {
+            error!("Failed to read {} types: {}", name, e);
+            return 0;
+        }
+
+        let count = loaded.len();
+        if types.set(loaded).is_err() {
+            error!("{} types were already loaded", name);
+        }
+        debug!("Loaded {} {} types", count, name);
+        count
+    }
+
+    #[inline]
+    pub(crate) fn get(types: &'static OnceLock<Vec<VarType>>, id: i32) -> Option<&'static VarType> {
+        types.get()?.get(usize::try_from(id).ok()?)
+    }
+
+    #[inline]
+    pub(crate) fn count(types: &OnceLock<Vec<VarType>>) -> usize {
+        types.get().map_or(0, Vec::len)
+    }
+}
+
+impl ConfigType for VarType {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn debugname(&self) -> Option<&String> {
+        self.debugname.as_ref()
+    }
+
+    fn set_debugname(&mut self, debugname: String) {
+        self.debugname = Some(debugname);
+    }
+
+    fn decode(&mut self, opcode: u8, packet: &mut Packet) {
+        match opcode {
+            1 => self.type_ = packet.g1(),
+            250 => self.set_debugname(packet.gjstr()),
+            _ => error!("Unrecognized var config code: {}", opcode),
+        }
+    }
+}
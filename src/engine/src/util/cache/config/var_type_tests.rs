@@ -0,0 +1,24 @@
+use crate::io::packet::Packet;
+use crate::util::cache::config::config_type::ConfigType;
+use crate::util::cache::config::var_type::VarType;
+
+fn decode(data: Vec<u8>) -> VarType {
+    let mut var = VarType::new(12);
+    var.decode_type(&mut Packet::from(data), &mut Vec::new());
+    var
+}
+
+#[test]
+fn test_decode() {
+    let var = decode(vec![1, 105, 250, b'c', b'o', b'u', b'n', b't', 0, 0]);
+    assert_eq!(var.id, 12);
+    assert_eq!(var.type_, 105);
+    assert_eq!(var.debugname().map(String::as_str), Some("count"));
+}
+
+#[test]
+fn test_decode_defaults() {
+    let var = decode(vec![0]);
+    assert_eq!(var.type_, 0);
+    assert_eq!(var.debugname(), None);
+}
@@ -0,0 +1,126 @@
+use std::path::Path;
+use std::sync::OnceLock;
+use log::{debug, error};
+use config::server_config::ServerConfig;
+use constants::js5_archive::js5_archive::CONFIG;
+use constants::js5_config_group::js5_config_group::VAR_PLAYER;
+use crate::io::packet::Packet;
+use crate::util::cache::config::config_type::{read_config_files, read_server_configs_in, ConfigType};
+
+static VARP_TYPES: OnceLock<Vec<VarpType>> = OnceLock::new();
+
+#[derive(Debug)]
+pub struct VarpType {
+    pub id: u32,
+    debugname: Option<String>,
+    pub clientcode: u16,
+    pub scope: u8,
+    pub type_: u8,
+    /// Writes need protected access to the player.
+    pub protect: bool,
+    /// Changes are sent to the client.
+    pub transmit: bool,
+}
+
+impl VarpType {
+    /// Reset on logout.
+    pub const SCOPE_TEMP: u8 = 0;
+    /// Kept in the player save.
+    pub const SCOPE_PERM: u8 = 1;
+
+    pub fn new(id: u32) -> Self {
+        VarpType {
+            id,
+            debugname: None,
+            clientcode: 0,
+            scope: Self::SCOPE_TEMP,
+            type_: 0,
+            protect: true,
+            transmit: false,
+        }
+    }
+
+    /// Decode every varp, returns how many were loaded.
+    pub fn load() -> usize {
+        Self::load_from(&ServerConfig::get().paths.data.join("pack/server"))
+    }
+
+    /// The client cache only carries what the client needs, the scope, protect and transmit
+    /// flags come from `varp.dat` in `server_pack` and are decoded on top.
+    pub(crate) fn load_from(server_pack: &Path) -> usize {
+        let mut varps: Vec<VarpType> = Vec::new();
+        let result = read_config_files(CONFIG, Some(VAR_PLAYER), |_, file_id, data| {
+            Self::decode_into(&mut varps, file_id as usize, &mut Packet::from(data));
+        });
+        if let Err(e) = result {
+            error!("Failed to read varp types from the cache: {}", e);
+        }
+
+        let result = read_server_configs_in(server_pack, "varp", |id, packet| {
+            Self::decode_into(&mut varps, id as usize, packet);
+        });
+        if let Err(e) = result {
+            error!("Failed to read server varp types: {}", e);
+        }
+
+        let count = varps.len();
+        if VARP_TYPES.set(varps).is_err() {
+            error!("Varp types were already loaded");
+        }
+        debug!("Loaded {} varp types", count);
+        count
+    }
+
+    fn decode_into(varps: &mut Vec<VarpType>, id: usize, packet: &mut Packet) {
+        if varps.len() <= id {
+            varps.resize_with(id + 1, || VarpType::new(u32::MAX));
+        }
+        if varps[id].id == u32::MAX {
+            varps[id] = VarpType::new(id as u32);
+        }
+        varps[id].decode_type(packet, &mut Vec::new());
+    }
+
+    #[inline]
+    pub fn get(id: i32) -> Option<&'static VarpType> {
+        VARP_TYPES.get()?.get(usize::try_from(id).ok()?).filter(|varp| varp.id != u32::MAX)
+    }
+
+    /// Size of the varp table, every player gets this many varps.
+    #[inline]
+    pub fn count() -> usize {
+        VARP_TYPES.get().map_or(0, Vec::len)
+    }
+
+    #[inline]
+    pub fn is_perm(&self) -> bool {
+        self.scope == Self::SCOPE_PERM
+    }
+}
+
+impl ConfigType for VarpType {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn debugname(&self) -> Option<&String> {
+        self.debugname.as_ref()
+    }
+
+    fn set_debugname(&mut self, debugname: String) {
+        self.debugname = Some(debugname);
+    }
+
+    fn decode(&mut self, opcode: u8, packet: &mut Packet) {
+        match opcode {
+            // Server-side opcodes, only present in the server pack.
+            1 => self.scope = packet.g1(),
+            2 => self.type_ = packet.g1(),
+            4 => self.protect = false,
+            5 => self.clientcode = packet.g2(),
+            6 => self.transmit = true,
+            250 => self.set_debugname(packet.gjstr()),
+            _ => error!("Unrecognized varp config code: {}", opcode),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+use crate::entity::player::Player;
+use crate::entity::player_save::PlayerSave;
+use crate::entity::player_type::PlayerType;
+use crate::grid::coord_grid::CoordGrid;
+use crate::io::client_socket::ClientSocket;
+use crate::io::packet::Packet;
+use crate::io::server::model::varp_small::VarpSmall;
+use crate::io::server::outgoing_message::OutgoingMessageEnum;
+use crate::util::cache::config::config_type::ConfigType;
+use crate::util::cache::config::varp_type::VarpType;
+
+fn decode(data: Vec<u8>) -> VarpType {
+    let mut varp = VarpType::new(281);
+    varp.decode_type(&mut Packet::from(data), &mut Vec::new());
+    varp
+}
+
+#[test]
+fn test_decode_defaults() {
+    let varp = decode(vec![0]);
+    assert_eq!(varp.scope, VarpType::SCOPE_TEMP);
+    assert_eq!(varp.type_, 0);
+    assert_eq!(varp.clientcode, 0);
+    assert!(varp.protect);
+    assert!(!varp.transmit);
+    assert!(!varp.is_perm());
+}
+
+#[test]
+fn test_decode() {
+    let mut data = vec![1, VarpType::SCOPE_PERM, 2, 3, 4, 5, 0x01, 0x02, 6, 250];
+    data.extend_from_slice(b"quest_points\0");
+    data.push(0);
+    let varp = decode(data);
+
+    assert_eq!(varp.id, 281);
+    assert!(varp.is_perm());
+    assert_eq!(varp.type_, 3);
+    assert!(!varp.protect);
+    assert_eq!(varp.clientcode, 0x0102);
+    assert!(varp.transmit);
+    assert_eq!(varp.debugname().map(String::as_str), Some("quest_points"));
+}
+
+// The only test that loads the varp table, it is set once for the whole process.
+#[tokio::test]
+async fn test_load_server_flags() {
+    let dir = std::env::temp_dir().join(format!("varp_pack_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut data = 3u16.to_be_bytes().to_vec();
+    data.push(0);
+    data.extend_from_slice(&[1, VarpType::SCOPE_PERM, 6, 0]);
+    data.extend_from_slice(&[4, 6, 0]);
+    std::fs::write(dir.join("varp.dat"), data).unwrap();
+
+    VarpType::load_from(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let temp = VarpType::get(0).unwrap();
+    assert!(!temp.is_perm() && temp.protect && !temp.transmit);
+    let perm = VarpType::get(1).unwrap();
+    assert!(perm.is_perm() && perm.protect && perm.transmit);
+    let open = VarpType::get(2).unwrap();
+    assert!(!open.is_perm() && !open.protect && open.transmit);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let _client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+    let (server, _) = listener.accept().await.unwrap();
+    let mut player = Player::new_dummy(CoordGrid::from(3222, 0, 3218), 0, 1);
+    player.player_type = PlayerType::ClientBound;
+    player.client.socket = Some(ClientSocket::spawn(server));
+
+    player.set_var(0, 5);
+    player.set_var(1, 7);
+    player.set_var(2, 9);
+    assert_eq!(player.outgoing_messages, vec![
+        OutgoingMessageEnum::VarpSmall(VarpSmall::new(1, 7)),
+        OutgoingMessageEnum::VarpSmall(VarpSmall::new(2, 9)),
+    ]);
+    assert_eq!(PlayerSave::from_player(&player).varps, vec![(1, 7)]);
+}
@@ -1,3 +1,3 @@
 pub mod obj_unpacker;
-mod config;
+pub mod config;
 pub mod param_helper;
\ No newline at end of file
@@ -1,5 +1,6 @@
 use std::fs::File;
 use log::{debug};
+use config::server_config::ServerConfig;
 use rs2cache::Cache;
 use rs2cache::js5_compression::Js5Compression;
 use rs2cache::js5_index::Js5Index;
@@ -9,15 +10,15 @@ use crate::util::cache::config::config_type::ConfigType;
 use crate::util::cache::config::obj_type::{write_obj, ObjType};
 
 pub fn unpack_objs() {
-    let cache_path = "../../src/cacheLocal";
+    let cache_path = ServerConfig::get().paths.cache.to_string_lossy();
 
-    let mut cache = Cache::open(cache_path).unwrap();
+    let mut cache = Cache::open(&cache_path).unwrap();
     let archive_id = CONFIG_OBJ;
     let js5_index_compressed = cache.store.read(255, archive_id).unwrap();
     let js5_index_decompressed = Js5Compression::uncompress(js5_index_compressed, None).unwrap();
     let js5_index = Js5Index::read(js5_index_decompressed).unwrap();
 
-    let mut file = File::create("data/src/scripts/_unpack/all.obj").unwrap();
+    let mut file = File::create(ServerConfig::get().paths.data.join("src/scripts/_unpack/all.obj")).unwrap();
     
     let mut obj_count = 0;
     for i in 0..js5_index.groups.len() {
@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use lazy_static::lazy_static;
 use log::debug;
+use config::server_config::ServerConfig;
 use crate::util::parse::{load_dir_ext_full, load_file};
 
 #[derive(Clone)]
@@ -39,8 +40,7 @@ impl PackFile {
             let args_clone = self.validator_args.clone();
             validator(self, &args_clone);
         } else {
-            // TODO - environment specification goes here.
-            let path = format!("./data/src/pack/{}.pack", self.type_name);
+            let path = format!("{}/pack/{}.pack", src_dir(), self.type_name);
             self.load(&path);
         }
     }
@@ -90,7 +90,7 @@ impl PackFile {
             .join("\n") + "\n";
         
         fs::write(
-            format!("{}/pack/{}.pack", "./data/src", self.type_name),
+            format!("{}/pack/{}.pack", src_dir(), self.type_name),
             content,
         ).expect("Unable to write pack file");
     }
@@ -117,7 +117,7 @@ impl PackFile {
 
 fn regenerate_script_pack(pack: &mut PackFile, _args: &[ValidatorArg]) {
     debug!("regenerate_script_pack");
-    let path = format!("{}/pack/script.pack", "./data/src");
+    let path = format!("{}/pack/script.pack", src_dir());
     pack.load(&path);
     
     let names = crawl_config_names(".rs2", true);
@@ -141,17 +141,23 @@ lazy_static! {
     };
 }
 
+/// Script and config sources, under the configured data directory.
+fn src_dir() -> String {
+    ServerConfig::get().paths.data.join("src").to_string_lossy().into_owned()
+}
+
 pub fn revalidate_pack() {
     SCRIPT_PACK.lock().unwrap().reload();
 }
 
 pub fn crawl_config_names(ext: &str, include_brackets: bool) -> Vec<String> {
     let mut names: Vec<String> = Vec::new();
-    let script_path = format!("{}/scripts", "./data/src");
+    let src_dir = src_dir();
+    let script_path = format!("{}/scripts", src_dir);
 
     load_dir_ext_full(&script_path, ext, |lines, file| {
         // Skip engine.rs2 file
-        if file == format!("{}/scripts/engine.rs2", "./data/src") {
+        if file == format!("{}/scripts/engine.rs2", src_dir) {
             return;
         }
 
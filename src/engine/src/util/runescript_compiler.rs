@@ -1,41 +1,32 @@
 use std::error::Error;
 use std::fs;
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
-use log::{debug, info};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use log::{error, info, warn};
 use reqwest::blocking;
 use sha2::Digest;
+use config::server_config::{CompilerConfig, ServerConfig};
 use crate::script::script_provider::ScriptProvider;
 
+/// Make sure the compiler jar is there and the expected build.
+///
+/// Offline, the vendored jar has to match the pinned SHA-256 and nothing is downloaded.
+/// Otherwise the jar is fetched from the release for [ScriptProvider::COMPILER_VERSION] when it is missing or outdated.
 pub fn update_compiler() -> Result<bool, Box<dyn Error>> {
-    info!("Checking for compiler update.");
-    let mut needs_update = false;
-
-    if !Path::new("./RuneScriptCompiler.jar").exists() {
-       needs_update = true;
-    } else {
-        let mut file = File::open("./RuneScriptCompiler.jar")?;
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)?;
-
-        let mut hasher = sha2::Sha256::new();
-        hasher.update(&buffer);
-        let shasum = format!("{:x}", hasher.finalize());
-
-        let sha_url = format!(
-            "https://github.com/LostCityRS/RuneScriptKt/releases/download/{}/RuneScriptCompiler.jar.sha256",
-            ScriptProvider::COMPILER_VERSION
-        );
-
-        let sha_response = blocking::get(&sha_url)?;
-        let expected = sha_response.text()?[..64].to_string();
+    let compiler = &ServerConfig::get().compiler;
 
-        if shasum != expected {
-            needs_update = true;
-        }
+    if compiler.offline {
+        return verify_compiler(compiler).map(|_| true);
     }
 
+    info!("Checking for compiler update.");
+    let needs_update = match fs::read(&compiler.jar) {
+        Ok(jar) => sha256(&jar) != expected_sha256(compiler)?,
+        Err(_) => true,
+    };
+
     if needs_update {
         info!("Updating compiler.");
         let jar_url = format!(
@@ -46,9 +37,112 @@ pub fn update_compiler() -> Result<bool, Box<dyn Error>> {
         let jar_response = blocking::get(&jar_url)?;
         let jar_bytes = jar_response.bytes()?;
 
-        fs::write("RuneScriptCompiler.jar", jar_bytes)?;
+        if !compiler.sha256.is_empty() && sha256(&jar_bytes) != compiler.sha256.to_lowercase() {
+            return Err(format!("downloaded compiler does not match the pinned sha256 {}", compiler.sha256).into());
+        }
+
+        fs::write(&compiler.jar, jar_bytes)?;
     }
 
     info!("Compiler is up to date.");
     Ok(true)
-}
\ No newline at end of file
+}
+
+/// Check the vendored jar against the pinned hash without touching the network.
+fn verify_compiler(compiler: &CompilerConfig) -> Result<(), Box<dyn Error>> {
+    let jar = fs::read(&compiler.jar)
+        .map_err(|e| format!("offline compiler {} could not be read: {}", compiler.jar.display(), e))?;
+
+    let actual = sha256(&jar);
+    if actual != compiler.sha256.to_lowercase() {
+        return Err(format!("offline compiler {} has sha256 {}, expected {}", compiler.jar.display(), actual, compiler.sha256).into());
+    }
+
+    info!("Using offline compiler {}.", compiler.jar.display());
+    Ok(())
+}
+
+fn expected_sha256(compiler: &CompilerConfig) -> Result<String, Box<dyn Error>> {
+    if !compiler.sha256.is_empty() {
+        return Ok(compiler.sha256.to_lowercase());
+    }
+
+    let sha_url = format!(
+        "https://github.com/LostCityRS/RuneScriptKt/releases/download/{}/RuneScriptCompiler.jar.sha256",
+        ScriptProvider::COMPILER_VERSION
+    );
+
+    let sha_response = blocking::get(&sha_url)?;
+    Ok(sha_response.text()?[..64].to_string())
+}
+
+#[inline]
+fn sha256(data: &[u8]) -> String {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Run the compiler over the project described by `neptune.toml`, logging whatever it prints.
+///
+/// Compile errors come back as an error so they show up before [ScriptProvider::load] picks up the last good pack.
+pub fn compile_scripts() -> Result<(), Box<dyn Error>> {
+    let compiler = &ServerConfig::get().compiler;
+    if compiler.offline {
+        verify_compiler(compiler)?;
+    }
+
+    let jar = fs::canonicalize(&compiler.jar)
+        .map_err(|e| format!("compiler {} not found: {}", compiler.jar.display(), e))?;
+    if !compiler.neptune.is_file() {
+        return Err(format!("compiler project {} not found", compiler.neptune.display()).into());
+    }
+
+    // The compiler reads neptune.toml from its working directory.
+    let project_dir = compiler.neptune.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+
+    info!("Compiling scripts with {}.", compiler.neptune.display());
+    let mut child = Command::new(&compiler.java)
+        .arg("-jar")
+        .arg(&jar)
+        .current_dir(&project_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run {}: {}", compiler.java, e))?;
+
+    // Drain stderr on its own thread so a chatty compiler can't fill one pipe while we block on the other.
+    let stderr = child.stderr.take().map(|stderr| thread::spawn(move || {
+        let mut errors = 0;
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            error!("[compiler] {}", line);
+            errors += 1;
+        }
+        errors
+    }));
+
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if line.to_lowercase().contains("error") {
+                error!("[compiler] {}", line);
+            } else {
+                info!("[compiler] {}", line);
+            }
+        }
+    }
+
+    let status = child.wait()?;
+    let stderr_lines = stderr.map_or(0, |handle| handle.join().unwrap_or(0));
+
+    if !status.success() {
+        return Err(format!("script compilation failed ({})", status).into());
+    }
+    if stderr_lines > 0 {
+        warn!("Compiler finished with {} lines of diagnostics.", stderr_lines);
+    }
+
+    info!("Scripts compiled.");
+    Ok(())
+}
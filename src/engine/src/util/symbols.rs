@@ -1,13 +1,15 @@
 use std::fs;
+use config::server_config::ServerConfig;
 use strum::IntoEnumIterator;
 use crate::script::script_opcode::ScriptOpcode;
 use crate::script::script_opcode_pointers::initialize_script_opcode_pointers;
 use crate::util::namemap::load_pack;
 
 pub fn generate_server_symbols() {
-    fs::create_dir_all("./data/symbols").expect("Failed to create symbols directory");
+    let data = &ServerConfig::get().paths.data;
+    fs::create_dir_all(data.join("symbols")).expect("Failed to create symbols directory");
     
-    let scripts = load_pack("./data/src/pack/script.pack");
+    let scripts = load_pack(&data.join("src/pack/script.pack").to_string_lossy());
     let mut script_symbols = String::new();
     for (i, script) in scripts.iter().enumerate() {
         if !script.is_empty() {
@@ -15,7 +17,7 @@ pub fn generate_server_symbols() {
         }
     }
     
-    fs::write("./data/symbols/runescript.sym", script_symbols).expect("Failed to write to RuneScript symbols file");
+    fs::write(data.join("symbols/runescript.sym"), script_symbols).expect("Failed to write to RuneScript symbols file");
     
     let mut command_symbols = String::new();
     
@@ -34,5 +36,5 @@ pub fn generate_server_symbols() {
         command_symbols.push_str(&line);
     }
     // TODO - Disabled for now, waiting on fix to compiler to handle no-pointer setups.
-    //fs::write("./data/symbols/commands.sym", command_symbols).expect("Failed to write to command symbols file");
+    //fs::write(data.join("symbols/commands.sym"), command_symbols).expect("Failed to write to command symbols file");
 }
\ No newline at end of file
@@ -3,7 +3,7 @@ mod js5_request;
 use std::error::Error;
 use cache::file_handler::ensure_initialized;
 use constants::js5_out::js5_out;
-use constants::server_addresses::server_addresses::JS5_ADDR;
+use config::server_config::ServerConfig;
 use engine::io::client_state::ConnectionState;
 use engine::io::connection::{try_write_packet, Connection};
 use log::{debug, error, info};
@@ -12,7 +12,7 @@ use constants::js5_in::js5_in;
 use crate::js5_request::Js5Request;
 
 async fn run_js5_server() -> Result<(), Box<dyn Error>> {
-    let listener = TcpListener::bind(JS5_ADDR).await?;
+    let listener = TcpListener::bind(&ServerConfig::get().network.js5_addr).await?;
 
     debug!("Initializing cache in main thread");
     if let Err(e) = ensure_initialized() {
@@ -166,9 +166,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
     env_logger::init();
 
+    let config = match ServerConfig::init() {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Failed to load configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     info!("Starting JS5 System");
     info!("---------------------------------------------");
-    info!("Starting JS5 server: {}", JS5_ADDR);
+    info!("Starting JS5 server: {}", config.network.js5_addr);
     info!("---------------------------------------------");
 
     tokio::select! {
@@ -1,7 +1,7 @@
 use std::error::Error;
 use std::time::Duration;
 use constants::proxy::proxy::{BUFFER_SIZE, READ_TIMEOUT_MS};
-use constants::server_addresses::server_addresses::{JS5_ADDR, WORLDLIST_ADDR, PROXY_ADDR};
+use config::server_config::ServerConfig;
 use constants::title_protocol::title_protocol;
 use engine::io::connection::{try_write_packet, Connection};
 use engine::io::packet::Packet;
@@ -28,12 +28,12 @@ fn choose_backend_and_consume(packet: &mut Packet) -> Destination {
     
     match opcode {
         title_protocol::INIT_JS5REMOTE_CONNECTION => {
-            debug!("Routing to JS5_ADDR: {}", JS5_ADDR);
+            debug!("Routing to JS5: {}", ServerConfig::get().network.js5_addr);
             Destination::JS5
         }
 
         title_protocol::REQUEST_WORLDLIST => {
-            debug!("Routing to WORLDLIST_ADDR: {}", WORLDLIST_ADDR);
+            debug!("Routing to worldlist: {}", ServerConfig::get().network.worldlist_addr);
             Destination::WorldList
         }
         
@@ -51,8 +51,8 @@ fn choose_backend_and_consume(packet: &mut Packet) -> Destination {
 /// Helper function to get the address for a destination
 fn get_address(destination: &Destination) -> &str {
     match destination {
-        Destination::JS5 => JS5_ADDR,
-        Destination::WorldList => WORLDLIST_ADDR,
+        Destination::JS5 => &ServerConfig::get().network.js5_addr,
+        Destination::WorldList => &ServerConfig::get().network.worldlist_addr,
         Destination::WorldSuitability => "world_suitability",
         Destination::Terminate => unreachable!(), // This should never be called
     }
@@ -206,7 +206,7 @@ async fn handle_proxy_client(client_stream: TcpStream) -> Result<(), Box<dyn Err
 }
 
 async fn run_proxy_server() -> Result<(), Box<dyn Error>> {
-    let listener = TcpListener::bind(PROXY_ADDR).await?;
+    let listener = TcpListener::bind(&ServerConfig::get().network.proxy_addr).await?;
 
     loop {
         match listener.accept().await {
@@ -233,9 +233,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
     env_logger::init();
 
+    let config = match ServerConfig::init() {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Failed to load configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     info!("Starting TCP Proxy System");
     info!("---------------------------------------------");
-    info!("Starting proxy server: {}", PROXY_ADDR);
+    info!("Starting proxy server: {}", config.network.proxy_addr);
     info!("---------------------------------------------");
 
     tokio::select! {
@@ -3,7 +3,7 @@ mod countries;
 use std::error::Error;
 use tokio::net::{TcpListener, TcpStream};
 use log::{debug, error, info};
-use constants::server_addresses::server_addresses::WORLDLIST_ADDR;
+use config::server_config::ServerConfig;
 use engine::io::connection::{try_write_packet, Connection};
 use engine::io::packet::Packet;
 use countries::COUNTRY_MAP;
@@ -48,7 +48,7 @@ async fn handle_worldlist_client(stream: TcpStream) -> Result<(), Box<dyn Error>
                     response.p1(0);
                     response.p4(0);
                     response.pjstr2("");
-                    response.pjstr2("localhost");
+                    response.pjstr2(&ServerConfig::get().world.host);
 
                     // Default value
                     response.p4(1);
@@ -78,7 +78,7 @@ async fn handle_worldlist_client(stream: TcpStream) -> Result<(), Box<dyn Error>
 }
 
 async fn run_worldlist_server() -> Result<(), Box<dyn Error>> {
-    let listener = TcpListener::bind(WORLDLIST_ADDR).await?;
+    let listener = TcpListener::bind(&ServerConfig::get().network.worldlist_addr).await?;
 
     loop {
         match listener.accept().await {
@@ -105,9 +105,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
     env_logger::init();
 
+    let config = match ServerConfig::init() {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Failed to load configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     info!("Starting Worldlist System");
     info!("---------------------------------------------");
-    info!("Starting Worldlist server: {}", WORLDLIST_ADDR);
+    info!("Starting Worldlist server: {}", config.network.worldlist_addr);
     info!("---------------------------------------------");
 
     tokio::select! {